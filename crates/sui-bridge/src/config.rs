@@ -435,11 +435,81 @@ pub struct BridgeClientConfig {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct BridgeCommitteeConfig {
-    pub bridge_authority_port_and_key_path: Vec<(u64, PathBuf)>,
+    pub bridge_authority_port_and_key_path: Vec<BridgeAuthorityEntry>,
 }
 
 impl Config for BridgeCommitteeConfig {}
 
+/// A bridge authority to register, either as a `[port, key-path]` pair, for a bridge node
+/// listening on `http://127.0.0.1:{port}`, or with its host and URL scheme given explicitly.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum BridgeAuthorityEntry {
+    PortAndKeyPath(u64, PathBuf),
+    #[serde(rename_all = "kebab-case")]
+    Url {
+        port: u64,
+        key_path: PathBuf,
+        /// Defaults to `127.0.0.1`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        host: Option<String>,
+        /// Defaults to `http`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scheme: Option<String>,
+    },
+}
+
+impl BridgeAuthorityEntry {
+    pub fn port(&self) -> u64 {
+        match self {
+            BridgeAuthorityEntry::PortAndKeyPath(port, _)
+            | BridgeAuthorityEntry::Url { port, .. } => *port,
+        }
+    }
+
+    pub fn key_path(&self) -> &PathBuf {
+        match self {
+            BridgeAuthorityEntry::PortAndKeyPath(_, key_path)
+            | BridgeAuthorityEntry::Url { key_path, .. } => key_path,
+        }
+    }
+
+    /// The URL that the bridge authority is registered with, which must be an `http` or `https`
+    /// URL.
+    pub fn url(&self) -> anyhow::Result<String> {
+        let (host, scheme) = match self {
+            BridgeAuthorityEntry::PortAndKeyPath(..) => (None, None),
+            BridgeAuthorityEntry::Url { host, scheme, .. } => (host.as_deref(), scheme.as_deref()),
+        };
+        let url = format!(
+            "{}://{}:{}",
+            scheme.unwrap_or("http"),
+            host.unwrap_or("127.0.0.1"),
+            self.port()
+        );
+
+        let parsed = url::Url::parse(&url)
+            .map_err(|e| anyhow!("Invalid bridge authority URL {url}: {e}"))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(anyhow!(
+                "Invalid bridge authority URL {url}: the scheme must be http or https"
+            ));
+        }
+        // Anything but a host in `host` (such as a path or credentials) ends up elsewhere in the
+        // URL.
+        let port = u16::try_from(self.port()).ok();
+        if parsed.port_or_known_default() != port
+            || !parsed.username().is_empty()
+            || parsed.path() != "/"
+            || parsed.query().is_some()
+            || parsed.fragment().is_some()
+        {
+            return Err(anyhow!("Invalid bridge authority URL {url}"));
+        }
+        Ok(url)
+    }
+}
+
 pub async fn pick_highest_balance_coin(
     coin_read_api: &CoinReadApi,
     address: SuiAddress,
@@ -492,3 +562,45 @@ pub struct EthContractAddresses {
     pub bridge_limiter: EthAddress,
     pub bridge_vault: EthAddress,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bridge_authority_url() {
+        let config: BridgeCommitteeConfig = serde_json::from_value(serde_json::json!({
+            "bridge-authority-port-and-key-path": [
+                [9191, "/keys/a.key"],
+                {
+                    "port": 443,
+                    "key-path": "/keys/b.key",
+                    "host": "bridge.example.com",
+                    "scheme": "https",
+                },
+                { "port": 9193, "key-path": "/keys/c.key", "host": "10.0.0.3" },
+            ]
+        }))
+        .unwrap();
+        let entries = &config.bridge_authority_port_and_key_path;
+        assert_eq!(entries[0].url().unwrap(), "http://127.0.0.1:9191");
+        assert_eq!(entries[1].url().unwrap(), "https://bridge.example.com:443");
+        assert_eq!(entries[2].url().unwrap(), "http://10.0.0.3:9193");
+        assert_eq!(entries[1].key_path(), &PathBuf::from("/keys/b.key"));
+
+        let url = |host: &str, scheme: &str, port: u64| {
+            BridgeAuthorityEntry::Url {
+                port,
+                key_path: PathBuf::from("/keys/a.key"),
+                host: Some(host.to_string()),
+                scheme: Some(scheme.to_string()),
+            }
+            .url()
+        };
+        assert!(url("bridge.example.com", "ftp", 21).is_err());
+        assert!(url("bridge.example.com/path", "https", 443).is_err());
+        assert!(url("user@bridge.example.com", "https", 443).is_err());
+        assert!(url("bridge example", "https", 443).is_err());
+        assert!(url("bridge.example.com", "https", 70000).is_err());
+    }
+}
@@ -74,6 +74,10 @@ pub fn validator_config_file(address: Multiaddr, i: usize) -> String {
     multiaddr_to_filename(address).unwrap_or(format!("validator-config-{}.yaml", i))
 }
 
+pub fn named_validator_config_file(name: &str) -> String {
+    format!("validator-config-{}.yaml", name)
+}
+
 pub fn ssfn_config_file(address: Multiaddr, i: usize) -> String {
     multiaddr_to_filename(address).unwrap_or(format!("ssfn-config-{}.yaml", i))
 }
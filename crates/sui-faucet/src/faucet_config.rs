@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use clap::Parser;
-use std::net::Ipv4Addr;
+use std::net::{IpAddr, Ipv4Addr};
 
 pub const DEFAULT_AMOUNT: u64 = 200_000_000_000;
 pub const DEFAULT_NUM_COINS: usize = 5;
@@ -18,7 +18,7 @@ pub struct FaucetConfig {
     pub port: u16,
 
     #[clap(long, default_value = "127.0.0.1")]
-    pub host_ip: Ipv4Addr,
+    pub host_ip: IpAddr,
 
     /// Amount of SUI per each dispensed coin.
     #[clap(long, default_value_t = DEFAULT_AMOUNT)]
@@ -35,7 +35,7 @@ impl Default for FaucetConfig {
     fn default() -> Self {
         Self {
             port: 5003,
-            host_ip: Ipv4Addr::new(127, 0, 0, 1),
+            host_ip: IpAddr::V4(Ipv4Addr::LOCALHOST),
             amount: DEFAULT_AMOUNT,
             num_coins: DEFAULT_NUM_COINS,
             wallet_client_timeout_secs: 60,
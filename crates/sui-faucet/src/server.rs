@@ -11,13 +11,7 @@ use axum::{
     BoxError, Extension, Json, Router,
 };
 use http::Method;
-use std::{
-    borrow::Cow,
-    net::{IpAddr, SocketAddr},
-    path::PathBuf,
-    sync::Arc,
-    time::Duration,
-};
+use std::{borrow::Cow, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
 use sui_config::SUI_CLIENT_CONFIG;
 use sui_sdk::wallet_context::WalletContext;
 use tower::ServiceBuilder;
@@ -123,7 +117,7 @@ pub async fn start_faucet(app_state: Arc<AppState>) -> Result<(), anyhow::Error>
                 .into_inner(),
         );
 
-    let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
+    let addr = SocketAddr::new(host_ip, port);
     info!("listening on {}", addr);
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(
@@ -203,4 +197,52 @@ mod tests {
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
         handle.abort();
     }
+
+    #[tokio::test]
+    async fn test_ipv6_host() {
+        // Reserve a free port on the IPv6 loopback, skipping the test on hosts without one.
+        let port = match std::net::TcpListener::bind("[::1]:0") {
+            Ok(listener) => listener.local_addr().unwrap().port(),
+            Err(e) => {
+                eprintln!("Skipping test_ipv6_host, IPv6 loopback is unavailable: {e}");
+                return;
+            }
+        };
+        let cluster = TestClusterBuilder::new().build().await;
+        let config = FaucetConfig {
+            host_ip: "::1".parse().unwrap(),
+            port,
+            ..Default::default()
+        };
+        let local_faucet = LocalFaucet::new(cluster.wallet, config.clone())
+            .await
+            .unwrap();
+
+        let app_state = Arc::new(AppState {
+            faucet: local_faucet,
+            config,
+        });
+
+        let handle = tokio::spawn(async move {
+            start_faucet(app_state)
+                .await
+                .expect("Failed to start faucet");
+        });
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        let recipient = SuiAddress::random_for_testing_only();
+        let req = FaucetRequest::new_fixed_amount_request(recipient);
+        let response = reqwest::Client::new()
+            .post(format!("http://[::1]:{port}/v2/gas"))
+            .json(&req)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let faucet_response = response.json::<FaucetResponse>().await.unwrap();
+        assert!(faucet_response.coins_sent.is_some());
+        handle.abort();
+    }
 }
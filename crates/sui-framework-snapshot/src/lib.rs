@@ -3,7 +3,11 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
-use std::{fs, io::Read, path::PathBuf};
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
 use sui_framework::{SystemPackage, SystemPackageMetadata};
 use sui_protocol_config::ProtocolVersion;
 use sui_types::base_types::ObjectID;
@@ -102,26 +106,39 @@ pub fn update_bytecode_snapshot_manifest(
 
 pub fn load_bytecode_snapshot(protocol_version: u64) -> anyhow::Result<Vec<SystemPackage>> {
     let snapshot_path = snapshot_path_for_version(protocol_version)?;
-    let mut snapshots: BTreeMap<ObjectID, SystemPackage> = fs::read_dir(&snapshot_path)?
+    load_bytecode_snapshot_from_dir(&snapshot_path)
+}
+
+/// Load the system packages in `snapshot_path`, which is laid out like a directory of the bytecode
+/// snapshot: one BCS-encoded [SystemPackage] per file. Packages are returned in the order they
+/// need to be published in, and files that do not hold a system package are an error.
+pub fn load_bytecode_snapshot_from_dir(snapshot_path: &Path) -> anyhow::Result<Vec<SystemPackage>> {
+    let mut snapshots: BTreeMap<ObjectID, SystemPackage> = fs::read_dir(snapshot_path)?
         .flatten()
         .map(|entry| {
-            let file_name = entry.file_name().to_str().unwrap().to_string();
-            let mut file = fs::File::open(snapshot_path.clone().join(file_name))?;
+            let mut file = fs::File::open(entry.path())?;
             let mut buffer = Vec::new();
             file.read_to_end(&mut buffer)?;
-            let package: SystemPackage = bcs::from_bytes(&buffer)?;
+            let package: SystemPackage = bcs::from_bytes(&buffer).map_err(|err| {
+                anyhow::anyhow!("{} is not a system package: {err}", entry.path().display())
+            })?;
             Ok((package.id, package))
         })
         .collect::<anyhow::Result<_>>()?;
 
     // system packages need to be restored in a specific order
-    assert!(snapshots.len() <= SYSTEM_PACKAGE_PUBLISH_ORDER.len());
     let mut snapshot_objects = Vec::new();
     for package_id in SYSTEM_PACKAGE_PUBLISH_ORDER {
         if let Some(object) = snapshots.remove(package_id) {
             snapshot_objects.push(object);
         }
     }
+    if let Some(package_id) = snapshots.keys().next() {
+        anyhow::bail!(
+            "{} holds package {package_id}, which is not a system package",
+            snapshot_path.display()
+        );
+    }
     Ok(snapshot_objects)
 }
 
@@ -90,10 +90,10 @@ pub fn validate_path(
                         {
                             Ok(p)
                         } else {
-                            Err(SuiError::SignatureKeyGenError("Invalid path".to_string()))
+                            Err(invalid_path(key_scheme, &p))
                         }
                     } else {
-                        Err(SuiError::SignatureKeyGenError("Invalid path".to_string()))
+                        Err(invalid_path(key_scheme, &p))
                     }
                 }
                 None => Ok(format!(
@@ -118,10 +118,10 @@ pub fn validate_path(
                         {
                             Ok(p)
                         } else {
-                            Err(SuiError::SignatureKeyGenError("Invalid path".to_string()))
+                            Err(invalid_path(key_scheme, &p))
                         }
                     } else {
-                        Err(SuiError::SignatureKeyGenError("Invalid path".to_string()))
+                        Err(invalid_path(key_scheme, &p))
                     }
                 }
                 None => Ok(format!(
@@ -146,10 +146,10 @@ pub fn validate_path(
                         {
                             Ok(p)
                         } else {
-                            Err(SuiError::SignatureKeyGenError("Invalid path".to_string()))
+                            Err(invalid_path(key_scheme, &p))
                         }
                     } else {
-                        Err(SuiError::SignatureKeyGenError("Invalid path".to_string()))
+                        Err(invalid_path(key_scheme, &p))
                     }
                 }
                 None => Ok(format!(
@@ -168,6 +168,25 @@ pub fn validate_path(
     }
 }
 
+/// An error for a derivation path `path` that `validate_path` rejects for `key_scheme`, which
+/// spells out the paths that it accepts.
+fn invalid_path(key_scheme: &SignatureScheme, path: &DerivationPath) -> SuiError {
+    let expected = match key_scheme {
+        SignatureScheme::ED25519 => format!(
+            "m/{DERVIATION_PATH_PURPOSE_ED25519}'/{DERIVATION_PATH_COIN_TYPE}'/{{account}}'/{{change}}'/{{address}}', with every level hardened"
+        ),
+        SignatureScheme::Secp256k1 => format!(
+            "m/{DERVIATION_PATH_PURPOSE_SECP256K1}'/{DERIVATION_PATH_COIN_TYPE}'/{{account}}'/{{change}}/{{address}}, with only the first three levels hardened"
+        ),
+        _ => format!(
+            "m/{DERVIATION_PATH_PURPOSE_SECP256R1}'/{DERIVATION_PATH_COIN_TYPE}'/{{account}}'/{{change}}/{{address}}, with only the first three levels hardened"
+        ),
+    };
+    SuiError::SignatureKeyGenError(format!(
+        "Invalid derivation path {path} for {key_scheme}: expected {expected}"
+    ))
+}
+
 pub fn generate_new_key(
     key_scheme: SignatureScheme,
     derivation_path: Option<DerivationPath>,
@@ -3,10 +3,13 @@
 
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 use crate::random_names::{random_name, random_names};
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
 use anyhow::{anyhow, bail, ensure, Context};
 use bip32::DerivationPath;
 use bip39::{Language, Mnemonic, Seed};
-use rand::{rngs::StdRng, SeedableRng};
+use fastcrypto::encoding::{Base64, Encoding};
+use rand::{rngs::OsRng, rngs::StdRng, RngCore, SeedableRng};
 use regex::Regex;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use shared_crypto::intent::{Intent, IntentMessage};
@@ -124,7 +127,7 @@ pub trait AccountKeystore: Send + Sync {
                 self.add_key(alias, kp)?;
                 Ok(address)
             }
-            Err(e) => Err(anyhow!("error getting keypair {:?}", e)),
+            Err(e) => Err(anyhow!("Failed to derive key from mnemonic: {e}")),
         }
     }
 }
@@ -152,11 +155,128 @@ pub struct Alias {
     pub public_key_base64: String,
 }
 
+/// Environment variable that `FileBasedKeystore::new` reads the passphrase of an encrypted
+/// keystore from.
+pub const KEYSTORE_PASSPHRASE_ENV_VAR: &str = "SUI_KEYSTORE_PASSPHRASE";
+
 #[derive(Default)]
 pub struct FileBasedKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
     aliases: BTreeMap<SuiAddress, Alias>,
     path: Option<PathBuf>,
+    /// When set, the keystore file is saved encrypted with this passphrase.
+    passphrase: Option<String>,
+}
+
+/// The contents of a keystore file: either a JSON array of Base64-encoded `flag || privkey`
+/// strings, or an `EncryptedKeystore` holding that same array, encrypted.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeystoreFile {
+    Plaintext(Vec<String>),
+    Encrypted(EncryptedKeystore),
+}
+
+/// The at-rest format of an encrypted keystore file, a JSON object of the form:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "kdf": "scrypt",
+///   "logN": 15,
+///   "r": 8,
+///   "p": 1,
+///   "salt": "<Base64, 16 bytes>",
+///   "cipher": "aes-256-gcm",
+///   "nonce": "<Base64, 12 bytes>",
+///   "ciphertext": "<Base64>"
+/// }
+/// ```
+///
+/// The passphrase is stretched into a 256-bit key with scrypt, using the given parameters and
+/// salt, and the key is used to encrypt the plaintext keystore's JSON array with AES-256-GCM. A
+/// fresh salt and nonce are picked every time the keystore is saved. The aliases file only holds
+/// public keys, and is never encrypted.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptedKeystore {
+    version: u32,
+    kdf: String,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedKeystore {
+    const VERSION: u32 = 1;
+    const KDF: &'static str = "scrypt";
+    const CIPHER: &'static str = "aes-256-gcm";
+    const LOG_N: u8 = 15;
+    const R: u32 = 8;
+    const P: u32 = 1;
+
+    fn encrypt(passphrase: &str, plaintext: &[u8]) -> Result<Self, anyhow::Error> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let cipher = Self::cipher(passphrase, &salt, Self::LOG_N, Self::R, Self::P)?;
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| anyhow!("Cannot encrypt the keystore"))?;
+
+        Ok(Self {
+            version: Self::VERSION,
+            kdf: Self::KDF.to_string(),
+            log_n: Self::LOG_N,
+            r: Self::R,
+            p: Self::P,
+            salt: Base64::encode(salt),
+            cipher: Self::CIPHER.to_string(),
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        })
+    }
+
+    fn decrypt(&self, passphrase: &str) -> Result<Vec<u8>, anyhow::Error> {
+        ensure!(
+            self.version == Self::VERSION && self.kdf == Self::KDF && self.cipher == Self::CIPHER,
+            "Unsupported keystore encryption: version {}, {} with {}",
+            self.version,
+            self.kdf,
+            self.cipher,
+        );
+        let salt = Base64::decode(&self.salt).map_err(|e| anyhow!("Invalid salt: {e}"))?;
+        let nonce = Base64::decode(&self.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+        let ciphertext =
+            Base64::decode(&self.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+        ensure!(nonce.len() == 12, "Invalid nonce: expected 12 bytes");
+
+        let cipher = Self::cipher(passphrase, &salt, self.log_n, self.r, self.p)?;
+        cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| anyhow!("Incorrect passphrase"))
+    }
+
+    fn cipher(
+        passphrase: &str,
+        salt: &[u8],
+        log_n: u8,
+        r: u32,
+        p: u32,
+    ) -> Result<Aes256Gcm, anyhow::Error> {
+        let params = scrypt::Params::new(log_n, r, p)
+            .map_err(|e| anyhow!("Invalid scrypt parameters: {e}"))?;
+        let mut key = [0u8; 32];
+        scrypt::scrypt(passphrase.as_bytes(), salt, &params, &mut key)
+            .map_err(|e| anyhow!("Cannot derive the keystore key: {e}"))?;
+        Aes256Gcm::new_from_slice(&key).map_err(|e| anyhow!("Invalid keystore key: {e}"))
+    }
 }
 
 impl Serialize for FileBasedKeystore {
@@ -308,15 +428,48 @@ impl AccountKeystore for FileBasedKeystore {
 }
 
 impl FileBasedKeystore {
+    /// Open the keystore at `path`, or an empty one if there is no file there yet. An encrypted
+    /// keystore is decrypted with the passphrase in the `SUI_KEYSTORE_PASSPHRASE` environment
+    /// variable.
     pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let passphrase = std::env::var(KEYSTORE_PASSPHRASE_ENV_VAR).ok();
+        Self::new_with_passphrase(path, passphrase.as_deref())
+    }
+
+    /// Open the keystore at `path`, decrypting it with `passphrase` if it is encrypted. The
+    /// passphrase is ignored if the keystore is not encrypted.
+    pub fn new_with_passphrase(
+        path: &PathBuf,
+        passphrase: Option<&str>,
+    ) -> Result<Self, anyhow::Error> {
+        let mut encrypted_with = None;
         let keys = if path.exists() {
             let reader =
                 BufReader::new(File::open(path).with_context(|| {
                     format!("Cannot open the keystore file: {}", path.display())
                 })?);
-            let kp_strings: Vec<String> = serde_json::from_reader(reader).with_context(|| {
+            let file: KeystoreFile = serde_json::from_reader(reader).with_context(|| {
                 format!("Cannot deserialize the keystore file: {}", path.display(),)
             })?;
+            let kp_strings: Vec<String> = match file {
+                KeystoreFile::Plaintext(kp_strings) => kp_strings,
+                KeystoreFile::Encrypted(encrypted) => {
+                    let Some(passphrase) = passphrase else {
+                        bail!(
+                            "The keystore file {} is encrypted: set {KEYSTORE_PASSPHRASE_ENV_VAR} \
+                             to its passphrase",
+                            path.display()
+                        );
+                    };
+                    let plaintext = encrypted.decrypt(passphrase).with_context(|| {
+                        format!("Cannot decrypt the keystore file: {}", path.display())
+                    })?;
+                    encrypted_with = Some(passphrase.to_string());
+                    serde_json::from_slice(&plaintext).with_context(|| {
+                        format!("Cannot deserialize the keystore file: {}", path.display())
+                    })?
+                }
+            };
             kp_strings
                 .iter()
                 .map(|kpstr| {
@@ -395,9 +548,36 @@ impl FileBasedKeystore {
             keys,
             aliases,
             path: Some(path.to_path_buf()),
+            passphrase: encrypted_with,
         })
     }
 
+    /// Whether the keystore file at `path` exists and is encrypted.
+    pub fn is_encrypted_file(path: &Path) -> Result<bool, anyhow::Error> {
+        if !path.exists() {
+            return Ok(false);
+        }
+        let reader = BufReader::new(
+            File::open(path)
+                .with_context(|| format!("Cannot open the keystore file: {}", path.display()))?,
+        );
+        let file: KeystoreFile = serde_json::from_reader(reader)
+            .with_context(|| format!("Cannot deserialize the keystore file: {}", path.display()))?;
+        Ok(matches!(file, KeystoreFile::Encrypted(_)))
+    }
+
+    /// Whether the keystore is saved encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.passphrase.is_some()
+    }
+
+    /// Save the keystore encrypted with `passphrase`, replacing any previous passphrase, or
+    /// unencrypted if it is `None`. Later saves keep using the same passphrase.
+    pub fn set_passphrase(&mut self, passphrase: Option<String>) -> Result<(), anyhow::Error> {
+        self.passphrase = passphrase;
+        self.save_keystore()
+    }
+
     pub fn set_path(&mut self, path: &Path) {
         self.path = Some(path.to_path_buf());
     }
@@ -423,9 +603,11 @@ impl FileBasedKeystore {
     /// Keys saved as Base64 with 33 bytes `flag || privkey` ($BASE64_STR).
     /// To see Bech32 format encoding, use `sui keytool export $SUI_ADDRESS` where
     /// $SUI_ADDRESS can be found with `sui keytool list`. Or use `sui keytool convert $BASE64_STR`
+    ///
+    /// If the keystore has a passphrase, the keys are saved encrypted, as an `EncryptedKeystore`.
     pub fn save_keystore(&self) -> Result<(), anyhow::Error> {
         if let Some(path) = &self.path {
-            let store = serde_json::to_string_pretty(
+            let mut store = serde_json::to_string_pretty(
                 &self
                     .keys
                     .values()
@@ -433,6 +615,12 @@ impl FileBasedKeystore {
                     .collect::<Vec<_>>(),
             )
             .with_context(|| format!("Cannot serialize keystore to file: {}", path.display()))?;
+            if let Some(passphrase) = &self.passphrase {
+                let encrypted = EncryptedKeystore::encrypt(passphrase, store.as_bytes())?;
+                store = serde_json::to_string_pretty(&encrypted).with_context(|| {
+                    format!("Cannot serialize keystore to file: {}", path.display())
+                })?;
+            }
             fs::write(path, store)?;
         }
         Ok(())
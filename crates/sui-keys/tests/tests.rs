@@ -307,3 +307,47 @@ fn remove_key_test() {
     let aliases_content = fs::read_to_string(&aliases_path).unwrap();
     assert!(!aliases_content.contains("test_key"));
 }
+
+#[test]
+fn encrypted_keystore_test() {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = FileBasedKeystore::new_with_passphrase(&keystore_path, None).unwrap();
+    let (addr, _, _) = keystore
+        .generate_and_add_new_key(SignatureScheme::ED25519, None, None, None)
+        .unwrap();
+    let plaintext = fs::read_to_string(&keystore_path).unwrap();
+    assert!(!FileBasedKeystore::is_encrypted_file(&keystore_path).unwrap());
+
+    keystore
+        .set_passphrase(Some("correct horse".to_string()))
+        .unwrap();
+    assert!(FileBasedKeystore::is_encrypted_file(&keystore_path).unwrap());
+    let encrypted = fs::read_to_string(&keystore_path).unwrap();
+    let private_key: Vec<String> = serde_json::from_str(&plaintext).unwrap();
+    assert!(!encrypted.contains(&private_key[0]));
+
+    // Opening an encrypted keystore needs the right passphrase.
+    assert!(FileBasedKeystore::new_with_passphrase(&keystore_path, None).is_err());
+    assert!(FileBasedKeystore::new_with_passphrase(&keystore_path, Some("wrong")).is_err());
+    let mut keystore =
+        FileBasedKeystore::new_with_passphrase(&keystore_path, Some("correct horse")).unwrap();
+    assert!(keystore.is_encrypted());
+    assert_eq!(keystore.addresses(), vec![addr]);
+
+    // Keys added later are saved encrypted too.
+    let (addr2, _, _) = keystore
+        .generate_and_add_new_key(SignatureScheme::Secp256k1, None, None, None)
+        .unwrap();
+    assert!(FileBasedKeystore::is_encrypted_file(&keystore_path).unwrap());
+    let mut keystore =
+        FileBasedKeystore::new_with_passphrase(&keystore_path, Some("correct horse")).unwrap();
+    assert!(keystore.addresses().contains(&addr2));
+
+    // Decrypting goes back to the unencrypted format, which needs no passphrase.
+    keystore.set_passphrase(None).unwrap();
+    assert!(!FileBasedKeystore::is_encrypted_file(&keystore_path).unwrap());
+    let keystore = FileBasedKeystore::new_with_passphrase(&keystore_path, None).unwrap();
+    assert!(!keystore.is_encrypted());
+    assert_eq!(keystore.addresses().len(), 2);
+}
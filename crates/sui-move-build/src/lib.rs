@@ -33,7 +33,10 @@ use move_package::{
         build_plan::BuildPlan, compiled_package::CompiledPackage as MoveCompiledPackage,
     },
     package_hooks::{PackageHooks, PackageIdentifier},
-    resolution::{dependency_graph::DependencyGraph, resolution_graph::ResolvedGraph},
+    resolution::{
+        dependency_graph::{DependencyGraph, DependencyMode},
+        resolution_graph::ResolvedGraph,
+    },
     source_package::parsed_manifest::{
         Dependencies, Dependency, DependencyKind, GitInfo, InternalDependency, PackageName,
     },
@@ -454,6 +457,63 @@ impl CompiledPackage {
         )
     }
 
+    /// Return a digest of the bytecode modules of each published dependency, keyed by its storage
+    /// package ID. Each digest is computed like `get_package_digest`, from the dependency's own
+    /// modules and the storage package IDs of its own transitive dependencies, so it is equal to
+    /// `MovePackage::digest` of the package on-chain if the dependency resolves to the same
+    /// modules and linkage locally as it has on-chain.
+    pub fn get_dependency_digests(&self) -> BTreeMap<ObjectID, [u8; 32]> {
+        let published = &self.dependency_ids.published;
+        let hash_modules = true;
+        published
+            .iter()
+            .map(|(name, id)| {
+                let modules: Vec<Vec<u8>> = self
+                    .package
+                    .deps_compiled_units
+                    .iter()
+                    .filter(|(package, _)| package == name)
+                    .map(|(_, unit)| &unit.unit.module)
+                    .chain(
+                        self.bytecode_deps
+                            .iter()
+                            .filter(|(package, _)| package == name)
+                            .map(|(_, module)| module),
+                    )
+                    .map(|m| {
+                        let mut bytes = Vec::new();
+                        m.serialize_with_version(m.version, &mut bytes).unwrap(); // safe because package built successfully
+                        bytes
+                    })
+                    .collect();
+
+                let mut transitive_deps = BTreeSet::new();
+                let mut to_visit = vec![*name];
+                while let Some(package) = to_visit.pop() {
+                    for (dep, _, _) in self
+                        .dependency_graph
+                        .immediate_dependencies(package, DependencyMode::Always)
+                    {
+                        if transitive_deps.insert(dep) {
+                            to_visit.push(dep);
+                        }
+                    }
+                }
+                let dep_ids: Vec<_> = transitive_deps
+                    .iter()
+                    .filter_map(|dep| published.get(dep))
+                    .collect();
+
+                let digest = MovePackage::compute_digest_for_modules_and_deps(
+                    &modules,
+                    dep_ids,
+                    hash_modules,
+                );
+                (*id, digest)
+            })
+            .collect()
+    }
+
     /// Return a serialized representation of the bytecode modules in this package, topologically sorted in dependency order
     pub fn get_package_bytes(&self, with_unpublished_deps: bool) -> Vec<Vec<u8>> {
         self.get_dependency_sorted_modules(with_unpublished_deps)
@@ -8,6 +8,7 @@ use move_package::BuildConfig as MoveBuildConfig;
 use std::{fs, path::Path};
 use sui_move_build::{implicit_deps, BuildConfig};
 use sui_package_management::system_package_versions::latest_system_packages;
+use sui_types::digests::{get_mainnet_chain_identifier, get_testnet_chain_identifier};
 
 const LAYOUTS_DIR: &str = "layouts";
 const STRUCT_LAYOUTS_FILENAME: &str = "struct_layouts.yaml";
@@ -28,6 +29,19 @@ pub struct Build {
     /// will not be able to automatically determine the addresses of its dependencies.
     #[clap(long, global = true, requires = "dump_bytecode_as_base64")]
     pub ignore_chain: bool,
+    /// Specialize the package to this chain when dumping bytecode as Base64, without connecting
+    /// to the network: `mainnet`, `testnet`, or the identifier of another chain, as printed by
+    /// `sui client chain-identifier` (devnet's changes every time it is wiped, so it has to be
+    /// given this way). The addresses of dependencies are resolved from the Move.lock for that
+    /// chain, but dependencies are not tree-shaken, as that needs their on-chain linkage tables.
+    #[clap(
+        long,
+        global = true,
+        requires = "dump_bytecode_as_base64",
+        conflicts_with = "ignore_chain",
+        value_parser = parse_chain_id
+    )]
+    pub chain: Option<String>,
     /// If true, generate struct layout schemas for
     /// all struct types passed into `entry` functions declared by modules in this package
     /// These layout schemas can be consumed by clients (e.g.,
@@ -93,3 +107,20 @@ impl Build {
         Ok(())
     }
 }
+
+/// Parse the chain identifier for `--chain`, as it is written in Move.lock files.
+fn parse_chain_id(s: &str) -> Result<String, String> {
+    match s {
+        "mainnet" => Ok(get_mainnet_chain_identifier().to_string()),
+        "testnet" => Ok(get_testnet_chain_identifier().to_string()),
+        "devnet" => Err(
+            "devnet's chain identifier changes every time it is wiped: pass its current \
+             identifier instead, as printed by `sui client chain-identifier`"
+                .to_string(),
+        ),
+        _ if s.len() == 8 && s.chars().all(|c| c.is_ascii_hexdigit()) => Ok(s.to_lowercase()),
+        _ => Err(format!(
+            "Unknown chain {s:?}: expected mainnet, testnet, or an 8-digit hex chain identifier"
+        )),
+    }
+}
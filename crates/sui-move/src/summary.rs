@@ -20,8 +20,48 @@ pub struct Summary {
     #[clap(flatten)]
     pub summary: summary::Summary,
     /// The object ID to summarize if `package-id` is present. The `--path` will be ignored if this field is used.
+    /// Can be passed more than once to summarize several packages, in which case each package's
+    /// summary is written to a sub-directory of `--output-directory` named after its ID.
     #[clap(long = "package-id", value_parser = ObjectID::from_hex_literal)]
-    pub package_id: Option<ObjectID>,
+    pub package_id: Vec<ObjectID>,
+    /// When summarizing several packages, carry on with the rest if one of them cannot be
+    /// summarized, and report every failure at the end.
+    #[clap(long = "keep-going", requires = "package_id")]
+    pub keep_going: bool,
+    /// The version of the `--package-id` package to summarize, when it has been upgraded in place
+    /// (as system packages are). Defaults to its latest version. This is not `--version`, which
+    /// prints the version of the CLI. Only available when summarizing a single package.
+    #[clap(
+        long = "package-version",
+        value_name = "VERSION",
+        requires = "package_id",
+        conflicts_with = "offline"
+    )]
+    pub package_version: Option<u64>,
+    /// Directory to download the bytecode of the `--package-id` package and its dependencies to.
+    /// Each summarized package gets its own sub-directory, which is kept after the command
+    /// finishes. Bytecode downloaded by a previous run is reused unless `--force` is passed.
+    /// When not set, bytecode is downloaded to a temporary directory that is removed afterwards.
+    #[clap(long = "download-dir", value_name = "PATH", requires = "package_id")]
+    pub download_dir: Option<PathBuf>,
+    /// Summarize the `--package-id` package from bytecode downloaded by a previous run with
+    /// `--download-dir`, instead of fetching it from the network.
+    #[clap(long = "offline", requires_all = ["package_id", "from_dir"])]
+    pub offline: bool,
+    /// The `--download-dir` that a previous run downloaded the `--package-id` package to. Only
+    /// used with `--offline`.
+    #[clap(
+        long = "from-dir",
+        value_name = "PATH",
+        requires = "offline",
+        conflicts_with = "download_dir"
+    )]
+    pub from_dir: Option<PathBuf>,
+    /// Read the `--package-id` packages and their dependencies from the package cache under the
+    /// Sui config directory if they are there, and add the ones fetched from the network to it.
+    /// Entries are kept per chain identifier.
+    #[clap(long = "cache", requires = "package_id", conflicts_with = "offline")]
+    pub cache: bool,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
@@ -14,7 +14,7 @@ use sui_json_rpc_types::{
     SuiObjectData, SuiObjectDataFilter, SuiObjectDataOptions, SuiObjectResponse,
     SuiObjectResponseQuery, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
-use sui_keys::keystore::AccountKeystore;
+use sui_keys::keystore::{AccountKeystore, Keystore};
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
 use sui_types::crypto::SuiKeyPair;
 use sui_types::gas_coin::GasCoin;
@@ -27,6 +27,9 @@ pub struct WalletContext {
     client: Arc<RwLock<Option<SuiClient>>>,
     max_concurrent_requests: Option<u64>,
     env_override: Option<String>,
+    /// The keystore and active address from the client config, while they are overridden by
+    /// `with_keystore_override`.
+    persisted_keystore: Option<(Keystore, Option<SuiAddress>)>,
 }
 
 impl WalletContext {
@@ -45,6 +48,7 @@ impl WalletContext {
             client: Default::default(),
             max_concurrent_requests: None,
             env_override: None,
+            persisted_keystore: None,
         };
         Ok(context)
     }
@@ -64,6 +68,46 @@ impl WalletContext {
         self
     }
 
+    /// Use `keystore` instead of the keystore referenced by the client config. The active address
+    /// is kept if `keystore` manages it, and otherwise defaults to the first address in `keystore`.
+    ///
+    /// The override only lasts as long as this context: `save_config` still writes the keystore
+    /// path from the client config.
+    pub fn with_keystore_override(mut self, keystore: Keystore) -> Self {
+        let active_address = self
+            .config
+            .active_address
+            .filter(|address| keystore.addresses().contains(address));
+        let persisted_keystore = std::mem::replace(&mut self.config.keystore, keystore);
+        let persisted_active_address =
+            std::mem::replace(&mut self.config.active_address, active_address);
+        self.persisted_keystore = Some((persisted_keystore, persisted_active_address));
+        self
+    }
+
+    /// Save the client config. If the keystore is overridden, the config is saved with its own
+    /// keystore instead, and with its own active address unless that was changed to an address
+    /// that its own keystore manages.
+    pub fn save_config(&mut self) -> Result<(), anyhow::Error> {
+        let Some((keystore, active_address)) = &mut self.persisted_keystore else {
+            return self.config.save();
+        };
+
+        std::mem::swap(&mut self.config.keystore, keystore);
+        let current_active_address = self.config.active_address;
+        if current_active_address
+            .is_some_and(|address| !self.config.keystore.addresses().contains(&address))
+        {
+            self.config.active_address = *active_address;
+        }
+
+        let result = self.config.save();
+
+        std::mem::swap(&mut self.config.keystore, keystore);
+        self.config.active_address = current_active_address;
+        result
+    }
+
     pub fn get_addresses(&self) -> Vec<SuiAddress> {
         self.config.keystore.addresses()
     }
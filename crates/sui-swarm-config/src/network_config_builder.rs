@@ -67,6 +67,8 @@ pub struct ConfigBuilder<R = OsRng> {
     chain_override: Option<Chain>,
     committee: CommitteeConfig,
     genesis_config: Option<GenesisConfig>,
+    validator_stakes: Option<Vec<u64>>,
+    validator_names: Option<Vec<String>>,
     reference_gas_price: Option<u64>,
     additional_objects: Vec<Object>,
     jwk_fetch_interval: Option<Duration>,
@@ -92,6 +94,8 @@ impl ConfigBuilder {
             // We need to change this. There are some tests that depend on it though.
             committee: CommitteeConfig::Size(NonZeroUsize::new(1).unwrap()),
             genesis_config: None,
+            validator_stakes: None,
+            validator_names: None,
             reference_gas_price: None,
             additional_objects: vec![],
             jwk_fetch_interval: None,
@@ -173,6 +177,20 @@ impl<R> ConfigBuilder<R> {
         self
     }
 
+    /// Override the stake of each validator in the committee, in order. There must be exactly one
+    /// stake per validator.
+    pub fn with_validator_stakes(mut self, stakes: Vec<u64>) -> Self {
+        self.validator_stakes = Some(stakes);
+        self
+    }
+
+    /// Override the name of each validator in the committee, in order. There must be exactly one
+    /// name per validator.
+    pub fn with_validator_names(mut self, names: Vec<String>) -> Self {
+        self.validator_names = Some(names);
+        self
+    }
+
     pub fn with_reference_gas_price(mut self, reference_gas_price: u64) -> Self {
         self.reference_gas_price = Some(reference_gas_price);
         self
@@ -290,6 +308,8 @@ impl<R> ConfigBuilder<R> {
             supported_protocol_versions_config: self.supported_protocol_versions_config,
             committee: self.committee,
             genesis_config: self.genesis_config,
+            validator_stakes: self.validator_stakes,
+            validator_names: self.validator_names,
             chain_override: self.chain_override,
             reference_gas_price: self.reference_gas_price,
             additional_objects: self.additional_objects,
@@ -320,7 +340,7 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
         let committee = self.committee;
 
         let mut rng = self.rng.unwrap();
-        let validators = match committee {
+        let mut validators = match committee {
             CommitteeConfig::Size(size) => {
                 // We always get fixed protocol keys from this function (which is isolated from
                 // external test randomness because it uses a fixed seed). Necessary because some
@@ -382,6 +402,28 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
             }
         };
 
+        if let Some(stakes) = self.validator_stakes {
+            assert_eq!(
+                stakes.len(),
+                validators.len(),
+                "Expected one stake per validator"
+            );
+            for (validator, stake) in validators.iter_mut().zip(stakes) {
+                validator.stake = stake;
+            }
+        }
+
+        if let Some(names) = self.validator_names {
+            assert_eq!(
+                names.len(),
+                validators.len(),
+                "Expected one name per validator"
+            );
+            for (validator, name) in validators.iter_mut().zip(names) {
+                validator.name = Some(name);
+            }
+        }
+
         let genesis_config = self
             .genesis_config
             .unwrap_or_else(GenesisConfig::for_local_testing);
@@ -517,6 +559,7 @@ impl<R: rand::RngCore + rand::CryptoRng> ConfigBuilder<R> {
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZeroUsize;
     use sui_config::node::Genesis;
 
     #[test]
@@ -547,6 +590,54 @@ mod tests {
         assert_eq!(g, loaded_genesis);
     }
 
+    #[test]
+    fn validator_stakes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let network_config = crate::network_config_builder::ConfigBuilder::new(&dir)
+            .committee_size(NonZeroUsize::new(3).unwrap())
+            .with_validator_stakes(vec![
+                30_000_000_000_000_000,
+                20_000_000_000_000_000,
+                10_000_000_000_000_000,
+            ])
+            .build();
+
+        // The genesis validator set is ordered by protocol key rather than by committee index.
+        let mut stakes: Vec<_> = network_config
+            .genesis
+            .validator_set_for_tooling()
+            .iter()
+            .map(|v| v.staking_pool.sui_balance)
+            .collect();
+        stakes.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(
+            stakes,
+            vec![
+                30_000_000_000_000_000,
+                20_000_000_000_000_000,
+                10_000_000_000_000_000,
+            ]
+        );
+    }
+
+    #[test]
+    fn validator_names() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let network_config = crate::network_config_builder::ConfigBuilder::new(&dir)
+            .committee_size(NonZeroUsize::new(2).unwrap())
+            .with_validator_names(vec!["alice".to_string(), "bob".to_string()])
+            .build();
+
+        let mut names: Vec<_> = network_config
+            .genesis
+            .validator_set_for_tooling()
+            .iter()
+            .map(|v| v.verified_metadata().name.clone())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["alice", "bob"]);
+    }
+
     #[test]
     fn load_genesis_config_from_file() {
         let file = tempfile::NamedTempFile::new().unwrap();
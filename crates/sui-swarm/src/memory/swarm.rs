@@ -15,7 +15,7 @@ use std::{
 };
 use sui_types::traffic_control::{PolicyConfig, RemoteFirewallConfig};
 
-use sui_config::node::{AuthorityOverloadConfig, DBCheckpointConfig, RunWithRange};
+use sui_config::node::{AuthorityOverloadConfig, DBCheckpointConfig, RunWithRange, ServerType};
 use sui_config::{ExecutionCacheConfig, NodeConfig};
 use sui_macros::nondeterministic;
 use sui_node::SuiNodeHandle;
@@ -57,6 +57,7 @@ pub struct SwarmBuilder<R = OsRng> {
     fullnode_run_with_range: Option<RunWithRange>,
     fullnode_policy_config: Option<PolicyConfig>,
     fullnode_fw_config: Option<RemoteFirewallConfig>,
+    fullnode_jsonrpc_server_type: Option<ServerType>,
     max_submit_position: Option<usize>,
     submit_delay_step_override_millis: Option<u64>,
     global_state_hash_v2_enabled_config: GlobalStateHashV2EnabledConfig,
@@ -88,6 +89,7 @@ impl SwarmBuilder {
             fullnode_run_with_range: None,
             fullnode_policy_config: None,
             fullnode_fw_config: None,
+            fullnode_jsonrpc_server_type: None,
             max_submit_position: None,
             submit_delay_step_override_millis: None,
             global_state_hash_v2_enabled_config: GlobalStateHashV2EnabledConfig::Global(true),
@@ -121,6 +123,7 @@ impl<R> SwarmBuilder<R> {
             fullnode_run_with_range: self.fullnode_run_with_range,
             fullnode_policy_config: self.fullnode_policy_config,
             fullnode_fw_config: self.fullnode_fw_config,
+            fullnode_jsonrpc_server_type: self.fullnode_jsonrpc_server_type,
             max_submit_position: self.max_submit_position,
             submit_delay_step_override_millis: self.submit_delay_step_override_millis,
             global_state_hash_v2_enabled_config: self.global_state_hash_v2_enabled_config,
@@ -299,6 +302,11 @@ impl<R> SwarmBuilder<R> {
         self
     }
 
+    pub fn with_fullnode_jsonrpc_server_type(mut self, server_type: ServerType) -> Self {
+        self.fullnode_jsonrpc_server_type = Some(server_type);
+        self
+    }
+
     fn get_or_init_genesis_config(&mut self) -> &mut GenesisConfig {
         if self.genesis_config.is_none() {
             assert!(self.network_config.is_none());
@@ -336,6 +344,7 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
         };
 
         let ingest_data = self.data_ingestion_dir.clone();
+        let mut rng = self.rng;
 
         let network_config = self.network_config.unwrap_or_else(|| {
             let mut config_builder = ConfigBuilder::new(dir.as_ref());
@@ -382,7 +391,7 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
 
             config_builder
                 .committee(self.committee)
-                .rng(self.rng)
+                .rng(&mut rng)
                 .with_objects(self.additional_objects)
                 .with_supported_protocol_versions_config(
                     self.supported_protocol_versions_config.clone(),
@@ -418,6 +427,10 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
             fullnode_config_builder = fullnode_config_builder.with_chain_override(chain);
         }
 
+        if let Some(server_type) = self.fullnode_jsonrpc_server_type {
+            fullnode_config_builder = fullnode_config_builder.with_jsonrpc_server_type(server_type);
+        }
+
         if let Some(spvc) = &self.fullnode_supported_protocol_versions_config {
             let supported_versions = match spvc {
                 ProtocolVersionsConfig::Default => SupportedProtocolVersions::SYSTEM_DEFAULT,
@@ -441,7 +454,7 @@ impl<R: rand::RngCore + rand::CryptoRng> SwarmBuilder<R> {
                         builder = builder.with_rpc_port(rpc_port);
                     }
                 }
-                let config = builder.build(&mut OsRng, &network_config);
+                let config = builder.build(&mut rng, &network_config);
                 info!(
                     "SwarmBuilder configuring full node with name {}",
                     config.protocol_public_key()
@@ -5,8 +5,10 @@ use crate::{
     clever_error_rendering::render_clever_error_opt,
     client_ptb::ptb::PTB,
     displays::Pretty,
+    gas_station::GasStationConfig,
     key_identity::{get_identity_address, KeyIdentity},
-    upgrade_compatibility::check_compatibility,
+    package_download::download_package_and_deps_under,
+    upgrade_compatibility::{check_compatibility, check_compatibility_against_download},
     verifier_meter::{AccumulatingMeter, Accumulator},
 };
 use std::{
@@ -16,6 +18,7 @@ use std::{
     path::{Path, PathBuf},
     str::FromStr,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use anyhow::{anyhow, bail, ensure, Context};
@@ -24,23 +27,26 @@ use clap::*;
 use colored::Colorize;
 use fastcrypto::{
     encoding::{Base64, Encoding},
+    hash::HashFunction,
     traits::ToFromBytes,
 };
 use reqwest::StatusCode;
 
 use move_binary_format::CompiledModule;
 use move_bytecode_verifier_meter::Scope;
+use move_command_line_common::files::MOVE_COMPILED_EXTENSION;
 use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
 use move_package::{source_package::parsed_manifest::Dependencies, BuildConfig as MoveBuildConfig};
 use prometheus::Registry;
 use serde::Serialize;
 use serde_json::{json, Value};
-use sui_config::verifier_signing_config::VerifierSigningConfig;
+use sui_config::{verifier_signing_config::VerifierSigningConfig, Config};
 use sui_move::manage_package::resolve_lock_file_path;
 use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
 use sui_source_validation::{BytecodeSourceVerifier, ValidationMode};
 
 use shared_crypto::intent::Intent;
+use sui_faucet::{CoinInfo, RequestStatus};
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
     Coin, DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, DynamicFieldInfo,
@@ -69,14 +75,14 @@ use sui_sdk::{
 };
 use sui_types::{
     base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress},
-    crypto::{EmptySignInfo, SignatureScheme},
-    digests::TransactionDigest,
+    crypto::{DefaultHash, EmptySignInfo, SignatureScheme},
+    digests::{Digest, TransactionDigest},
     error::SuiError,
     gas::GasCostSummary,
     gas_coin::GasCoin,
     message_envelope::Envelope,
     metrics::BytecodeVerifierMetrics,
-    move_package::{MovePackage, UpgradeCap},
+    move_package::{MovePackage, UpgradeCap, UpgradePolicy},
     object::Owner,
     parse_sui_type_tag,
     signature::GenericSignature,
@@ -112,6 +118,9 @@ static USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_V
 /// Only to be used within CLI
 pub const GAS_SAFE_OVERHEAD: u64 = 1000;
 
+/// How often `sui client await-transfer` checks who owns the object it is waiting for.
+const AWAIT_TRANSFER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
 pub enum SuiClientCommands {
@@ -128,6 +137,17 @@ pub enum SuiClientCommands {
         #[clap(long, short = 's')]
         sort_by_alias: bool,
     },
+    /// Wait until the active address owns an object, for example because another client is about
+    /// to transfer it, then print the object and exit.
+    #[clap(name = "await-transfer")]
+    AwaitTransfer {
+        /// Object ID of the object to wait for
+        #[clap(name = "object_id")]
+        object_id: ObjectID,
+        /// Give up if the active address does not own the object after this many seconds
+        #[clap(long, default_value_t = 60)]
+        timeout_secs: u64,
+    },
     /// List the coin balance of an address
     #[clap(name = "balance")]
     Balance {
@@ -198,6 +218,15 @@ pub enum SuiClientCommands {
     /// List all Sui environments
     Envs,
 
+    /// Print the active environment (alias, RPC and WebSocket URLs, and basic auth) as YAML, or as
+    /// JSON with `--json`, to feed the endpoint configuration into other tools.
+    #[clap(name = "export-env")]
+    ExportEnv {
+        /// Include the environment's basic auth credentials instead of redacting them.
+        #[clap(long)]
+        show_secrets: bool,
+    },
+
     /// Execute a Signed Transaction. This is useful when the user prefers to sign elsewhere and use this command to execute.
     ExecuteSignedTx {
         /// BCS serialized transaction data bytes without its type tag, as base64 encoded string. This is the output of sui client command using --serialize-unsigned-transaction.
@@ -215,6 +244,26 @@ pub enum SuiClientCommands {
         signed_tx_bytes: String,
     },
 
+    /// Sign a transaction written by `--build-unsigned`, using only the local keystore. This does
+    /// not contact the network, so it can run on an air-gapped machine.
+    #[clap(name = "sign-offline")]
+    SignOffline {
+        /// Path of the unsigned transaction written by `--build-unsigned`.
+        #[clap(name = "tx_path")]
+        tx_path: PathBuf,
+        /// Path to write the signed transaction to, for `sui client submit-signed`.
+        #[clap(long)]
+        out: PathBuf,
+    },
+
+    /// Submit a transaction signed by `sui client sign-offline` to the network.
+    #[clap(name = "submit-signed")]
+    SubmitSigned {
+        /// Path of the signed transaction written by `sui client sign-offline`.
+        #[clap(name = "tx_path")]
+        tx_path: PathBuf,
+    },
+
     /// Request gas coin from faucet. By default, it will use the active address and the active network.
     #[clap[name = "faucet"]]
     Faucet {
@@ -222,11 +271,54 @@ pub enum SuiClientCommands {
         #[clap(long)]
         #[arg(value_parser)]
         address: Option<KeyIdentity>,
-        /// The url to the faucet
+        /// The url to the faucet, e.g. `http://127.0.0.1:9123/v2/gas` for the faucet started by
+        /// `sui start --with-faucet`.
         #[clap(long)]
         url: Option<String>,
     },
 
+    /// Export the gas usage of an executed transaction as folded stacks, which can be rendered
+    /// as a flamegraph (e.g. with `inferno-flamegraph` or `flamegraph.pl`).
+    ///
+    /// The profile is built from the transaction's gas cost summary, so gas is broken down into
+    /// computation and storage (net of the storage rebate) for the whole transaction; per-command
+    /// costs are not reported by the RPC. For an instruction-level profile, use
+    /// `sui client profile-transaction`.
+    #[clap(name = "gas-profile")]
+    GasProfile {
+        /// Digest of the transaction block
+        #[clap(name = "digest")]
+        digest: TransactionDigest,
+
+        /// Write the folded stacks to this file. When not set, a bar chart of the gas usage is
+        /// printed instead.
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Set aside coins for a local sponsored-transaction gas station: split the active address's
+    /// SUI into `--reserve` coins of `--coin-value` MIST each, and write a gas station
+    /// configuration referencing them. See `sui::gas_station` for the configuration format.
+    #[clap(name = "init-gas-station")]
+    InitGasStation {
+        /// Number of coins to reserve
+        #[clap(long)]
+        reserve: u64,
+
+        /// Value of each reserved coin, in MIST
+        #[clap(long, value_name = "MIST")]
+        coin_value: u64,
+
+        /// Write the gas station configuration to this file
+        #[clap(long, default_value = "gas-station.yaml")]
+        output: PathBuf,
+
+        /// An optional gas budget for the splitting transaction. When not set, it is estimated
+        /// with a dry run.
+        #[clap(long)]
+        gas_budget: Option<u64>,
+    },
+
     /// Obtain all gas objects owned by the address.
     /// An address' alias can be used instead of the address.
     #[clap(name = "gas")]
@@ -465,8 +557,16 @@ pub enum SuiClientCommands {
         address: Option<KeyIdentity>,
         /// The RPC server URL (e.g., local rpc server, devnet rpc server, etc) to be
         /// used for subsequent commands.
-        #[clap(long)]
+        #[clap(long, visible_alias = "alias")]
         env: Option<String>,
+        /// The RPC server URL of `--env`. If there is no environment called `--env` yet, one is
+        /// added with this URL (like `sui client new-env`) before switching to it.
+        #[clap(long, value_hint = ValueHint::Url, requires = "env")]
+        rpc: Option<String>,
+        /// With `--rpc`, switch to `--env` even if it already exists with a different RPC server
+        /// URL, replacing its URL with `--rpc`.
+        #[clap(long, requires = "rpc")]
+        force: bool,
     },
 
     /// Get the effects of executing the given transaction block
@@ -522,6 +622,24 @@ pub enum SuiClientCommands {
         processing: TxProcessingArgs,
     },
 
+    /// Check whether a local Move package is a compatible upgrade of an on-chain package, without
+    /// upgrading it. Public function signatures, struct and enum layouts and abilities are checked
+    /// under the `compatible` upgrade policy, and every incompatibility is reported.
+    #[clap(name = "check-upgrade")]
+    CheckUpgrade {
+        /// ID of the on-chain package to check the upgrade against.
+        #[clap(name = "package_id")]
+        package_id: ObjectID,
+
+        /// Path to directory containing the new version of the Move package
+        #[clap(long, default_value = ".")]
+        path: PathBuf,
+
+        /// Package build options
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+    },
+
     /// Upgrade Move modules
     #[clap(name = "upgrade")]
     Upgrade {
@@ -611,6 +729,15 @@ pub enum SuiClientCommands {
         /// Only works for unpublished modules (whose addresses are currently 0x0).
         #[clap(long)]
         address_override: Option<ObjectID>,
+
+        /// Compare the package's modules against those of the on-chain package with this ID, and
+        /// report whether each module's bytecode matches. The command still fails if any module
+        /// does not match, but only after the whole report is printed.
+        #[clap(
+            long,
+            conflicts_with_all = ["verify_deps", "skip_source", "address_override"]
+        )]
+        package_id: Option<ObjectID>,
     },
 
     /// Profile the gas usage of a transaction. Unless an output filepath is not specified, outputs a file `gas_profile_{tx_digest}_{unix_timestamp}.json` which can be opened in a flamegraph tool such as speedscope.
@@ -728,6 +855,12 @@ pub struct TxProcessingArgs {
     /// Perform a dry run of the transaction, without executing it.
     #[arg(long)]
     pub dry_run: bool,
+    /// With `--dry-run`, also estimate the cost of the transaction as if the reference gas price
+    /// were this many MIST, to see its cost under different market conditions. This only affects
+    /// the estimate: the dry run itself, and any real submission, use `--gas-price` or the
+    /// network's reference gas price.
+    #[arg(long, value_name = "MIST", requires = "dry_run")]
+    pub estimate_gas_price: Option<u64>,
     /// Perform a dev inspect
     #[arg(long)]
     pub dev_inspect: bool,
@@ -742,6 +875,14 @@ pub struct TxProcessingArgs {
     /// `sui client execute-combined-signed-tx --signed-tx-bytes <SIGNED_TX_BYTES>`.
     #[arg(long)]
     pub serialize_signed_transaction: bool,
+    /// Instead of executing the transaction, write the base64 encoded bcs bytes of the unsigned
+    /// transaction data (TransactionData) to the path given by `--out`. The file can be signed
+    /// offline with `sui client sign-offline` and then submitted with `sui client submit-signed`.
+    #[arg(long, requires = "out")]
+    pub build_unsigned: bool,
+    /// Path to write the unsigned transaction to when using `--build-unsigned`.
+    #[arg(long, value_name = "PATH", requires = "build_unsigned")]
+    pub out: Option<PathBuf>,
     /// Set the transaction sender to this address. When not specified, the sender is inferred
     /// by finding the owner of the gas payment. Note that when setting this field, the
     /// transaction will fail to execute if the sender's private key is not in the keystore;
@@ -751,9 +892,22 @@ pub struct TxProcessingArgs {
     pub sender: Option<SuiAddress>,
 }
 
+/// A faucet's response to a gas request. Faucets report errors either in `error`, or as a failed
+/// `status`, and may list the coins they sent.
 #[derive(serde::Deserialize, Debug)]
 struct FaucetResponse {
     error: Option<String>,
+    status: Option<RequestStatus>,
+    coins_sent: Option<Vec<CoinInfo>>,
+}
+
+impl FaucetResponse {
+    fn error(self) -> Option<String> {
+        match self.status {
+            Some(RequestStatus::Failure(err)) => Some(err.to_string()),
+            _ => self.error,
+        }
+    }
 }
 
 impl SuiClientCommands {
@@ -947,6 +1101,57 @@ impl SuiClientCommands {
                 SuiClientCommandResult::DynamicFieldQuery(df_read)
             }
 
+            SuiClientCommands::CheckUpgrade {
+                package_id,
+                path,
+                build_config,
+            } => {
+                let client = context.get_client().await?;
+                let read_api = client.read_api();
+                let chain_id = read_api.get_chain_identifier().await.ok();
+                let protocol_version = read_api.get_protocol_config(None).await?.protocol_version;
+                let protocol_config = ProtocolConfig::get_for_version(
+                    protocol_version,
+                    match chain_id
+                        .as_ref()
+                        .and_then(ChainIdentifier::from_chain_short_id)
+                    {
+                        Some(chain_id) => chain_id.chain(),
+                        None => Chain::Unknown,
+                    },
+                );
+
+                let package_path =
+                    path.canonicalize()
+                        .map_err(|e| SuiError::ModulePublishFailure {
+                            error: format!("Failed to canonicalize package path: {}", e),
+                        })?;
+                // Only the package's own modules are compared, so there is no need to check
+                // that its dependencies match their on-chain bytecode.
+                let compiled_package = compile_package(
+                    read_api,
+                    build_config,
+                    &package_path,
+                    /* with_unpublished_dependencies */ false,
+                    /* skip_dependency_verification */ true,
+                )
+                .await?;
+
+                let download_dir = tempfile::tempdir()?;
+                check_compatibility_against_download(
+                    read_api,
+                    package_id,
+                    compiled_package,
+                    package_path,
+                    UpgradePolicy::Compatible,
+                    &protocol_config,
+                    download_dir.path(),
+                )
+                .await?;
+
+                SuiClientCommandResult::CheckUpgrade(package_id)
+            }
+
             SuiClientCommands::Upgrade {
                 package_path,
                 upgrade_capability,
@@ -1308,6 +1513,30 @@ impl SuiClientCommands {
                 }
             }
 
+            SuiClientCommands::AwaitTransfer {
+                object_id,
+                timeout_secs,
+            } => {
+                let active_address = context.active_address()?;
+                let client = context.get_client().await?;
+                let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+                loop {
+                    let object_read = client
+                        .read_api()
+                        .get_object_with_options(object_id, SuiObjectDataOptions::full_content())
+                        .await?;
+                    if object_read.owner() == Some(Owner::AddressOwner(active_address)) {
+                        break SuiClientCommandResult::Object(object_read);
+                    }
+                    ensure!(
+                        Instant::now() < deadline,
+                        "Timed out after {timeout_secs}s waiting for {active_address} to own \
+                         object {object_id}"
+                    );
+                    tokio::time::sleep(AWAIT_TRANSFER_POLL_INTERVAL).await;
+                }
+            }
+
             SuiClientCommands::TransactionBlock { digest } => {
                 let client = context.get_client().await?;
                 let tx_read = client
@@ -1328,6 +1557,111 @@ impl SuiClientCommands {
                 SuiClientCommandResult::TransactionBlock(tx_read)
             }
 
+            SuiClientCommands::GasProfile { digest, output } => {
+                let client = context.get_client().await?;
+                let tx_read = client
+                    .read_api()
+                    .get_transaction_with_options(
+                        digest,
+                        SuiTransactionBlockResponseOptions::new().with_effects(),
+                    )
+                    .await?;
+                let Some(effects) = tx_read.effects else {
+                    bail!("No effects were returned for transaction {digest}");
+                };
+
+                let profile = GasProfileOutput::new(digest, effects.gas_cost_summary().clone());
+                if let Some(path) = output {
+                    fs::write(&path, profile.folded_stacks()).with_context(|| {
+                        format!("Cannot write gas profile to {}", path.display())
+                    })?;
+                }
+                SuiClientCommandResult::GasProfile(profile)
+            }
+
+            SuiClientCommands::InitGasStation {
+                reserve,
+                coin_value,
+                output,
+                gas_budget,
+            } => {
+                ensure!(reserve > 0, "Must reserve at least one coin");
+                ensure!(coin_value > 0, "Reserved coins must have a non-zero value");
+
+                let sponsor = context.active_address()?;
+                let client = context.get_client().await?;
+                let coins = context.gas_objects(sponsor).await?;
+                let balance: u128 = coins.iter().map(|(value, _)| *value as u128).sum();
+                let reserved = reserve as u128 * coin_value as u128;
+                ensure!(
+                    balance > reserved,
+                    "Cannot reserve {reserve} coins of {coin_value} MIST: {sponsor} only owns \
+                     {balance} MIST, and also needs to pay for gas"
+                );
+
+                // All of the address's SUI pays for gas, so that the reserved coins can be split
+                // off the merged gas coin.
+                let gas_payment: Vec<_> = coins.iter().map(|(_, o)| o.object_ref()).collect();
+                let tx_kind = client.transaction_builder().pay_sui_tx_kind(
+                    vec![sponsor; reserve as usize],
+                    vec![coin_value; reserve as usize],
+                )?;
+
+                let gas_price = context.get_reference_gas_price().await?;
+                let gas_budget = match gas_budget {
+                    Some(gas_budget) => gas_budget,
+                    None => {
+                        estimate_gas_budget(
+                            context,
+                            sponsor,
+                            tx_kind.clone(),
+                            gas_price,
+                            gas_payment.clone(),
+                            None,
+                        )
+                        .await?
+                    }
+                };
+
+                let tx_data = TransactionData::new_with_gas_coins(
+                    tx_kind,
+                    sponsor,
+                    gas_payment,
+                    gas_budget,
+                    gas_price,
+                );
+                let response = context
+                    .execute_transaction_may_fail(context.sign_transaction(&tx_data))
+                    .await?;
+                let Some(effects) = &response.effects else {
+                    bail!(
+                        "No effects were returned for transaction {}",
+                        response.digest
+                    );
+                };
+                if let SuiExecutionStatus::Failure { error } = effects.status() {
+                    bail!("Transaction {} failed: {error}", response.digest);
+                }
+
+                let config = GasStationConfig {
+                    sponsor,
+                    rpc_url: context.get_active_env()?.rpc.clone(),
+                    coin_value,
+                    coins: effects
+                        .created()
+                        .iter()
+                        .map(|o| o.reference.object_id)
+                        .collect(),
+                };
+                config.save(&output)?;
+
+                SuiClientCommandResult::InitGasStation(GasStationOutput {
+                    digest: response.digest,
+                    path: output,
+                    config,
+                })
+            }
+
             SuiClientCommands::Call {
                 package,
                 module,
@@ -1682,8 +2016,8 @@ impl SuiClientCommands {
                         bail!("No URL for faucet was provided and there is no active network.")
                     }
                 };
-                request_tokens_from_faucet(address, url).await?;
-                SuiClientCommandResult::NoOutput
+                let coins = request_tokens_from_faucet(address, url).await?;
+                SuiClientCommandResult::Faucet(coins)
             }
             SuiClientCommands::ChainIdentifier => {
                 let ci = context
@@ -1824,7 +2158,12 @@ impl SuiClientCommands {
                 )
                 .await?
             }
-            SuiClientCommands::Switch { address, env } => {
+            SuiClientCommands::Switch {
+                address,
+                env,
+                rpc,
+                force,
+            } => {
                 let mut addr = None;
 
                 if address.is_none() && env.is_none() {
@@ -1843,9 +2182,12 @@ impl SuiClientCommands {
                 }
 
                 if let Some(ref env) = env {
+                    if let Some(rpc) = rpc {
+                        Self::add_or_update_env(&mut context.config, env, rpc, force).await?;
+                    }
                     Self::switch_env(&mut context.config, env)?;
                 }
-                context.config.save()?;
+                context.save_config()?;
                 SuiClientCommandResult::Switch(SwitchResponse { address: addr, env })
             }
             SuiClientCommands::ActiveAddress => {
@@ -1891,6 +2233,40 @@ impl SuiClientCommands {
                 let response = context.execute_transaction_may_fail(transaction).await?;
                 SuiClientCommandResult::TransactionBlock(response)
             }
+            SuiClientCommands::SignOffline { tx_path, out } => {
+                let tx_data: TransactionData = read_offline_tx(&tx_path)?;
+                let sender = tx_data.sender();
+                let gas_owner = tx_data.gas_owner();
+
+                let mut signatures = vec![context
+                    .config
+                    .keystore
+                    .sign_secure(&sender, &tx_data, Intent::sui_transaction())?
+                    .into()];
+                if gas_owner != sender {
+                    signatures.push(
+                        context
+                            .config
+                            .keystore
+                            .sign_secure(&gas_owner, &tx_data, Intent::sui_transaction())?
+                            .into(),
+                    );
+                }
+
+                let digest = tx_data.digest();
+                write_offline_tx(&out, &SenderSignedData::new(tx_data, signatures))?;
+                SuiClientCommandResult::OfflineTransaction(OfflineTransactionOutput {
+                    digest,
+                    path: out,
+                    signed: true,
+                })
+            }
+            SuiClientCommands::SubmitSigned { tx_path } => {
+                let data: SenderSignedData = read_offline_tx(&tx_path)?;
+                let transaction = Transaction::new(data);
+                let response = context.execute_transaction_may_fail(transaction).await?;
+                SuiClientCommandResult::TransactionBlock(response)
+            }
             SuiClientCommands::NewEnv {
                 alias,
                 rpc,
@@ -1912,7 +2288,7 @@ impl SuiClientCommands {
                 // Check urls are valid and server is reachable
                 env.create_rpc_client(None, None).await?;
                 context.config.envs.push(env.clone());
-                context.config.save()?;
+                context.save_config()?;
                 SuiClientCommandResult::NewEnv(env)
             }
             SuiClientCommands::ActiveEnv => SuiClientCommandResult::ActiveEnv(
@@ -1922,12 +2298,20 @@ impl SuiClientCommands {
                 context.config.envs.clone(),
                 context.get_active_env().ok().map(|e| e.alias.clone()),
             ),
+            SuiClientCommands::ExportEnv { show_secrets } => {
+                let mut env = context.get_active_env()?.clone();
+                if !show_secrets && env.basic_auth.is_some() {
+                    env.basic_auth = Some("<redacted>".to_string());
+                }
+                SuiClientCommandResult::ExportEnv(env)
+            }
             SuiClientCommands::VerifySource {
                 package_path,
                 mut build_config,
                 verify_deps,
                 skip_source,
                 address_override,
+                package_id,
             } => {
                 let mode = match (!skip_source, verify_deps, address_override) {
                     (false, false, _) => {
@@ -1958,11 +2342,22 @@ impl SuiClientCommands {
                 .build(&package_path)?;
 
                 let client = context.get_client().await?;
-                BytecodeSourceVerifier::new(client.read_api())
-                    .verify(&compiled_package, mode)
+                if let Some(package_id) = package_id {
+                    let download_dir = tempfile::tempdir()?;
+                    let output = verify_source_against_download(
+                        client.read_api(),
+                        package_id,
+                        &compiled_package,
+                        download_dir.path(),
+                    )
                     .await?;
-
-                SuiClientCommandResult::VerifySource
+                    SuiClientCommandResult::VerifySourceModules(output)
+                } else {
+                    BytecodeSourceVerifier::new(client.read_api())
+                        .verify(&compiled_package, mode)
+                        .await?;
+                    SuiClientCommandResult::VerifySource
+                }
             }
             SuiClientCommands::PTB(ptb) => {
                 ptb.execute(context).await?;
@@ -1972,6 +2367,43 @@ impl SuiClientCommands {
         Ok(ret.prerender_clever_errors(context).await)
     }
 
+    /// Make sure that there is an environment called `alias` with RPC server URL `rpc`, adding it
+    /// if it is missing. Fails if it exists with a different URL, unless `force` is set, in which
+    /// case its URL is replaced. The server is checked to be reachable before it is added or
+    /// replaces the existing URL.
+    pub async fn add_or_update_env(
+        config: &mut SuiClientConfig,
+        alias: &str,
+        rpc: String,
+        force: bool,
+    ) -> Result<(), anyhow::Error> {
+        match config.envs.iter_mut().find(|env| env.alias == alias) {
+            Some(env) if env.rpc.trim_end_matches('/') == rpc.trim_end_matches('/') => {}
+            Some(env) => {
+                ensure!(
+                    force,
+                    "Environment config [{alias}] already exists with RPC URL {}. Pass --force to \
+                     change it to {rpc}.",
+                    env.rpc,
+                );
+                let updated = SuiEnv { rpc, ..env.clone() };
+                updated.create_rpc_client(None, None).await?;
+                *env = updated;
+            }
+            None => {
+                let env = SuiEnv {
+                    alias: alias.to_string(),
+                    rpc,
+                    ws: None,
+                    basic_auth: None,
+                };
+                env.create_rpc_client(None, None).await?;
+                config.add_env(env);
+            }
+        }
+        Ok(())
+    }
+
     pub fn switch_env(config: &mut SuiClientConfig, env: &str) -> Result<(), anyhow::Error> {
         let env = Some(env.into());
         ensure!(config.get_env(&env).is_some(), "Environment config not found for [{env:?}], add new environment config using the `sui client new-env` command.");
@@ -2478,6 +2910,15 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::ComputeTransactionDigest(tx_data) => {
                 writeln!(writer, "{}", tx_data.digest())?;
             }
+            SuiClientCommandResult::OfflineTransaction(output) => {
+                let kind = if output.signed { "signed" } else { "unsigned" };
+                writeln!(
+                    writer,
+                    "Wrote {kind} transaction {} to {}",
+                    output.digest,
+                    output.path.display()
+                )?;
+            }
             SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) => {
                 writeln!(
                     writer,
@@ -2529,9 +2970,76 @@ impl Display for SuiClientCommandResult {
                 table.with(TableStyle::rounded());
                 write!(f, "{}", table)?
             }
+            SuiClientCommandResult::ExportEnv(env) => {
+                let yaml = serde_yaml::to_string(env).map_err(|_| std::fmt::Error)?;
+                write!(writer, "{}", yaml)?;
+            }
+            SuiClientCommandResult::CheckUpgrade(package_id) => {
+                writeln!(writer, "Package is a compatible upgrade of {package_id}.")?;
+            }
+            SuiClientCommandResult::Faucet(coins) => {
+                if coins.is_empty() {
+                    writeln!(
+                        writer,
+                        "Request successful. It can take up to 1 minute to get the coin. Run sui \
+                        client gas to check your gas coins."
+                    )?;
+                } else {
+                    let mut builder = TableBuilder::default();
+                    builder.set_header(vec!["coinId", "amount (MIST)", "transferTxDigest"]);
+                    for coin in coins {
+                        builder.push_record(vec![
+                            coin.id.to_string(),
+                            coin.amount.to_string(),
+                            coin.transfer_tx_digest.to_string(),
+                        ]);
+                    }
+                    let mut table = builder.build();
+                    table.with(TableStyle::rounded());
+                    writeln!(writer, "{table}")?;
+                    let total: u128 = coins.iter().map(|coin| coin.amount as u128).sum();
+                    writeln!(
+                        writer,
+                        "Received {} coin(s) with {total} MIST in total.",
+                        coins.len()
+                    )?;
+                }
+            }
             SuiClientCommandResult::VerifySource => {
                 writeln!(writer, "Source verification succeeded!")?;
             }
+            SuiClientCommandResult::VerifySourceModules(output) => {
+                let mut builder = TableBuilder::default();
+                builder.set_header(vec!["module", "status", "localDigest", "onChainDigest"]);
+                for module in &output.modules {
+                    builder.push_record(vec![
+                        module.name.clone(),
+                        module.status.to_string(),
+                        module
+                            .local_digest
+                            .map_or_else(|| "-".to_string(), |d| d.to_string()),
+                        module
+                            .on_chain_digest
+                            .map_or_else(|| "-".to_string(), |d| d.to_string()),
+                    ]);
+                }
+                let mut table = builder.build();
+                table.with(TableStyle::rounded());
+                writeln!(writer, "{table}")?;
+                if output.verified {
+                    writeln!(
+                        writer,
+                        "Source verification succeeded: all modules match package {}.",
+                        output.package_id
+                    )?;
+                } else {
+                    writeln!(
+                        writer,
+                        "Source verification failed: the local build does not match package {}.",
+                        output.package_id
+                    )?;
+                }
+            }
             SuiClientCommandResult::VerifyBytecodeMeter {
                 success,
                 max_package_ticks,
@@ -2620,10 +3128,50 @@ impl Display for SuiClientCommandResult {
 
                 writeln!(f, "{}", table)?;
             }
+            SuiClientCommandResult::GasProfile(profile) => {
+                write!(f, "{}", profile)?;
+            }
+            SuiClientCommandResult::InitGasStation(output) => {
+                writeln!(
+                    f,
+                    "Reserved {} coins of {} MIST for {} in transaction {}",
+                    output.config.coins.len(),
+                    output.config.coin_value,
+                    output.config.sponsor,
+                    output.digest
+                )?;
+                writeln!(
+                    f,
+                    "Wrote gas station configuration to {}",
+                    output.path.display()
+                )?;
+            }
             SuiClientCommandResult::NoOutput => {}
             SuiClientCommandResult::DryRun(response) => {
                 writeln!(f, "{}", Pretty(response))?;
             }
+            SuiClientCommandResult::GasEstimate {
+                dry_run,
+                gas_price,
+                gas_cost_summary,
+                estimated_gas_budget,
+            } => {
+                writeln!(f, "{}", Pretty(dry_run))?;
+                writeln!(
+                    f,
+                    "Estimate at a reference gas price of {gas_price} MIST (dry run used {} MIST):",
+                    dry_run.input.gas_data().price
+                )?;
+                writeln!(
+                    f,
+                    "  Computation cost: {} MIST",
+                    gas_cost_summary.computation_cost
+                )?;
+                writeln!(
+                    f,
+                    "  Estimated gas cost (includes a small buffer): {estimated_gas_budget} MIST"
+                )?;
+            }
             SuiClientCommandResult::DevInspect(response) => {
                 writeln!(f, "{}", Pretty(response))?;
             }
@@ -2711,6 +3259,10 @@ impl SuiClientCommandResult {
     pub async fn prerender_clever_errors(mut self, context: &mut WalletContext) -> Self {
         match &mut self {
             SuiClientCommandResult::DryRun(DryRunTransactionBlockResponse { effects, .. })
+            | SuiClientCommandResult::GasEstimate {
+                dry_run: DryRunTransactionBlockResponse { effects, .. },
+                ..
+            }
             | SuiClientCommandResult::TransactionBlock(SuiTransactionBlockResponse {
                 effects: Some(effects),
                 ..
@@ -2729,15 +3281,21 @@ impl SuiClientCommandResult {
             | SuiClientCommandResult::Balance(_, _)
             | SuiClientCommandResult::ComputeTransactionDigest(_)
             | SuiClientCommandResult::ChainIdentifier(_)
+            | SuiClientCommandResult::CheckUpgrade(_)
             | SuiClientCommandResult::DynamicFieldQuery(_)
             | SuiClientCommandResult::DevInspect(_)
             | SuiClientCommandResult::Envs(_, _)
+            | SuiClientCommandResult::ExportEnv(_)
+            | SuiClientCommandResult::Faucet(_)
             | SuiClientCommandResult::Gas(_)
+            | SuiClientCommandResult::GasProfile(_)
+            | SuiClientCommandResult::InitGasStation(_)
             | SuiClientCommandResult::NewAddress(_)
             | SuiClientCommandResult::NewEnv(_)
             | SuiClientCommandResult::NoOutput
             | SuiClientCommandResult::Object(_)
             | SuiClientCommandResult::Objects(_)
+            | SuiClientCommandResult::OfflineTransaction(_)
             | SuiClientCommandResult::RemoveAddress(_)
             | SuiClientCommandResult::RawObject(_)
             | SuiClientCommandResult::SerializedSignedTransaction(_)
@@ -2745,7 +3303,8 @@ impl SuiClientCommandResult {
             | SuiClientCommandResult::Switch(_)
             | SuiClientCommandResult::SyncClientState
             | SuiClientCommandResult::VerifyBytecodeMeter { .. }
-            | SuiClientCommandResult::VerifySource => (),
+            | SuiClientCommandResult::VerifySource
+            | SuiClientCommandResult::VerifySourceModules(_) => (),
         }
         self
     }
@@ -2835,6 +3394,162 @@ impl From<&GasCoin> for GasCoinOutput {
     }
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineTransactionOutput {
+    pub digest: TransactionDigest,
+    pub path: PathBuf,
+    pub signed: bool,
+}
+
+/// How a module of a locally built package compares to the module of the same name in the
+/// on-chain package.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ModuleVerificationStatus {
+    Match,
+    Mismatch,
+    MissingOnChain,
+    MissingLocally,
+}
+
+impl Display for ModuleVerificationStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            ModuleVerificationStatus::Match => "match",
+            ModuleVerificationStatus::Mismatch => "mismatch",
+            ModuleVerificationStatus::MissingOnChain => "missing on-chain",
+            ModuleVerificationStatus::MissingLocally => "missing locally",
+        };
+        write!(f, "{status}")
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleVerification {
+    pub name: String,
+    pub status: ModuleVerificationStatus,
+    /// Digest of the module's bytecode in the local build, if it was built.
+    pub local_digest: Option<Digest>,
+    /// Digest of the module's bytecode on-chain, if the on-chain package has it.
+    pub on_chain_digest: Option<Digest>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceVerificationOutput {
+    pub package_id: ObjectID,
+    /// Whether every module matches.
+    pub verified: bool,
+    pub modules: Vec<ModuleVerification>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasStationOutput {
+    pub digest: TransactionDigest,
+    pub path: PathBuf,
+    pub config: GasStationConfig,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfileOutput {
+    pub digest: TransactionDigest,
+    pub gas_used: GasCostSummary,
+    /// The gas charged to the transaction, net of its storage rebate, broken down by category, in
+    /// MIST.
+    pub frames: Vec<GasProfileFrame>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasProfileFrame {
+    pub name: String,
+    pub mist: u64,
+}
+
+impl GasProfileOutput {
+    pub fn new(digest: TransactionDigest, gas_used: GasCostSummary) -> Self {
+        // The frames add up to what the transaction was charged: `computation_cost +
+        // storage_cost - storage_rebate`, or nothing if the rebate covers both. A frame cannot
+        // have a negative weight, so the rebate is taken off the storage cost, and any rebate
+        // left over off the computation cost. The non-refundable storage fee is the part of the
+        // rebate that was withheld, not an extra charge, so it is only reported in `gas_used`.
+        let storage = gas_used
+            .storage_cost
+            .saturating_sub(gas_used.storage_rebate);
+        let excess_rebate = gas_used
+            .storage_rebate
+            .saturating_sub(gas_used.storage_cost);
+        let computation = gas_used.computation_cost.saturating_sub(excess_rebate);
+        let frames = [("computation", computation), ("storage", storage)]
+            .into_iter()
+            .map(|(name, mist)| GasProfileFrame {
+                name: name.to_string(),
+                mist,
+            })
+            .collect();
+
+        Self {
+            digest,
+            gas_used,
+            frames,
+        }
+    }
+
+    /// Render the profile in the folded-stack format: one `<stack>;<frame> <weight>` line per
+    /// frame with a non-zero weight.
+    pub fn folded_stacks(&self) -> String {
+        let mut folded = String::new();
+        for frame in self.frames.iter().filter(|frame| frame.mist > 0) {
+            let _ = writeln!(folded, "{};{} {}", self.digest, frame.name, frame.mist);
+        }
+        folded
+    }
+}
+
+impl Display for GasProfileOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const BAR_WIDTH: u64 = 40;
+        let max = self
+            .frames
+            .iter()
+            .map(|frame| frame.mist)
+            .max()
+            .unwrap_or(0);
+
+        let mut builder = TableBuilder::default();
+        builder.set_header(vec!["category", "gas (MIST)", ""]);
+        for frame in &self.frames {
+            let width = if max == 0 {
+                0
+            } else {
+                (frame.mist as u128 * BAR_WIDTH as u128 / max as u128) as usize
+            };
+            builder.push_record(vec![
+                frame.name.clone(),
+                frame.mist.to_string(),
+                "█".repeat(width),
+            ]);
+        }
+
+        let mut table = builder.build();
+        table.with(TablePanel::header(format!(
+            "Gas usage of transaction {} (storage rebate: {} MIST)",
+            self.digest, self.gas_used.storage_rebate
+        )));
+        table.with(TableStyle::rounded().horizontals([HorizontalLine::new(
+            1,
+            TableStyle::modern().get_horizontal(),
+        )]));
+        table.with(TableModify::new(TableCols::single(1)).with(TableAlignment::right()));
+        table.with(tabled::settings::style::BorderSpanCorrection);
+        write!(f, "{}", table)
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ObjectsOutput {
@@ -2885,17 +3600,30 @@ pub enum SuiClientCommandResult {
     Addresses(AddressesOutput),
     Balance(Vec<(Option<SuiCoinMetadata>, Vec<Coin>)>, bool),
     ChainIdentifier(String),
+    CheckUpgrade(ObjectID),
     ComputeTransactionDigest(TransactionData),
     DynamicFieldQuery(DynamicFieldPage),
     DryRun(DryRunTransactionBlockResponse),
     DevInspect(DevInspectResults),
     Envs(Vec<SuiEnv>, Option<String>),
+    ExportEnv(SuiEnv),
+    Faucet(Vec<CoinInfo>),
     Gas(Vec<GasCoin>),
+    #[serde(rename_all = "camelCase")]
+    GasEstimate {
+        dry_run: DryRunTransactionBlockResponse,
+        gas_price: u64,
+        gas_cost_summary: GasCostSummary,
+        estimated_gas_budget: u64,
+    },
+    GasProfile(GasProfileOutput),
+    InitGasStation(GasStationOutput),
     NewAddress(NewAddressOutput),
     NewEnv(SuiEnv),
     NoOutput,
     Object(SuiObjectResponse),
     Objects(Vec<SuiObjectResponse>),
+    OfflineTransaction(OfflineTransactionOutput),
     RawObject(SuiObjectResponse),
     RemoveAddress(RemoveAddressOutput),
     SerializedSignedTransaction(SenderSignedData),
@@ -2911,6 +3639,7 @@ pub enum SuiClientCommandResult {
         used_ticks: Accumulator,
     },
     VerifySource,
+    VerifySourceModules(SourceVerificationOutput),
 }
 
 #[derive(Serialize, Clone)]
@@ -2934,11 +3663,12 @@ impl Display for SwitchResponse {
     }
 }
 
-/// Request tokens from the Faucet for the given address
+/// Request tokens from the Faucet for the given address, returning the coins that the faucet
+/// reports to have sent. Faucets that do not report them return no coins.
 pub async fn request_tokens_from_faucet(
     address: SuiAddress,
     url: String,
-) -> Result<(), anyhow::Error> {
+) -> Result<Vec<CoinInfo>, anyhow::Error> {
     let address_str = address.to_string();
     let json_body = json![{
         "FixedAmountRequest": {
@@ -2958,19 +3688,20 @@ pub async fn request_tokens_from_faucet(
 
     match resp.status() {
         StatusCode::ACCEPTED | StatusCode::CREATED | StatusCode::OK => {
-            let faucet_resp: FaucetResponse = resp.json().await?;
+            let mut faucet_resp: FaucetResponse = resp.json().await?;
+            let coins = faucet_resp.coins_sent.take().unwrap_or_default();
 
-            if let Some(err) = faucet_resp.error {
+            if let Some(err) = faucet_resp.error() {
                 bail!("Faucet request was unsuccessful: {err}")
-            } else {
-                println!("Request successful. It can take up to 1 minute to get the coin. Run sui client gas to check your gas coins.");
             }
+            Ok(coins)
         }
         StatusCode::BAD_REQUEST => {
             let faucet_resp: FaucetResponse = resp.json().await?;
-            if let Some(err) = faucet_resp.error {
+            if let Some(err) = faucet_resp.error() {
                 bail!("Faucet request was unsuccessful. {err}");
             }
+            Ok(vec![])
         }
         StatusCode::TOO_MANY_REQUESTS => {
             bail!("Faucet service received too many requests from this IP address. Please try again after 60 minutes.");
@@ -2982,7 +3713,6 @@ pub async fn request_tokens_from_faucet(
             bail!("Faucet request was unsuccessful: {status_code}");
         }
     }
-    Ok(())
 }
 
 fn pretty_print_balance(
@@ -3199,6 +3929,28 @@ pub fn estimate_gas_budget_from_gas_cost(
     computation_cost_with_overhead.max(if gas_usage < 0 { 0 } else { gas_usage as u64 })
 }
 
+/// Scale the costs in `gas_cost_summary`, from a transaction run at `gas_price`, to what they would
+/// be at `estimate_gas_price`. Only the computation cost depends on the gas price; storage is
+/// charged at the storage price, which stays the same.
+pub fn gas_cost_at_price(
+    gas_cost_summary: &GasCostSummary,
+    gas_price: u64,
+    estimate_gas_price: u64,
+) -> GasCostSummary {
+    let computation_cost = if gas_price == 0 {
+        0
+    } else {
+        let scaled = gas_cost_summary.computation_cost as u128 * estimate_gas_price as u128
+            / gas_price as u128;
+        u64::try_from(scaled).unwrap_or(u64::MAX)
+    };
+
+    GasCostSummary {
+        computation_cost,
+        ..gas_cost_summary.clone()
+    }
+}
+
 /// Queries the protocol config for the maximum gas allowed in a transaction.
 pub async fn max_gas_budget(client: &SuiClient) -> Result<u64, anyhow::Error> {
     let cfg = client.read_api().get_protocol_config(None).await?;
@@ -3232,9 +3984,12 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
     let TxProcessingArgs {
         tx_digest,
         dry_run,
+        estimate_gas_price,
         dev_inspect,
         serialize_unsigned_transaction,
         serialize_signed_transaction,
+        build_unsigned,
+        out,
         sender,
     } = processing;
 
@@ -3242,6 +3997,10 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
         !serialize_unsigned_transaction || !serialize_signed_transaction,
         "Cannot specify both flags: --serialize-unsigned-transaction and --serialize-signed-transaction."
     );
+    ensure!(
+        !build_unsigned || !(serialize_unsigned_transaction || serialize_signed_transaction),
+        "Cannot combine --build-unsigned with --serialize-unsigned-transaction or --serialize-signed-transaction."
+    );
 
     let gas_price = if let Some(gas_price) = gas_price {
         gas_price
@@ -3268,7 +4027,7 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
     }
 
     if dry_run {
-        return execute_dry_run(
+        let result = execute_dry_run(
             context,
             signer,
             tx_kind,
@@ -3277,7 +4036,26 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
             gas_payment.clone(),
             None,
         )
-        .await;
+        .await?;
+
+        let (estimate_gas_price, dry_run) = match (estimate_gas_price, result) {
+            (Some(price), SuiClientCommandResult::DryRun(dry_run)) => (price, dry_run),
+            (_, result) => return Ok(result),
+        };
+
+        let gas_cost_summary = gas_cost_at_price(
+            dry_run.effects.gas_cost_summary(),
+            gas_price,
+            estimate_gas_price,
+        );
+        let estimated_gas_budget =
+            estimate_gas_budget_from_gas_cost(&gas_cost_summary, estimate_gas_price);
+        return Ok(SuiClientCommandResult::GasEstimate {
+            dry_run,
+            gas_price: estimate_gas_price,
+            gas_cost_summary,
+            estimated_gas_budget,
+        });
     }
 
     let gas_budget = match gas_budget {
@@ -3339,6 +4117,15 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
         Ok(SuiClientCommandResult::SerializedUnsignedTransaction(
             tx_data,
         ))
+    } else if let Some(path) = out.filter(|_| build_unsigned) {
+        write_offline_tx(&path, &tx_data)?;
+        Ok(SuiClientCommandResult::OfflineTransaction(
+            OfflineTransactionOutput {
+                digest: tx_data.digest(),
+                path,
+                signed: false,
+            },
+        ))
     } else if tx_digest {
         Ok(SuiClientCommandResult::ComputeTransactionDigest(tx_data))
     } else {
@@ -3389,6 +4176,27 @@ pub(crate) async fn dry_run_or_execute_or_serialize(
     }
 }
 
+/// Write `value` to `path` as base64 encoded bcs bytes, the format read by [`read_offline_tx`].
+fn write_offline_tx<T: Serialize>(path: &Path, value: &T) -> Result<(), anyhow::Error> {
+    let bytes = bcs::to_bytes(value)?;
+    std::fs::write(path, Base64::encode(bytes))
+        .with_context(|| format!("Failed to write transaction to {}", path.display()))
+}
+
+/// Read a transaction written by [`write_offline_tx`] from `path`.
+fn read_offline_tx<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, anyhow::Error> {
+    let encoded = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read transaction from {}", path.display()))?;
+    let bytes = Base64::decode(encoded.trim())
+        .map_err(|_| anyhow!("Invalid Base64 encoding in {}", path.display()))?;
+    bcs::from_bytes(&bytes).map_err(|_| {
+        anyhow!(
+            "Failed to parse transaction in {}, check that it was written by the expected command",
+            path.display()
+        )
+    })
+}
+
 async fn execute_dev_inspect(
     context: &mut WalletContext,
     signer: SuiAddress,
@@ -3458,6 +4266,108 @@ async fn check_protocol_version_and_warn(read_api: &ReadApi) -> Result<(), anyho
     Ok(())
 }
 
+/// Compare the modules of `compiled_package` against those of the on-chain package `package_id`,
+/// which is downloaded under `download_dir`. Modules that have not been published yet (whose
+/// address is 0x0) are compared as if they were published at the package's original ID, and each
+/// local module is serialized at the bytecode version of its on-chain counterpart.
+async fn verify_source_against_download(
+    read_api: &ReadApi,
+    package_id: ObjectID,
+    compiled_package: &CompiledPackage,
+    download_dir: &Path,
+) -> Result<SourceVerificationOutput, anyhow::Error> {
+    let metadata =
+        download_package_and_deps_under(read_api, download_dir, package_id, None, false, None)
+            .await?;
+    let original_id = metadata
+        .root_package_original_id
+        .ok_or_else(|| anyhow!("Unable to get original ID of package {package_id}"))?;
+
+    let package_path = download_dir.join(package_id.to_canonical_string(/* with_prefix */ true));
+    let mut on_chain_modules = BTreeMap::new();
+    for entry in fs::read_dir(&package_path).with_context(|| {
+        format!(
+            "Unable to read downloaded package at {}",
+            package_path.display()
+        )
+    })? {
+        let path = entry?.path();
+        if !path
+            .extension()
+            .is_some_and(|ext| ext == MOVE_COMPILED_EXTENSION)
+        {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let bytes =
+            fs::read(&path).with_context(|| format!("Unable to read module {}", path.display()))?;
+        on_chain_modules.insert(name.to_string(), bytes);
+    }
+
+    let mut local_modules = BTreeMap::new();
+    for module in compiled_package.get_modules() {
+        let name = module.self_id().name().to_string();
+        let mut module = module.clone();
+        let self_address = module.self_handle().address;
+        let address = &mut module.address_identifiers[self_address.0 as usize];
+        if *address == AccountAddress::ZERO {
+            *address = *original_id;
+        }
+
+        let version = on_chain_modules
+            .get(&name)
+            .and_then(|bytes| CompiledModule::deserialize_with_defaults(bytes).ok())
+            .map_or(module.version, |on_chain| on_chain.version);
+        let mut bytes = vec![];
+        module
+            .serialize_with_version(version, &mut bytes)
+            .with_context(|| format!("Unable to serialize module {name}"))?;
+        local_modules.insert(name, bytes);
+    }
+
+    let digest = |bytes: &Vec<u8>| {
+        let mut hasher = DefaultHash::default();
+        hasher.update(bytes);
+        Digest::new(hasher.finalize().digest)
+    };
+
+    let names: BTreeSet<_> = local_modules
+        .keys()
+        .chain(on_chain_modules.keys())
+        .collect();
+    let modules: Vec<_> = names
+        .into_iter()
+        .map(|name| {
+            let local = local_modules.get(name);
+            let on_chain = on_chain_modules.get(name);
+            let status = match (local, on_chain) {
+                (Some(local), Some(on_chain)) if local == on_chain => {
+                    ModuleVerificationStatus::Match
+                }
+                (Some(_), Some(_)) => ModuleVerificationStatus::Mismatch,
+                (Some(_), None) => ModuleVerificationStatus::MissingOnChain,
+                (None, _) => ModuleVerificationStatus::MissingLocally,
+            };
+            ModuleVerification {
+                name: name.clone(),
+                status,
+                local_digest: local.map(digest),
+                on_chain_digest: on_chain.map(digest),
+            }
+        })
+        .collect();
+
+    Ok(SourceVerificationOutput {
+        package_id,
+        verified: modules
+            .iter()
+            .all(|m| m.status == ModuleVerificationStatus::Match),
+        modules,
+    })
+}
+
 /// Try to convert this object into a package.
 fn to_package(o: SuiObjectResponse) -> anyhow::Result<MovePackage> {
     let id = o.object_id()?;
@@ -214,9 +214,12 @@ impl PTB {
         let processing = TxProcessingArgs {
             tx_digest: program_metadata.tx_digest_set,
             dry_run: program_metadata.dry_run_set,
+            estimate_gas_price: None,
             dev_inspect: program_metadata.dev_inspect_set,
             serialize_unsigned_transaction: program_metadata.serialize_unsigned_set,
             serialize_signed_transaction: program_metadata.serialize_signed_set,
+            build_unsigned: false,
+            out: None,
             sender: program_metadata.sender.map(|x| x.value.into_inner().into()),
         };
 
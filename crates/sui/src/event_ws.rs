@@ -0,0 +1,60 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! WebSocket endpoint for event and transaction subscriptions, for `sui start --with-event-ws`.
+//!
+//! Subscriptions are served by the fullnode's JSON-RPC server, which `sui start` configures to
+//! also accept WebSocket connections when this endpoint is enabled. The endpoint forwards every
+//! connection to the fullnode unchanged, so that clients can subscribe at an address of their
+//! choosing, regardless of whether reads are served by the fullnode or by the indexer.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, warn};
+
+/// Listen for WebSocket connections on `address`, forwarding each one to the fullnode's JSON-RPC
+/// server at `fullnode_address`. Returns the address that is being listened on, which differs
+/// from `address` if it has port 0.
+pub async fn start_event_ws_bridge(
+    address: SocketAddr,
+    fullnode_address: SocketAddr,
+) -> anyhow::Result<SocketAddr> {
+    let listener = TcpListener::bind(address).await?;
+    let address = listener.local_addr()?;
+
+    // The fullnode listens on all interfaces by default, which cannot be connected to as such.
+    let fullnode_address = match fullnode_address.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => {
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), fullnode_address.port())
+        }
+        IpAddr::V6(ip) if ip.is_unspecified() => {
+            SocketAddr::new(Ipv6Addr::LOCALHOST.into(), fullnode_address.port())
+        }
+        _ => fullnode_address,
+    };
+
+    tokio::spawn(async move {
+        loop {
+            let (client, peer) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    warn!("Event WebSocket endpoint failed to accept a connection: {e}");
+                    continue;
+                }
+            };
+            tokio::spawn(async move {
+                if let Err(e) = forward(client, fullnode_address).await {
+                    debug!("Event WebSocket connection from {peer} closed: {e}");
+                }
+            });
+        }
+    });
+
+    Ok(address)
+}
+
+async fn forward(mut client: TcpStream, fullnode_address: SocketAddr) -> std::io::Result<()> {
+    let mut fullnode = TcpStream::connect(fullnode_address).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut fullnode).await?;
+    Ok(())
+}
@@ -0,0 +1,286 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Randomized transaction generation for `sui start --fuzz`.
+//!
+//! Each round picks a random funded account and sends one of a SUI transfer, a coin split, a coin
+//! merge, or a call to `0x2::pay::split`, with random coins, amounts and recipients. Every
+//! transaction is valid, so any failure is reported as an anomaly. At the end, the total SUI
+//! balance of the accounts is checked against the gas they have spent. Rounds only depend on the
+//! seed and on the state of the network, so re-running with the same seed on a fresh network
+//! sends the same transactions.
+
+use anyhow::{anyhow, bail};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde_json::json;
+use sui_json::SuiJsonValue;
+use sui_json_rpc_types::{
+    Coin, SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions,
+    SuiTypeTag,
+};
+use sui_sdk::SuiClient;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::SuiKeyPair;
+use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::transaction::{Transaction, TransactionData};
+use sui_types::SUI_FRAMEWORK_PACKAGE_ID;
+use tracing::{info, warn};
+
+/// Gas budget for every transaction sent while fuzzing.
+const GAS_BUDGET: u64 = 50_000_000;
+
+/// The largest amount of MIST moved by a single transfer or split.
+const MAX_AMOUNT: u64 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug)]
+enum Operation {
+    TransferSui,
+    SplitCoin,
+    MergeCoins,
+    PaySplit,
+}
+
+const OPERATIONS: [Operation; 4] = [
+    Operation::TransferSui,
+    Operation::SplitCoin,
+    Operation::MergeCoins,
+    Operation::PaySplit,
+];
+
+/// Send `rounds` randomized transactions, seeded by `seed`, between the accounts owning `keys`.
+/// Fails if any transaction fails, or if the accounts' total balance does not add up afterwards.
+pub async fn run_fuzz(
+    client: &SuiClient,
+    keys: &[SuiKeyPair],
+    seed: u64,
+    rounds: usize,
+) -> anyhow::Result<()> {
+    if keys.is_empty() {
+        bail!("No funded accounts to fuzz with");
+    }
+
+    info!("Fuzzing with seed {seed} for {rounds} rounds");
+    let mut rng = StdRng::seed_from_u64(seed);
+    let addresses: Vec<SuiAddress> = keys.iter().map(|k| (&k.public()).into()).collect();
+
+    let initial_balance = total_balance(client, &addresses).await?;
+    let mut net_gas_usage: i128 = 0;
+    let mut anomalies = vec![];
+
+    for round in 0..rounds {
+        let sender = rng.gen_range(0..keys.len());
+        let operation = *OPERATIONS.choose(&mut rng).unwrap();
+        let tx_data = match build_transaction(client, &mut rng, &addresses, sender, operation).await
+        {
+            Ok(Some(tx_data)) => tx_data,
+            // The sender does not have enough coins for this operation.
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Round {round}: failed to build {operation:?}: {e}");
+                anomalies.push(format!("round {round}: building {operation:?}: {e}"));
+                continue;
+            }
+        };
+
+        let tx = Transaction::from_data_and_signer(tx_data, vec![&keys[sender]]);
+        let digest = *tx.digest();
+        let response = client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                tx,
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+                Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+            )
+            .await;
+
+        let effects = match response.map(|r| r.effects) {
+            Ok(Some(effects)) => effects,
+            Ok(None) => {
+                anomalies.push(format!(
+                    "round {round}: {operation:?} {digest} has no effects"
+                ));
+                continue;
+            }
+            Err(e) => {
+                warn!("Round {round}: {operation:?} {digest} was not executed: {e}");
+                anomalies.push(format!("round {round}: {operation:?} {digest}: {e}"));
+                continue;
+            }
+        };
+
+        // Gas is charged whether or not the transaction succeeded.
+        net_gas_usage += effects.gas_cost_summary().net_gas_usage() as i128;
+        if let SuiExecutionStatus::Failure { error } = effects.status() {
+            warn!("Round {round}: {operation:?} {digest} failed: {error}");
+            anomalies.push(format!(
+                "round {round}: {operation:?} {digest} failed: {error}"
+            ));
+        }
+    }
+
+    let final_balance = total_balance(client, &addresses).await?;
+    if initial_balance as i128 - final_balance as i128 != net_gas_usage {
+        anomalies.push(format!(
+            "total balance went from {initial_balance} to {final_balance} MIST, but \
+             {net_gas_usage} MIST was spent on gas"
+        ));
+    }
+
+    if !anomalies.is_empty() {
+        bail!(
+            "Fuzzing with seed {seed} found {} anomalies:\n{}",
+            anomalies.len(),
+            anomalies.join("\n")
+        );
+    }
+
+    info!("Fuzzing with seed {seed} finished without anomalies");
+    Ok(())
+}
+
+/// Build a transaction performing `operation` for the account at index `sender`, or `None` if it
+/// does not own enough coins to do so.
+async fn build_transaction(
+    client: &SuiClient,
+    rng: &mut StdRng,
+    addresses: &[SuiAddress],
+    sender: usize,
+    operation: Operation,
+) -> anyhow::Result<Option<TransactionData>> {
+    let signer = addresses[sender];
+    let mut coins = client
+        .coin_read_api()
+        .get_coins(signer, None, None, None)
+        .await?
+        .data;
+    // Coins come back in an unspecified order; sort them so that rounds are reproducible.
+    coins.sort_by_key(|c| c.coin_object_id);
+
+    let builder = client.transaction_builder();
+    let tx_data = match operation {
+        Operation::TransferSui => {
+            // The transferred coin also pays for gas, so it needs to cover both.
+            let Some(coin) = coins
+                .iter()
+                .filter(|c| c.balance > GAS_BUDGET + MAX_AMOUNT)
+                .collect::<Vec<_>>()
+                .choose(rng)
+                .copied()
+            else {
+                return Ok(None);
+            };
+            let recipient = *addresses.choose(rng).unwrap();
+            let amount = rng.gen_range(1..=MAX_AMOUNT);
+            builder
+                .transfer_sui(
+                    signer,
+                    coin.coin_object_id,
+                    GAS_BUDGET,
+                    recipient,
+                    Some(amount),
+                )
+                .await?
+        }
+
+        Operation::SplitCoin => {
+            let Some((coin, amount, gas)) = choose_coin_to_split(rng, &coins) else {
+                return Ok(None);
+            };
+            builder
+                .split_coin(
+                    signer,
+                    coin.coin_object_id,
+                    vec![amount],
+                    Some(gas.coin_object_id),
+                    GAS_BUDGET,
+                )
+                .await?
+        }
+
+        Operation::MergeCoins => {
+            let Some(gas) = choose_gas(rng, &coins, &[]) else {
+                return Ok(None);
+            };
+            let others: Vec<_> = coins
+                .iter()
+                .filter(|c| c.coin_object_id != gas.coin_object_id)
+                .collect();
+            let [primary, to_merge] = others.choose_multiple(rng, 2).collect::<Vec<_>>()[..] else {
+                return Ok(None);
+            };
+            builder
+                .merge_coins(
+                    signer,
+                    primary.coin_object_id,
+                    to_merge.coin_object_id,
+                    Some(gas.coin_object_id),
+                    GAS_BUDGET,
+                )
+                .await?
+        }
+
+        Operation::PaySplit => {
+            let Some((coin, amount, gas)) = choose_coin_to_split(rng, &coins) else {
+                return Ok(None);
+            };
+            builder
+                .move_call(
+                    signer,
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    "pay",
+                    "split",
+                    vec![SuiTypeTag::new("0x2::sui::SUI".to_string())],
+                    vec![
+                        SuiJsonValue::from_object_id(coin.coin_object_id),
+                        SuiJsonValue::new(json!(amount.to_string()))?,
+                    ],
+                    Some(gas.coin_object_id),
+                    GAS_BUDGET,
+                    None,
+                )
+                .await?
+        }
+    };
+
+    Ok(Some(tx_data))
+}
+
+/// Pick a coin that can pay for gas, other than any of the coins in `exclude`.
+fn choose_gas<'c>(rng: &mut StdRng, coins: &'c [Coin], exclude: &[&Coin]) -> Option<&'c Coin> {
+    coins
+        .iter()
+        .filter(|c| c.balance >= GAS_BUDGET)
+        .filter(|c| exclude.iter().all(|e| e.coin_object_id != c.coin_object_id))
+        .collect::<Vec<_>>()
+        .choose(rng)
+        .copied()
+}
+
+/// Pick a non-empty coin to split, an amount to split off it, and another coin to pay for gas.
+fn choose_coin_to_split<'c>(
+    rng: &mut StdRng,
+    coins: &'c [Coin],
+) -> Option<(&'c Coin, u64, &'c Coin)> {
+    let coin = coins
+        .iter()
+        .filter(|c| c.balance > 0)
+        .collect::<Vec<_>>()
+        .choose(rng)
+        .copied()?;
+    let gas = choose_gas(rng, coins, &[coin])?;
+    let amount = rng.gen_range(1..=coin.balance.min(MAX_AMOUNT));
+    Some((coin, amount, gas))
+}
+
+async fn total_balance(client: &SuiClient, addresses: &[SuiAddress]) -> anyhow::Result<u128> {
+    let mut total: u128 = 0;
+    for address in addresses {
+        let balance = client.coin_read_api().get_balance(*address, None).await?;
+        total = total
+            .checked_add(balance.total_balance)
+            .ok_or_else(|| anyhow!("Total balance overflowed"))?;
+    }
+    Ok(total)
+}
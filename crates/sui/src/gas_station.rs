@@ -0,0 +1,37 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuration for a local sponsored-transaction gas station, written by
+//! `sui client init-gas-station`.
+//!
+//! The gas station sponsors transactions by lending out coins that were set aside for it ahead of
+//! time, one coin per in-flight transaction. The configuration is a YAML file of the form:
+//!
+//! ```yaml
+//! # The address that owns the reserved coins, and signs for gas as the sponsor.
+//! sponsor: "0x8a2b..."
+//! # The fullnode the coins were reserved on.
+//! rpc-url: "http://127.0.0.1:9000"
+//! # The value of each reserved coin, in MIST.
+//! coin-value: 1000000000
+//! # The IDs of the reserved coins. Versions are left out, as they change every time a coin
+//! # pays for gas: the gas station looks up the latest version of a coin before using it.
+//! coins:
+//!   - "0x1f3c..."
+//!   - "0x77a0..."
+//! ```
+
+use serde::{Deserialize, Serialize};
+use sui_config::Config;
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct GasStationConfig {
+    pub sponsor: SuiAddress,
+    pub rpc_url: String,
+    pub coin_value: u64,
+    pub coins: Vec<ObjectID>,
+}
+
+impl Config for GasStationConfig {}
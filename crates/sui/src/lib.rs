@@ -7,12 +7,16 @@ pub mod client_commands;
 pub mod client_ptb;
 mod clever_error_rendering;
 pub mod displays;
+pub mod event_ws;
 pub mod fire_drill;
+pub mod fuzz;
+pub mod gas_station;
 pub mod genesis_ceremony;
 pub mod genesis_inspector;
 pub mod key_identity;
 pub mod keytool;
 pub mod mvr_resolver;
+pub mod package_download;
 pub mod sui_commands;
 pub mod upgrade_compatibility;
 pub mod validator_commands;
@@ -4,7 +4,7 @@
 use clap::*;
 use colored::Colorize;
 use sui::client_commands::SuiClientCommands::{ProfileTransaction, ReplayBatch, ReplayTransaction};
-use sui::sui_commands::SuiCommand;
+use sui::sui_commands::{format_error_json, SuiCommand};
 use sui_types::exit_main;
 use tracing::debug;
 
@@ -75,11 +75,34 @@ async fn main() {
                 .init()
         }
 
+        SuiCommand::Start {
+            log_journald,
+            log_syslog,
+            ..
+        } => {
+            let mut config = telemetry_subscribers::TelemetryConfig::new()
+                .with_log_level("error")
+                .with_env();
+            if log_journald {
+                config = config.with_journald();
+            }
+            if let Some(facility) = log_syslog {
+                config = config.with_syslog(facility);
+            }
+            config.init()
+        }
+
         _ => telemetry_subscribers::TelemetryConfig::new()
             .with_log_level("error")
             .with_env()
             .init(),
     };
     debug!("Sui CLI version: {VERSION}");
-    exit_main!(args.command.execute().await);
+    let json = args.command.json_output();
+    let result = args.command.execute().await;
+    if let (true, Err(err)) = (json, &result) {
+        println!("{}", format_error_json(err));
+        std::process::exit(1);
+    }
+    exit_main!(result);
 }
@@ -0,0 +1,401 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Context};
+use futures::stream::{self, StreamExt};
+use move_command_line_common::files::MOVE_COMPILED_EXTENSION;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use sui_json_rpc_types::{SuiObjectDataOptions, SuiRawData};
+use sui_move::summary::PackageSummaryMetadata;
+use sui_sdk::apis::ReadApi;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::Digest;
+use sui_types::is_system_package;
+use sui_types::move_package::MovePackage;
+use tracing::warn;
+
+/// The maximum number of dependency packages fetched from the RPC at the same time.
+pub const MAX_CONCURRENT_PACKAGE_DOWNLOADS: usize = 8;
+
+/// Name of the file that `download_package_and_deps_under` writes the package's metadata to,
+/// alongside the downloaded modules, so that the download can be summarized again offline.
+pub const PACKAGE_METADATA_FILE: &str = "package-summary-metadata.json";
+
+/// Name of the directory under the Sui config directory that holds the default `PackageCache`.
+pub const PACKAGE_CACHE_DIR: &str = "package-cache";
+
+/// An on-disk cache of the packages fetched by `resolve_package`, keyed by chain, storage ID and
+/// version. Each package is stored as `<root>/<chain ID>/<storage ID>/<version>.bcs`, so that
+/// networks that share storage IDs (such as the system packages) never share entries.
+///
+/// A package can only be found in the cache without knowing its version if it is not a system
+/// package: every other storage ID holds a single version of a package, but system packages are
+/// upgraded in place, so their latest version is always fetched from the network.
+#[derive(Clone, Debug)]
+pub struct PackageCache {
+    root: PathBuf,
+}
+
+impl PackageCache {
+    /// A cache of the packages on the chain identified by `chain_id`, stored under `root`.
+    pub fn new(root: PathBuf, chain_id: &str) -> Self {
+        Self {
+            root: root.join(chain_id),
+        }
+    }
+
+    /// The cached package `package_id` at `version`, or at the latest cached version if `version`
+    /// is not set (for packages that are not system packages). Unreadable entries are treated as
+    /// missing.
+    pub fn get(
+        &self,
+        package_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Option<MovePackage> {
+        let version = match version {
+            Some(version) => version,
+            None if is_system_package(package_id) => return None,
+            None => fs::read_dir(self.package_dir(package_id))
+                .ok()?
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    if path.extension()? != "bcs" {
+                        return None;
+                    }
+                    path.file_stem()?.to_str()?.parse().ok()
+                })
+                .max()
+                .map(SequenceNumber::from_u64)?,
+        };
+
+        let bytes = fs::read(self.entry_path(package_id, version)).ok()?;
+        let package: MovePackage = bcs::from_bytes(&bytes).ok()?;
+        (package.id() == package_id && package.version() == version).then_some(package)
+    }
+
+    /// Add `package` to the cache, replacing any existing entry for the same version.
+    pub fn insert(&self, package: &MovePackage) -> anyhow::Result<()> {
+        let dir = self.package_dir(package.id());
+        fs::create_dir_all(&dir).with_context(|| {
+            format!("Unable to create package cache directory {}", dir.display())
+        })?;
+
+        // Write to a temporary file first, so that concurrent readers never see a partial entry.
+        let path = self.entry_path(package.id(), package.version());
+        let tmp_path = path.with_extension(format!("bcs.{}.tmp", std::process::id()));
+        fs::write(&tmp_path, bcs::to_bytes(package)?)
+            .and_then(|()| fs::rename(&tmp_path, &path))
+            .with_context(|| format!("Unable to write package to cache at {}", path.display()))
+    }
+
+    fn package_dir(&self, package_id: ObjectID) -> PathBuf {
+        self.root
+            .join(package_id.to_canonical_string(/* with_prefix */ true))
+    }
+
+    fn entry_path(&self, package_id: ObjectID, version: SequenceNumber) -> PathBuf {
+        self.package_dir(package_id)
+            .join(format!("{}.bcs", version.value()))
+    }
+}
+
+/// Try to resolve an ObjectID to a MovePackage. If `version` is set, the package is resolved at
+/// that version, otherwise at its latest version. If a `cache` is given, it is consulted before
+/// the network, and packages fetched from the network are added to it.
+pub async fn resolve_package(
+    reader: &ReadApi,
+    package_id: ObjectID,
+    version: Option<SequenceNumber>,
+    cache: Option<&PackageCache>,
+) -> anyhow::Result<MovePackage> {
+    if let Some(package) = cache.and_then(|cache| cache.get(package_id, version)) {
+        return Ok(package);
+    }
+
+    let package = fetch_package(reader, package_id, version).await?;
+    if let Some(cache) = cache {
+        // The cache only saves time, so failing to update it is not an error.
+        if let Err(e) = cache.insert(&package) {
+            warn!("{e:#}");
+        }
+    }
+    Ok(package)
+}
+
+async fn fetch_package(
+    reader: &ReadApi,
+    package_id: ObjectID,
+    version: Option<SequenceNumber>,
+) -> anyhow::Result<MovePackage> {
+    let object = match version {
+        Some(version) => reader
+            .try_get_parsed_past_object(package_id, version, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .into_object()?,
+        None => reader
+            .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
+            .await?
+            .into_object()?,
+    };
+
+    let Some(SuiRawData::Package(package)) = object.bcs else {
+        bail!("Object {} is not a package.", package_id);
+    };
+
+    if let Some(version) = version {
+        if package.version != version {
+            bail!(
+                "Requested version {} of package {package_id}, but got version {}",
+                version.value(),
+                package.version.value(),
+            );
+        }
+    }
+
+    Ok(MovePackage::new(
+        package.id,
+        package.version,
+        package.module_map,
+        // This package came from on-chain and the tool runs locally, so don't worry about
+        // trying to enforce the package size limit.
+        u64::MAX,
+        package.type_origin_table,
+        package.linkage_table,
+    )?)
+}
+
+/// Download the package's modules and its dependencies to the specified path. If `version` is
+/// set, the package is downloaded at that version. Dependencies are downloaded from the storage
+/// IDs in the package's linkage table, which each hold a single version of a package (except for
+/// system packages, which are always downloaded at their latest version).
+///
+/// Each package (the root package and every package in its linkage table) is written to its own
+/// sub-directory of `path`, named after its storage ID, containing one `.mv` file per module. The
+/// returned metadata records where each dependency was written, relative to `path`, and is also
+/// written to `PACKAGE_METADATA_FILE` under `path` (see `read_downloaded_package`).
+///
+/// Once a package's modules are written, they are read back and checked against the package's
+/// digest (see `verify_package_modules`), so that a module that was not written in full fails
+/// with the ID of the package it affects. The digest is computed from the package returned by the
+/// RPC, so this does not detect an RPC that returns the wrong package.
+///
+/// If `reuse_existing` is set, packages whose directory already exists under `path` are assumed
+/// to have been downloaded before, and their modules are only written again if they fail the same
+/// check (e.g. because they are from another version of a system package, or were changed on
+/// disk). Otherwise existing module files are overwritten. Packages are resolved through `cache`,
+/// if one is given (see `resolve_package`).
+///
+/// ```no_run
+/// # async fn example() -> anyhow::Result<()> {
+/// use sui::package_download::download_package_and_deps_under;
+/// use sui_sdk::SuiClientBuilder;
+/// use sui_types::base_types::ObjectID;
+///
+/// let client = SuiClientBuilder::default().build_testnet().await?;
+/// let package_id = ObjectID::from_hex_literal("0x2")?;
+/// let out = std::env::temp_dir().join("sui-packages");
+/// let metadata =
+///     download_package_and_deps_under(client.read_api(), &out, package_id, None, false, None)
+///         .await?;
+/// println!("{:?}", metadata.dependencies);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn download_package_and_deps_under(
+    read_api: &ReadApi,
+    path: &Path,
+    package_id: ObjectID,
+    version: Option<SequenceNumber>,
+    reuse_existing: bool,
+    cache: Option<&PackageCache>,
+) -> anyhow::Result<PackageSummaryMetadata> {
+    let mut dependencies = BTreeMap::new();
+    let mut linkage = BTreeMap::new();
+    let mut type_origins = BTreeMap::new();
+
+    let root_package = resolve_package(read_api, package_id, version, cache).await?;
+
+    // Dependencies are fetched concurrently and may complete in any order, but each one is
+    // written to its own directory and recorded in ordered maps, so the result is the same
+    // regardless of completion order.
+    let mut downloads = stream::iter(root_package.linkage_table().iter())
+        .map(|(original_id, pkg_info)| async move {
+            let package = resolve_package(read_api, pkg_info.upgraded_id, None, cache).await?;
+            anyhow::Ok((original_id, pkg_info, package))
+        })
+        .buffer_unordered(MAX_CONCURRENT_PACKAGE_DOWNLOADS);
+
+    while let Some(download) = downloads.next().await {
+        let (original_id, pkg_info, package) = download?;
+        let relative_package_path = write_package_modules(path, &package, reuse_existing)?;
+        dependencies.insert(*original_id, relative_package_path);
+        linkage.insert(*original_id, pkg_info.clone());
+        type_origins.insert(*original_id, package.type_origin_table().clone());
+    }
+
+    write_package_modules(path, &root_package, reuse_existing)?;
+
+    let metadata = PackageSummaryMetadata {
+        root_package_id: Some(root_package.id()),
+        root_package_original_id: Some(root_package.original_package_id()),
+        root_package_version: Some(root_package.version().value()),
+        type_origins: Some(type_origins),
+        dependencies: Some(dependencies),
+        linkage: Some(linkage),
+    };
+
+    let metadata_path = path.join(PACKAGE_METADATA_FILE);
+    fs::write(&metadata_path, serde_json::to_string_pretty(&metadata)?).with_context(|| {
+        format!(
+            "Unable to write package metadata to {}",
+            metadata_path.display()
+        )
+    })?;
+
+    Ok(metadata)
+}
+
+/// Read back the metadata of a package previously downloaded to `path` by
+/// `download_package_and_deps_under`, checking that the modules of the package and of each of its
+/// dependencies are still there.
+pub fn read_downloaded_package(path: &Path) -> anyhow::Result<PackageSummaryMetadata> {
+    let metadata_path = path.join(PACKAGE_METADATA_FILE);
+    if !metadata_path.is_file() {
+        bail!(
+            "No package metadata found at {}. Download the package first with \
+             `sui move summary --package-id <object_id> --download-dir <PATH>`",
+            metadata_path.display()
+        );
+    }
+
+    let metadata: PackageSummaryMetadata = serde_json::from_slice(&fs::read(&metadata_path)?)
+        .with_context(|| {
+            format!(
+                "Unable to read package metadata from {}",
+                metadata_path.display()
+            )
+        })?;
+
+    let Some(root_package_id) = metadata.root_package_id else {
+        bail!(
+            "Package metadata at {} does not record the downloaded package",
+            metadata_path.display()
+        );
+    };
+
+    let root_package_path =
+        PathBuf::from(root_package_id.to_canonical_string(/* with_prefix */ true));
+    let package_paths = std::iter::once(&root_package_path)
+        .chain(metadata.dependencies.iter().flat_map(|deps| deps.values()));
+    for relative_package_path in package_paths {
+        let package_path = path.join(relative_package_path);
+        let has_modules = package_path.is_dir()
+            && fs::read_dir(&package_path)?.any(|entry| {
+                entry.is_ok_and(|e| {
+                    e.path().extension().and_then(|ext| ext.to_str())
+                        == Some(MOVE_COMPILED_EXTENSION)
+                })
+            });
+        if !has_modules {
+            bail!(
+                "Missing bytecode for package {} under {}. Re-download the package with \
+                 `sui move summary --package-id <object_id> --download-dir <PATH> --force`",
+                relative_package_path.display(),
+                path.display()
+            );
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Check that the modules of `package` written under `path` by `download_package_and_deps_under`
+/// have the package's digest, i.e. that every module was written in full and has not changed on
+/// disk since. This only checks the files against `package`, not `package` against the chain.
+pub fn verify_package_modules(path: &Path, package: &MovePackage) -> anyhow::Result<()> {
+    let package_path = path.join(package.id().to_canonical_string(/* with_prefix */ true));
+    let modules = package
+        .serialized_module_map()
+        .keys()
+        .map(|m_name| {
+            let file_path = package_path
+                .join(m_name)
+                .with_extension(MOVE_COMPILED_EXTENSION);
+            fs::read(&file_path).with_context(|| {
+                format!(
+                    "Unable to read module {m_name} of package {} from {}",
+                    package.id(),
+                    file_path.display(),
+                )
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let expected = package.digest(/* hash_modules */ true);
+    let actual = MovePackage::compute_digest_for_modules_and_deps(
+        &modules,
+        package
+            .linkage_table()
+            .values()
+            .map(|info| &info.upgraded_id),
+        /* hash_modules */ true,
+    );
+    if actual != expected {
+        bail!(
+            "Modules of package {} written to {} were not written correctly: their digest does \
+             not match the package's digest (expected {}, got {})",
+            package.id(),
+            package_path.display(),
+            Digest::new(expected),
+            Digest::new(actual),
+        );
+    }
+    Ok(())
+}
+
+/// Write the package's modules to a directory under `path` named after its storage ID, returning
+/// that directory relative to `path`, and check them against the package's digest. If
+/// `reuse_existing` is set and the directory already exists, nothing is written, unless the
+/// modules already in it fail the check.
+pub fn write_package_modules(
+    path: &Path,
+    package: &MovePackage,
+    reuse_existing: bool,
+) -> anyhow::Result<PathBuf> {
+    let relative_package_path = PathBuf::from(
+        package
+            .id()
+            .deref()
+            .to_canonical_string(/* with_prefix */ true),
+    );
+
+    let package_path = path.join(&relative_package_path);
+    if reuse_existing && package_path.is_dir() {
+        match verify_package_modules(path, package) {
+            Ok(()) => return Ok(relative_package_path),
+            Err(e) => warn!("Downloading package {} again: {e:#}", package.id()),
+        }
+    }
+
+    fs::create_dir_all(&package_path)?;
+    for (m_name, module) in package.serialized_module_map() {
+        let file_path = package_path
+            .join(m_name)
+            .with_extension(MOVE_COMPILED_EXTENSION);
+        let mut file = fs::File::create(&file_path)?;
+        file.write_all(module).with_context(|| {
+            format!(
+                "Unable to write module {m_name} for package {} to {}",
+                relative_package_path.display(),
+                file_path.display(),
+            )
+        })?;
+    }
+
+    verify_package_modules(path, package)?;
+    Ok(relative_package_path)
+}
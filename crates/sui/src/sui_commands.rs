@@ -2,26 +2,33 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::client_commands::{
-    implicit_deps_for_protocol_version, pkg_tree_shake, SuiClientCommands,
+    implicit_deps_for_protocol_version, pkg_tree_shake, SuiClientCommandResult, SuiClientCommands,
 };
+use crate::event_ws::start_event_ws_bridge;
 use crate::fire_drill::{run_fire_drill, FireDrill};
+use crate::fuzz::run_fuzz;
 use crate::genesis_ceremony::{run, Ceremony};
-use crate::keytool::KeyToolCommand;
+use crate::keytool::{open_keystore_file, parse_key_scheme, KeyToolCommand};
+use crate::package_download::{
+    download_package_and_deps_under, read_downloaded_package, PackageCache, PACKAGE_CACHE_DIR,
+};
 use crate::validator_commands::SuiValidatorCommand;
 use anyhow::{anyhow, bail, ensure, Context};
 use clap::*;
 use colored::Colorize;
+use fastcrypto::encoding::{Base64, Encoding, Hex};
+use fastcrypto::secp256k1::Secp256k1KeyPair;
 use fastcrypto::traits::KeyPair;
 use move_analyzer::analyzer;
-use move_command_line_common::files::MOVE_COMPILED_EXTENSION;
 use move_package::BuildConfig;
 use mysten_common::tempdir;
 use rand::rngs::OsRng;
-use std::collections::BTreeMap;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::{stdout, Write};
-use std::net::{AddrParseError, IpAddr, Ipv4Addr, SocketAddr};
+use std::net::{AddrParseError, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::num::NonZeroUsize;
-use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::{fs, io};
@@ -29,8 +36,9 @@ use sui_bridge::config::BridgeCommitteeConfig;
 use sui_bridge::metrics::BridgeMetrics;
 use sui_bridge::sui_client::SuiBridgeClient;
 use sui_bridge::sui_transaction_builder::build_committee_register_transaction;
-use sui_config::node::Genesis;
+use sui_config::node::{Genesis, ServerType};
 use sui_config::p2p::SeedPeer;
+use sui_config::NodeConfig;
 use sui_config::{
     genesis_blob_exists, sui_config_dir, Config, PersistedConfig, FULL_NODE_DB_PATH,
     SUI_CLIENT_CONFIG, SUI_FULLNODE_CONFIG, SUI_NETWORK_CONFIG,
@@ -39,14 +47,13 @@ use sui_config::{
     SUI_BENCHMARK_GENESIS_GAS_KEYSTORE_FILENAME, SUI_GENESIS_FILENAME, SUI_KEYSTORE_FILENAME,
 };
 use sui_faucet::{create_wallet_context, start_faucet, AppState, FaucetConfig, LocalFaucet};
+use sui_framework::{BuiltInFramework, SystemPackage};
+use sui_framework_snapshot::load_bytecode_snapshot_from_dir;
 use sui_indexer::test_utils::{
     start_indexer_jsonrpc_for_testing, start_indexer_writer_for_testing,
 };
-use sui_json_rpc_types::{SuiObjectDataOptions, SuiRawData};
-use sui_move::summary::PackageSummaryMetadata;
 use sui_sdk::apis::ReadApi;
-use sui_sdk::SuiClient;
-use sui_types::move_package::MovePackage;
+use sui_sdk::{SuiClient, SuiClientBuilder};
 
 use sui_graphql_rpc::{
     config::{ConnectionConfig, ServiceConfig},
@@ -58,21 +65,27 @@ use serde_json::json;
 use sui_keys::keypair_file::read_key;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_move::manage_package::resolve_lock_file_path;
+use sui_move::summary::PackageSummaryMetadata;
 use sui_move::{self, execute_move_command};
 use sui_move_build::{
     check_conflicting_addresses, check_invalid_dependencies, check_unpublished_dependencies,
     implicit_deps, BuildConfig as SuiBuildConfig, SuiPackageHooks,
 };
 use sui_package_management::system_package_versions::latest_system_packages;
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
 use sui_sdk::sui_client_config::{SuiClientConfig, SuiEnv};
 use sui_sdk::wallet_context::WalletContext;
 use sui_swarm::memory::Swarm;
-use sui_swarm_config::genesis_config::GenesisConfig;
+use sui_swarm_config::genesis_config::{GenesisConfig, ValidatorGenesisConfig};
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_swarm_config::network_config_builder::ConfigBuilder;
 use sui_swarm_config::node_config_builder::FullnodeConfigBuilder;
-use sui_types::base_types::{ObjectID, SuiAddress};
-use sui_types::crypto::{SignatureScheme, SuiKeyPair, ToFromBytes};
+use sui_types::base_types::{ConciseableName, SequenceNumber, SuiAddress};
+use sui_types::crypto::{AuthorityPublicKeyBytes, SignatureScheme, SuiKeyPair, ToFromBytes};
+use sui_types::digests::ChainIdentifier;
+use sui_types::multiaddr::Multiaddr;
+use sui_types::sui_system_state::SuiSystemStateTrait;
+use telemetry_subscribers::SyslogFacility;
 use tracing;
 use tracing::info;
 
@@ -85,6 +98,20 @@ const DEFAULT_GRAPHQL_PORT: u16 = 9125;
 
 const DEFAULT_INDEXER_PORT: u16 = 9124;
 
+const DEFAULT_EVENT_WS_PORT: u16 = 9126;
+
+/// Default gas budget of each validator's bridge committee registration transaction.
+const DEFAULT_BRIDGE_REGISTRATION_GAS_BUDGET: u64 = 1_000_000_000;
+
+/// Name of the file that `sui move summary` writes the metadata of every package to, keyed by
+/// package ID, when it summarizes several packages.
+const PACKAGE_SUMMARIES_METADATA_FILE: &str = "package-summaries-metadata.json";
+
+const WAIT_FOR_READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Default for `sui move --timeout`, in seconds.
+const DEFAULT_MOVE_NETWORK_TIMEOUT_SECS: u64 = 300;
+
 #[derive(Args)]
 pub struct IndexerArgs {
     /// Start an indexer with default host and port: 0.0.0.0:9124. This flag accepts also a port,
@@ -115,6 +142,12 @@ pub struct IndexerArgs {
         )]
     with_graphql: Option<String>,
 
+    /// Configure the GraphQL server with this TOML file, in the same format as the standalone
+    /// GraphQL service's `--config` file, instead of the restrictive limits used in tests. Any
+    /// settings that the file leaves out take the standalone service's defaults.
+    #[clap(long, value_name = "PATH", requires = "with_graphql")]
+    graphql_config: Option<PathBuf>,
+
     /// Port for the Indexer Postgres DB. Default port is 5432.
     #[clap(long, default_value = "5432")]
     pg_port: u16,
@@ -134,6 +167,17 @@ pub struct IndexerArgs {
     /// DB password for the Indexer Postgres DB. Default password is postgrespw.
     #[clap(long, default_value = "postgrespw")]
     pg_password: String,
+
+    /// Whether to connect to the Indexer Postgres DB over TLS, e.g. for a managed Postgres that
+    /// requires it. Default is disable.
+    #[clap(long, value_enum, value_name = "MODE", default_value_t = PgSslMode::Disable)]
+    pg_sslmode: PgSslMode,
+
+    /// The CA certificates to check the Indexer Postgres DB's certificate against, as a PEM file.
+    /// With `--pg-sslmode verify-full`, the server's certificate must be signed by one of these
+    /// (or, if this is not set, by one of those in `~/.postgresql/root.crt`).
+    #[clap(long, value_name = "PATH")]
+    pg_sslrootcert: Option<PathBuf>,
 }
 
 impl IndexerArgs {
@@ -141,15 +185,102 @@ impl IndexerArgs {
         Self {
             with_indexer: None,
             with_graphql: None,
+            graphql_config: None,
             pg_port: 5432,
             pg_host: "localhost".to_string(),
             pg_db_name: "sui_indexer".to_string(),
             pg_user: "postgres".to_string(),
             pg_password: "postgrespw".to_string(),
+            pg_sslmode: PgSslMode::Disable,
+            pg_sslrootcert: None,
+        }
+    }
+
+    /// The connection string of the Indexer Postgres DB, with its TLS settings.
+    pub fn pg_address(&self) -> Result<String, anyhow::Error> {
+        let Self {
+            pg_port,
+            pg_host,
+            pg_db_name,
+            pg_user,
+            pg_password,
+            pg_sslmode,
+            pg_sslrootcert,
+            ..
+        } = self;
+
+        let address =
+            format!("postgres://{pg_user}:{pg_password}@{pg_host}:{pg_port}/{pg_db_name}");
+        if *pg_sslmode == PgSslMode::Disable {
+            ensure!(
+                pg_sslrootcert.is_none(),
+                "Cannot set `--pg-sslrootcert` without TLS; set `--pg-sslmode` as well."
+            );
+            return Ok(address);
+        }
+
+        let mut url = url::Url::parse(&address)
+            .map_err(|err| anyhow!("Invalid Indexer Postgres DB address: {err}"))?;
+        url.query_pairs_mut()
+            .append_pair("sslmode", pg_sslmode.as_str());
+        if let Some(path) = pg_sslrootcert {
+            ensure!(
+                path.is_file(),
+                "Cannot find the Postgres CA certificates at {}",
+                path.display()
+            );
+            url.query_pairs_mut()
+                .append_pair("sslrootcert", &path.to_string_lossy());
+        }
+        Ok(url.to_string())
+    }
+}
+
+/// Whether the indexer connects to its Postgres DB over TLS, for `--pg-sslmode`. These are the
+/// Postgres `sslmode` settings of the same name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PgSslMode {
+    /// Connect without TLS.
+    Disable,
+    /// Connect over TLS, without checking the server's certificate.
+    Require,
+    /// Connect over TLS, and check that the server's certificate is signed by a trusted CA (see
+    /// `--pg-sslrootcert`) and matches its host name.
+    VerifyFull,
+}
+
+impl PgSslMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PgSslMode::Disable => "disable",
+            PgSslMode::Require => "require",
+            PgSslMode::VerifyFull => "verify-full",
         }
     }
 }
 
+/// Output formats for `sui start --dump-keys`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum KeyDumpFormat {
+    /// `SUI_TEST_ADDRESS_<i>=<address>` and `SUI_TEST_PRIVATE_KEY_<i>=<suiprivkey>` lines, which
+    /// can be sourced by a shell.
+    Env,
+    /// A JSON array of `{"address", "privateKey"}` objects, with Bech32 (`suiprivkey`) keys.
+    Json,
+    /// One `<address> <private key>` line per account, with the raw private key hex-encoded.
+    Hex,
+}
+
+/// Ways for the fullnode started by `sui start` to dump executed checkpoints, for
+/// `--checkpoint-dump-format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CheckpointDumpFormat {
+    /// One file per checkpoint, in the data ingestion directory.
+    Files,
+    /// Checkpoints are not dumped.
+    None,
+}
+
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
 pub struct SuiEnvConfig {
@@ -159,6 +290,11 @@ pub struct SuiEnvConfig {
     /// The Sui environment to use. This must be present in the current config file.
     #[clap(long = "client.env")]
     env: Option<String>,
+    /// If the client config does not exist yet, create it with an environment called "custom"
+    /// that connects to this RPC server URL, instead of prompting for one. Takes precedence over
+    /// the `SUI_CONFIG_WITH_RPC_URL` environment variable.
+    #[clap(long = "rpc", value_name = "URL", value_hint = ValueHint::Url)]
+    rpc: Option<String>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -194,6 +330,12 @@ pub enum SuiCommand {
         #[clap(long = "network.config")]
         config_dir: Option<std::path::PathBuf>,
 
+        /// Directory to generate the network config, genesis and keystore in, and to start the
+        /// network from, instead of the Sui config default dir. It is created if it does not exist,
+        /// and a genesis is only generated if it does not hold a network config yet.
+        #[clap(long, conflicts_with_all = ["config_dir", "force_regenesis"])]
+        working_dir: Option<PathBuf>,
+
         /// A new genesis is created each time this flag is set, and state is not persisted between
         /// runs. Only use this flag when you want to start the network from scratch every time you
         /// run this command.
@@ -216,6 +358,22 @@ pub enum SuiCommand {
         )]
         with_faucet: Option<String>,
 
+        /// Serve WebSocket subscriptions to events and transactions, backed by the fullnode, with
+        /// default host and port: 0.0.0.0:9126. This flag accepts also a port, a host, or both
+        /// (e.g., 0.0.0.0:9126). Subscriptions are available at this address whether reads are
+        /// served by the fullnode or by the indexer.
+        /// When providing a specific value, please use the = sign between the flag and value:
+        /// `--with-event-ws=6126` or `--with-event-ws=0.0.0.0`, or `--with-event-ws=0.0.0.0:9126`
+        #[clap(
+            long,
+            default_missing_value = "0.0.0.0:9126",
+            num_args = 0..=1,
+            require_equals = true,
+            value_name = "EVENT_WS_HOST_PORT",
+            conflicts_with = "no_full_node",
+        )]
+        with_event_ws: Option<String>,
+
         #[clap(flatten)]
         indexer_feature_args: IndexerArgs,
 
@@ -223,12 +381,40 @@ pub enum SuiCommand {
         #[clap(long, default_value = "9000")]
         fullnode_rpc_port: u16,
 
+        /// Credentials for an authenticating proxy in front of the fullnode's RPC server, in the
+        /// format `username:password`. They are added to the `localnet` environment in the client
+        /// config, and used by the indexer, GraphQL service and any other client started by this
+        /// command. They are not printed in logs.
+        #[clap(
+            long,
+            value_name = "USER:PASS",
+            value_parser = parse_basic_auth,
+            conflicts_with = "no_full_node"
+        )]
+        fullnode_basic_auth: Option<String>,
+
         /// Set the epoch duration. Can only be used when `--force-regenesis` flag is passed or if
         /// there's no genesis config and one will be auto-generated. When this flag is not set but
         /// `--force-regenesis` is set, the epoch duration will be set to 60 seconds.
         #[clap(long)]
         epoch_duration_ms: Option<u64>,
 
+        /// Set the epoch duration, as a human-readable duration like `60s`, `5m` or `1h`. Behaves
+        /// like `--epoch-duration-ms`, which it cannot be combined with.
+        #[clap(
+            long,
+            value_name = "DURATION",
+            value_parser = parse_epoch_duration,
+            conflicts_with = "epoch_duration_ms"
+        )]
+        epoch_duration: Option<u64>,
+
+        /// Start the network at this protocol version instead of the latest one supported by this
+        /// binary. Can only be used when `--force-regenesis` flag is passed or if there's no
+        /// genesis config and one will be auto-generated.
+        #[clap(long, value_name = "N", value_parser = parse_protocol_version)]
+        protocol_version: Option<ProtocolVersion>,
+
         /// Make the fullnode dump executed checkpoints as files to this directory. This is
         /// incompatible with --no-full-node.
         ///
@@ -236,14 +422,141 @@ pub enum SuiCommand {
         #[clap(long, value_name = "DATA_INGESTION_DIR")]
         data_ingestion_dir: Option<PathBuf>,
 
+        /// Whether the fullnode dumps executed checkpoints. By default, checkpoints are only dumped
+        /// if --data-ingestion-dir or --with-indexer is set. `files` always dumps them, to
+        /// --data-ingestion-dir or else to a temporary directory, and `none` never does, which
+        /// cannot be combined with --data-ingestion-dir or an indexer. Checkpoints are dumped by
+        /// the fullnode, so this is incompatible with --no-full-node.
+        #[clap(long, value_name = "FORMAT", conflicts_with = "no_full_node")]
+        checkpoint_dump_format: Option<CheckpointDumpFormat>,
+
         /// Start the network without a fullnode
         #[clap(long = "no-full-node")]
         no_full_node: bool,
+
+        /// Do not start a network: only start the indexer, and the GraphQL service if
+        /// `--with-graphql` is set, against the existing fullnode at `--fullnode-url`. The indexer
+        /// reads the checkpoints that the fullnode dumps to `--data-ingestion-dir`, which is
+        /// required. `--fullnode-basic-auth` applies to this fullnode. Cannot be combined with
+        /// flags that configure the network.
+        #[clap(
+            long,
+            requires = "fullnode_url",
+            conflicts_with_all = [
+                "config_dir",
+                "working_dir",
+                "force_regenesis",
+                "with_faucet",
+                "with_event_ws",
+                "fullnode_rpc_port",
+                "checkpoint_dump_format",
+                "no_full_node",
+                "epoch_duration_ms",
+                "epoch_duration",
+                "protocol_version",
+                "committee_size",
+                "wait_for_ready",
+                "no_health_loop",
+                "dump_keys",
+                "print_protocol_flags",
+                "fuzz",
+                "rng_seed",
+                "second_network",
+            ]
+        )]
+        indexer_only: bool,
+
+        /// The URL of the fullnode's JSON-RPC server, e.g. `http://127.0.0.1:9000`, for
+        /// `--indexer-only`.
+        #[clap(long, value_name = "URL", requires = "indexer_only")]
+        fullnode_url: Option<String>,
         /// Set the number of validators in the network. If a genesis was already generated with a
         /// specific number of validators, this will not override it; the user should recreate the
         /// genesis with the desired number of validators.
         #[clap(long)]
         committee_size: Option<usize>,
+
+        /// Wait until the validators (and the indexer, GraphQL service and faucet, if enabled)
+        /// report healthy, print a summary of the running services and exit, instead of running the
+        /// network in the foreground.
+        ///
+        /// All services run inside this process, so they are shut down when the command returns.
+        /// This is meant as a readiness check for scripts and CI, not as a way to leave a network
+        /// running in the background.
+        #[clap(long)]
+        wait_for_ready: bool,
+
+        /// Print a summary of the running services and keep running until interrupted, without
+        /// checking the validators' health. By default, `sui start` exits if a validator is
+        /// unhealthy for several checks in a row; use this flag when a supervisor such as systemd
+        /// or docker manages the process instead.
+        #[clap(long, conflicts_with = "wait_for_ready")]
+        no_health_loop: bool,
+
+        /// Print the address and private key of every account funded at genesis, in the given
+        /// format. Only available with `--force-regenesis`, as these keys are generated for the
+        /// temporary network and must only be used for testing.
+        #[clap(long, value_enum, value_name = "FORMAT", requires = "force_regenesis")]
+        dump_keys: Option<KeyDumpFormat>,
+
+        /// Also write logs to the systemd journal. Only available on Linux; if the journal cannot
+        /// be reached, a warning is printed and logs only go to the console.
+        #[clap(long)]
+        log_journald: bool,
+
+        /// Also write logs to the local syslog daemon under the given facility. Only available on
+        /// Unix; if no syslog daemon can be reached, a warning is printed and logs only go to the
+        /// console.
+        #[clap(long, value_enum, value_name = "FACILITY")]
+        log_syslog: Option<SyslogFacility>,
+
+        /// Once the network has started, print the protocol version it is running at and which
+        /// protocol feature flags are enabled or disabled at that version, as JSON.
+        #[clap(long)]
+        print_protocol_flags: bool,
+
+        /// Once the network has started, send randomized transactions between the funded accounts
+        /// (transfers, coin splits and merges, and Move calls), and exit with an error if any of
+        /// them fails or the accounts' balances do not add up. Optionally takes a seed, to
+        /// reproduce an earlier run on a fresh network; otherwise a random seed is used and logged.
+        #[clap(
+            long,
+            value_name = "SEED",
+            num_args = 0..=1,
+            conflicts_with = "no_full_node"
+        )]
+        fuzz: Option<Option<u64>>,
+
+        /// The number of randomized transactions to send with `--fuzz`.
+        #[clap(long, default_value_t = 100, requires = "fuzz")]
+        fuzz_rounds: usize,
+
+        /// Generate the temporary network's keys from this 32-byte, hex-encoded seed instead of
+        /// from the operating system's randomness, so that every run with the same seed and
+        /// configuration uses the same keys and addresses. Only available with
+        /// `--force-regenesis`. For testing only: anyone who knows the seed knows every private
+        /// key.
+        #[clap(
+            long,
+            value_name = "HEX",
+            value_parser = parse_rng_seed,
+            requires = "force_regenesis"
+        )]
+        rng_seed: Option<[u8; 32]>,
+
+        /// Also start a second, independent network, for testing flows that span networks (such
+        /// as bridging, or clients with several environments). It has its own genesis and the
+        /// same committee size as the first network, and one fullnode, but no faucet, indexer or
+        /// GraphQL service. Its state is kept in a temporary directory that is removed when it
+        /// shuts down. Running a second network takes as much CPU, memory and disk space again as
+        /// the first one.
+        #[clap(long)]
+        second_network: bool,
+
+        /// The second network's fullnode serves JSON-RPC on `--fullnode-rpc-port` plus this
+        /// offset. Its validators pick free ports, like the first network's.
+        #[clap(long, default_value_t = 100, requires = "second_network")]
+        second_network_port_offset: u16,
     },
     #[clap(name = "network")]
     Network {
@@ -251,9 +564,37 @@ pub enum SuiCommand {
         config: Option<PathBuf>,
         #[clap(short, long, help = "Dump the public keys of all authorities")]
         dump_addresses: bool,
+        /// Dump the P2P external address and anemo peer ID of all authorities, e.g. to assemble a
+        /// list of seed peers.
+        #[clap(long)]
+        dump_peers: bool,
+        /// Print the chain identifier of the network, computed from its genesis checkpoint without
+        /// connecting to it.
+        #[clap(long)]
+        chain_id: bool,
+        /// Read the genesis for `--chain-id` from this genesis blob, instead of the network config.
+        #[clap(
+            long,
+            requires = "chain_id",
+            conflicts_with_all = ["config", "dump_addresses", "dump_peers", "validate"]
+        )]
+        genesis: Option<PathBuf>,
+        /// Print the `--dump-addresses`, `--dump-peers` and `--chain-id` output as JSON.
+        #[clap(long)]
+        json: bool,
+        /// Check that the network config is consistent, and fail with a list of its problems if it
+        /// is not: the validator set must be non-empty and match the genesis committee, and no two
+        /// validators may share a key or network address.
+        #[clap(long)]
+        validate: bool,
     },
     /// Bootstrap and initialize a new sui network
     #[clap(name = "genesis")]
+    #[clap(group(
+        ArgGroup::new("genesis_report")
+            .multiple(true)
+            .args(&["print_addresses", "dry_run"])
+    ))]
     Genesis {
         #[clap(long, help = "Start genesis with a given config file")]
         from_config: Option<PathBuf>,
@@ -268,6 +609,16 @@ pub enum SuiCommand {
         force: bool,
         #[clap(long = "epoch-duration-ms")]
         epoch_duration_ms: Option<u64>,
+        /// Create the genesis at this protocol version instead of the latest one supported by this
+        /// binary.
+        #[clap(long, value_name = "N", value_parser = parse_protocol_version)]
+        protocol_version: Option<ProtocolVersion>,
+        /// Create the genesis with the compiled system packages in this directory instead of the
+        /// ones built into this binary. The directory is laid out like a version directory of the
+        /// framework's bytecode snapshot (one BCS-encoded package per file), and must contain
+        /// every system package.
+        #[clap(long, value_name = "DIR", conflicts_with = "write_config")]
+        system_packages_dir: Option<PathBuf>,
         #[clap(
             long,
             value_name = "ADDR",
@@ -284,6 +635,57 @@ pub enum SuiCommand {
         /// Set number of validators in the network.
         #[clap(long)]
         committee_size: Option<usize>,
+        /// Also write a human-readable JSON summary of the genesis contents (the system state,
+        /// including validators and their stake, and every genesis object) to this path. The
+        /// genesis blob is written as usual.
+        #[clap(long, value_name = "PATH")]
+        genesis_json: Option<PathBuf>,
+        /// Generate validator, account and full node keys from this 32-byte, hex-encoded seed
+        /// instead of from the operating system's randomness, so that running genesis again with
+        /// the same seed and configuration produces the same keys and addresses. For testing
+        /// only: anyone who knows the seed knows every private key.
+        #[clap(long, value_name = "HEX", value_parser = parse_rng_seed)]
+        rng_seed: Option<[u8; 32]>,
+        /// Give validators unequal stakes, read from a YAML or JSON file containing a list of
+        /// stake amounts in MIST, one per validator. The list must have one entry for each
+        /// validator in the committee.
+        #[clap(long, value_name = "FILE", conflicts_with = "write_config")]
+        stake_distribution: Option<PathBuf>,
+        /// Name the validators, one name per validator in the committee. The names are used as
+        /// the validators' on-chain names, in their config file names, and in log output.
+        #[clap(
+            long,
+            value_name = "NAMES",
+            value_delimiter = ',',
+            conflicts_with = "write_config"
+        )]
+        validator_names: Option<Vec<String>>,
+        /// Once genesis is done, print the address of every account in the keystore and of every
+        /// validator, and the peer ID of the fullnode.
+        #[clap(long, conflicts_with = "write_config")]
+        print_addresses: bool,
+        /// With `--force`, keep the existing validators: build a new genesis for the same
+        /// committee, and leave the validators' config files in place. Their databases are still
+        /// removed. The committee size, if it is set, must match the existing network's.
+        #[clap(
+            long,
+            requires = "force",
+            conflicts_with_all = [
+                "write_config",
+                "from_config",
+                "benchmark_ips",
+                "stake_distribution",
+                "validator_names",
+            ]
+        )]
+        keep_network: bool,
+        /// Print the files that genesis would create, overwrite and delete, and the addresses that
+        /// `--print-addresses` would print, without changing anything on disk.
+        #[clap(long)]
+        dry_run: bool,
+        /// Print the `--print-addresses` or `--dry-run` output as JSON.
+        #[clap(long, requires = "genesis_report")]
+        json: bool,
     },
     GenesisCeremony(Ceremony),
     /// Sui keystore tool.
@@ -303,6 +705,11 @@ pub enum SuiCommand {
     Client {
         #[clap(flatten)]
         config: SuiEnvConfig,
+        /// Use the keystore at this path instead of the one referenced by the client config. The
+        /// flag takes precedence for this command only: the client config is never changed to
+        /// reference it.
+        #[clap(long)]
+        keystore_path: Option<PathBuf>,
         #[clap(subcommand)]
         cmd: Option<SuiClientCommands>,
         /// Return command outputs in json format.
@@ -310,6 +717,15 @@ pub enum SuiCommand {
         json: bool,
         #[clap(short = 'y', long = "yes")]
         accept_defaults: bool,
+        /// With `-y`, the scheme of the key generated if the client config does not exist yet:
+        /// ed25519 (0), secp256k1 (1) or secp256r1 (2). Defaults to ed25519.
+        #[clap(
+            long,
+            value_name = "SCHEME",
+            value_parser = parse_key_scheme,
+            requires = "accept_defaults"
+        )]
+        key_scheme: Option<SignatureScheme>,
     },
     /// A tool for validators and validator candidates.
     #[clap(name = "validator")]
@@ -317,6 +733,16 @@ pub enum SuiCommand {
         /// Sets the file storing the state of our user accounts (an empty one will be created if missing)
         #[clap(long = "client.config")]
         config: Option<PathBuf>,
+        /// Use the keystore at this path instead of the one referenced by the client config. The
+        /// flag takes precedence for this command only: the client config is never changed to
+        /// reference it.
+        #[clap(long)]
+        keystore_path: Option<PathBuf>,
+        /// If the client config does not exist yet, create it with an environment called
+        /// "custom" that connects to this RPC server URL, instead of prompting for one. Takes
+        /// precedence over the `SUI_CONFIG_WITH_RPC_URL` environment variable.
+        #[clap(long = "rpc", value_name = "URL", value_hint = ValueHint::Url)]
+        rpc: Option<String>,
         #[clap(subcommand)]
         cmd: Option<SuiValidatorCommand>,
         /// Return command outputs in json format.
@@ -324,6 +750,15 @@ pub enum SuiCommand {
         json: bool,
         #[clap(short = 'y', long = "yes")]
         accept_defaults: bool,
+        /// With `-y`, the scheme of the key generated if the client config does not exist yet:
+        /// ed25519 (0), secp256k1 (1) or secp256r1 (2). Defaults to ed25519.
+        #[clap(
+            long,
+            value_name = "SCHEME",
+            value_parser = parse_key_scheme,
+            requires = "accept_defaults"
+        )]
+        key_scheme: Option<SignatureScheme>,
     },
 
     /// Tool to build and test Move applications.
@@ -337,6 +772,16 @@ pub enum SuiCommand {
         /// Package build options
         #[clap(flatten)]
         build_config: BuildConfig,
+        /// Maximum time, in seconds, to wait for each network operation (such as connecting, or
+        /// downloading a package and its dependencies) of the commands that use the network
+        /// (`summary --package-id` and `build --dump-bytecode-as-base64`).
+        #[clap(
+            long,
+            global = true,
+            value_name = "SECS",
+            default_value_t = DEFAULT_MOVE_NETWORK_TIMEOUT_SECS
+        )]
+        timeout: u64,
         /// Subcommands.
         #[clap(subcommand)]
         cmd: sui_move::Command,
@@ -352,6 +797,13 @@ pub enum SuiCommand {
         client_config: Option<PathBuf>,
         #[clap(long = "bridge_committee.config")]
         bridge_committee_config_path: PathBuf,
+        /// Build each validator's registration transaction and print it as JSON, without signing
+        /// or executing it.
+        #[clap(long)]
+        dry_run: bool,
+        /// Gas budget of each validator's registration transaction, in MIST.
+        #[clap(long, default_value_t = DEFAULT_BRIDGE_REGISTRATION_GAS_BUDGET)]
+        bridge_gas_budget: u64,
     },
 
     /// Tool for Fire Drill
@@ -372,7 +824,21 @@ impl SuiCommand {
             SuiCommand::Network {
                 config,
                 dump_addresses,
+                dump_peers,
+                chain_id,
+                genesis,
+                json,
+                validate,
             } => {
+                if let Some(genesis_path) = genesis {
+                    let genesis = Genesis::new_from_file(&genesis_path);
+                    let genesis = genesis.genesis().map_err(|err| {
+                        err.context(format!("Cannot open genesis blob at {:?}", genesis_path))
+                    })?;
+                    print_chain_identifier(genesis, json)?;
+                    return Ok(());
+                }
+
                 let config_path = config.unwrap_or(sui_config_dir()?.join(SUI_NETWORK_CONFIG));
                 let config: NetworkConfig = PersistedConfig::read(&config_path).map_err(|err| {
                     err.context(format!(
@@ -381,39 +847,134 @@ impl SuiCommand {
                     ))
                 })?;
 
-                if dump_addresses {
-                    for validator in config.validator_configs() {
-                        println!(
-                            "{} - {}",
-                            validator.network_address(),
-                            validator.protocol_key_pair().public(),
+                if validate {
+                    let problems = validate_network_config(&config);
+                    if !problems.is_empty() {
+                        bail!(
+                            "Sui network config file at {:?} is invalid:\n{}",
+                            config_path,
+                            problems
+                                .iter()
+                                .map(|problem| format!("  - {problem}"))
+                                .collect::<Vec<_>>()
+                                .join("\n"),
                         );
                     }
+                    eprintln!(
+                        "Sui network config file at {:?} is valid ({} validators)",
+                        config_path,
+                        config.validator_configs().len(),
+                    );
+                }
+
+                if chain_id {
+                    print_chain_identifier(&config.genesis, json)?;
+                }
+
+                if dump_addresses {
+                    println!("{}", format_validator_addresses(&config, json)?);
+                }
+
+                if dump_peers {
+                    let peers: Vec<_> = config
+                        .validator_configs()
+                        .iter()
+                        .map(|validator| {
+                            let peer_id =
+                                anemo::PeerId(validator.network_key_pair().public().0.to_bytes());
+                            (peer_id, validator.p2p_config.external_address.clone())
+                        })
+                        .collect();
+
+                    if json {
+                        let peers: Vec<_> = peers
+                            .into_iter()
+                            .map(|(peer_id, address)| {
+                                json!({
+                                    "peerId": peer_id.to_string(),
+                                    "address": address.map(|a| a.to_string()),
+                                })
+                            })
+                            .collect();
+                        println!("{}", serde_json::to_string_pretty(&peers)?);
+                    } else {
+                        for (peer_id, address) in peers {
+                            match address {
+                                Some(address) => println!("{peer_id} - {address}"),
+                                None => println!("{peer_id} - <no external address>"),
+                            }
+                        }
+                    }
                 }
                 Ok(())
             }
             SuiCommand::Start {
                 config_dir,
+                working_dir,
                 force_regenesis,
                 with_faucet,
+                with_event_ws,
                 indexer_feature_args,
                 fullnode_rpc_port,
+                fullnode_basic_auth,
                 data_ingestion_dir,
+                checkpoint_dump_format,
                 no_full_node,
+                indexer_only,
+                fullnode_url,
                 epoch_duration_ms,
+                epoch_duration,
+                protocol_version,
                 committee_size,
+                wait_for_ready,
+                no_health_loop,
+                dump_keys,
+                // System log output is set up along with the rest of logging, in `main`.
+                log_journald: _,
+                log_syslog: _,
+                print_protocol_flags,
+                fuzz,
+                fuzz_rounds,
+                rng_seed,
+                second_network,
+                second_network_port_offset,
             } => {
-                start(
-                    config_dir.clone(),
+                if indexer_only {
+                    // `--indexer-only` requires `--fullnode-url`.
+                    let fullnode_url = fullnode_url.context("`--fullnode-url` is required")?;
+                    return start_indexer_only(
+                        fullnode_url,
+                        fullnode_basic_auth,
+                        indexer_feature_args,
+                        data_ingestion_dir,
+                    )
+                    .await;
+                }
+
+                start(StartArgs {
+                    config: config_dir.clone(),
+                    working_dir,
                     with_faucet,
+                    with_event_ws,
                     indexer_feature_args,
                     force_regenesis,
-                    epoch_duration_ms,
+                    epoch_duration_ms: epoch_duration_ms.or(epoch_duration),
+                    protocol_version,
                     fullnode_rpc_port,
+                    fullnode_basic_auth,
                     data_ingestion_dir,
+                    checkpoint_dump_format,
                     no_full_node,
                     committee_size,
-                )
+                    wait_for_ready,
+                    no_health_loop,
+                    dump_keys,
+                    print_protocol_flags,
+                    fuzz: fuzz.map(|seed| (seed.unwrap_or_else(rand::random), fuzz_rounds)),
+                    rng_seed,
+                    second_network_port_offset: second_network
+                        .then_some(second_network_port_offset),
+                })
                 .await?;
 
                 Ok(())
@@ -424,55 +985,96 @@ impl SuiCommand {
                 from_config,
                 write_config,
                 epoch_duration_ms,
+                protocol_version,
+                system_packages_dir,
                 benchmark_ips,
                 with_faucet,
                 committee_size,
+                genesis_json,
+                rng_seed,
+                stake_distribution,
+                validator_names,
+                print_addresses,
+                keep_network,
+                dry_run,
+                json,
             } => {
-                genesis(
+                genesis(GenesisArgs {
                     from_config,
                     write_config,
                     working_dir,
                     force,
                     epoch_duration_ms,
+                    protocol_version,
+                    system_packages_dir,
                     benchmark_ips,
                     with_faucet,
                     committee_size,
-                )
+                    genesis_json,
+                    rng_seed,
+                    stake_distribution,
+                    validator_names,
+                    print_addresses,
+                    keep_network,
+                    dry_run,
+                    json,
+                })
                 .await
             }
             SuiCommand::GenesisCeremony(cmd) => run(cmd),
             SuiCommand::KeyTool {
                 keystore_path,
                 json,
-                cmd,
+                mut cmd,
             } => {
+                // Asking for JSON output is taken as confirmation that the private key should be
+                // printed.
+                if let KeyToolCommand::Export { yes, .. } = &mut cmd {
+                    *yes |= json;
+                }
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
-                let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
+                let mut keystore = Keystore::from(open_keystore_file(&keystore_path)?);
                 cmd.execute(&mut keystore).await?.print(!json);
                 Ok(())
             }
             SuiCommand::Client {
                 config,
+                keystore_path,
                 cmd,
                 json,
                 accept_defaults,
+                key_scheme,
             } => {
                 let config_path = config
                     .config
                     .unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
-                prompt_if_no_config(&config_path, accept_defaults).await?;
+                prompt_if_no_config(&config_path, config.rpc, accept_defaults, key_scheme).await?;
                 if let Some(cmd) = cmd {
                     let mut context = WalletContext::new(&config_path)?;
                     if let Some(env_override) = config.env {
                         context = context.with_env_override(env_override);
                     }
+                    if let Some(keystore_path) = keystore_path {
+                        let keystore = Keystore::from(open_keystore_file(&keystore_path)?);
+                        context = context.with_keystore_override(keystore);
+                    }
                     if let Ok(client) = context.get_client().await {
                         if let Err(e) = client.check_api_version() {
                             eprintln!("{}", format!("[warning] {e}").yellow().bold());
                         }
                     }
-                    cmd.execute(&mut context).await?.print(!json);
+                    let result = cmd.execute(&mut context).await?;
+                    result.print(!json);
+                    // Report a mismatch found by `verify-source --package-id` through the exit
+                    // status, like the other verification modes, once the report is printed.
+                    if let SuiClientCommandResult::VerifySourceModules(output) = &result {
+                        ensure!(
+                            output.verified,
+                            "Source verification failed: the local build does not match package {}",
+                            output.package_id,
+                        );
+                    }
                 } else {
                     // Print help
                     let mut app: Command = SuiCommand::command();
@@ -483,13 +1085,20 @@ impl SuiCommand {
             }
             SuiCommand::Validator {
                 config,
+                keystore_path,
+                rpc,
                 cmd,
                 json,
                 accept_defaults,
+                key_scheme,
             } => {
                 let config_path = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
-                prompt_if_no_config(&config_path, accept_defaults).await?;
+                prompt_if_no_config(&config_path, rpc, accept_defaults, key_scheme).await?;
                 let mut context = WalletContext::new(&config_path)?;
+                if let Some(keystore_path) = keystore_path {
+                    let keystore = Keystore::from(open_keystore_file(&keystore_path)?);
+                    context = context.with_keystore_override(keystore);
+                }
                 if let Some(cmd) = cmd {
                     if let Ok(client) = context.get_client().await {
                         if let Err(e) = client.check_api_version() {
@@ -508,22 +1117,13 @@ impl SuiCommand {
             SuiCommand::Move {
                 package_path,
                 build_config,
+                timeout,
                 cmd,
                 config: client_config,
             } => {
+                let timeout = std::time::Duration::from_secs(timeout);
                 match cmd {
-                    sui_move::Command::Summary(mut s) if s.package_id.is_some() => {
-                        let (_, client) = get_chain_id_and_client(
-                            client_config,
-                            "sui move summary --package-id <object_id>",
-                        )
-                        .await?;
-                        let Some(client) = client else {
-                            bail!("`sui move summary --package-id <object_id>` requires a configured network");
-                        };
-
-                        let read_api = client.read_api();
-
+                    sui_move::Command::Summary(mut s) if !s.package_id.is_empty() => {
                         // If they didn't run with `--bytecode` correct this for them but warn them
                         // to let them know that we are changing it.
                         if !s.summary.bytecode {
@@ -533,27 +1133,85 @@ impl SuiCommand {
                             );
                             s.summary.bytecode = true;
                         }
-                        let root_package_id = s
-                            .package_id
-                            .as_ref()
-                            .expect("Safe since we checked in the match statement");
-
-                        // Create a tempdir to download the package bytes to, and then download the
-                        // packages bytes there.
-                        let package_bytes_location = tempdir()?;
-                        let path = package_bytes_location.path();
-                        let package_metadata =
-                            download_package_and_deps_under(read_api, path, *root_package_id)
-                                .await?;
-
-                        // Now produce the summary, pointing at the tempdir containing the package
-                        // bytes.
-                        execute_move_command(
-                            Some(path),
-                            build_config,
-                            sui_move::Command::Summary(s),
-                            Some(sui_move::CommandMeta::Summary(package_metadata)),
+                        let package_ids = s.package_id.clone();
+                        ensure!(
+                            package_ids.len() == 1 || s.package_version.is_none(),
+                            "`--package-version` can only be used when summarizing a single package"
+                        );
+
+                        // With `--offline`, packages are summarized from the bytes downloaded by a
+                        // previous run under `--from-dir`, without connecting to the network.
+                        let client = if s.offline {
+                            None
+                        } else {
+                            let (_, client) = get_chain_id_and_client(
+                                client_config,
+                                "sui move summary --package-id <object_id>",
+                                timeout,
+                            )
+                            .await?;
+                            let Some(client) = client else {
+                                bail!("`sui move summary --package-id <object_id>` requires a configured network");
+                            };
+                            Some(client)
+                        };
+                        let read_api = client.as_ref().map(|client| client.read_api());
+
+                        // A single package is summarized straight into `--output-directory`, and
+                        // each of several packages into its own sub-directory of it.
+                        if let [package_id] = package_ids[..] {
+                            summarize_on_chain_package(
+                                &s,
+                                package_id,
+                                s.summary.output_directory.clone(),
+                                build_config,
+                                read_api,
+                                timeout,
+                            )
+                            .await?;
+                            return Ok(());
+                        }
+
+                        let output_dir = PathBuf::from(&s.summary.output_directory);
+                        let mut summarized = BTreeMap::new();
+                        let mut failed = vec![];
+                        for package_id in &package_ids {
+                            let package_output_dir = output_dir
+                                .join(package_id.to_canonical_string(/* with_prefix */ true));
+                            let result = summarize_on_chain_package(
+                                &s,
+                                *package_id,
+                                package_output_dir.to_string_lossy().into_owned(),
+                                build_config.clone(),
+                                read_api,
+                                timeout,
+                            )
+                            .await
+                            .with_context(|| format!("Failed to summarize package {package_id}"));
+                            match result {
+                                Ok(metadata) => {
+                                    summarized.insert(*package_id, metadata);
+                                }
+                                Err(err) if s.keep_going => {
+                                    eprintln!("{}", format!("{err:#}").red());
+                                    failed.push(package_id.to_string());
+                                }
+                                Err(err) => return Err(err),
+                            }
+                        }
+
+                        fs::create_dir_all(&output_dir)?;
+                        fs::write(
+                            output_dir.join(PACKAGE_SUMMARIES_METADATA_FILE),
+                            serde_json::to_string_pretty(&summarized)?,
                         )?;
+                        ensure!(
+                            failed.is_empty(),
+                            "Failed to summarize {} of {} packages: {}",
+                            failed.len(),
+                            package_ids.len(),
+                            failed.join(", "),
+                        );
                         return Ok(());
                     }
                     sui_move::Command::Build(build) if build.dump_bytecode_as_base64 => {
@@ -562,14 +1220,18 @@ impl SuiCommand {
                         // case, we should resolve the correct addresses for the respective chain
                         // (e.g., testnet, mainnet) from the Move.lock under automated address management.
                         // In addition, tree shaking also requires a network as it needs to fetch
-                        // on-chain linkage table of package dependencies.
+                        // on-chain linkage table of package dependencies. With --chain, addresses
+                        // are resolved for the given chain, but tree shaking is skipped.
                         let (chain_id, client) = if build.ignore_chain {
                             // for tests it's useful to ignore the chain id!
                             (None, None)
+                        } else if let Some(chain_id) = &build.chain {
+                            (Some(chain_id.clone()), None)
                         } else {
                             get_chain_id_and_client(
                                 client_config,
                                 "sui move build --dump-bytecode-as-base64",
+                                timeout,
                             )
                             .await?
                         };
@@ -590,8 +1252,12 @@ impl SuiCommand {
                         };
 
                         if let Some(client) = &client {
-                            let protocol_config =
-                                client.read_api().get_protocol_config(None).await?;
+                            let protocol_config = with_network_timeout(
+                                timeout,
+                                "fetching the protocol config",
+                                client.read_api().get_protocol_config(None),
+                            )
+                            .await?;
                             build_config.implicit_dependencies =
                                 implicit_deps_for_protocol_version(
                                     protocol_config.protocol_version,
@@ -628,8 +1294,12 @@ impl SuiCommand {
                         }
 
                         if let Some(client) = client {
-                            pkg_tree_shake(client.read_api(), with_unpublished_deps, &mut pkg)
-                                .await?;
+                            with_network_timeout(
+                                timeout,
+                                "fetching the linkage of the package's dependencies",
+                                pkg_tree_shake(client.read_api(), with_unpublished_deps, &mut pkg),
+                            )
+                            .await?;
                         }
 
                         println!(
@@ -638,6 +1308,7 @@ impl SuiCommand {
                                 "modules": pkg.get_package_base64(with_unpublished_deps),
                                 "dependencies": pkg.get_dependency_storage_package_ids(),
                                 "digest": pkg.get_package_digest(with_unpublished_deps),
+                                "dependency_digests": pkg.get_dependency_digests(),
                             })
                         );
                         return Ok(());
@@ -650,6 +1321,8 @@ impl SuiCommand {
                 network_config,
                 client_config,
                 bridge_committee_config_path,
+                dry_run,
+                bridge_gas_budget,
             } => {
                 // Load the config of the Sui authority.
                 let network_config_path = network_config
@@ -680,7 +1353,12 @@ impl SuiCommand {
                 }
                 let rgp = context.get_reference_gas_price().await?;
                 let rpc_url = &context.get_active_env()?.rpc;
-                println!("rpc_url: {}", rpc_url);
+                if dry_run {
+                    // Keep stdout for the JSON output.
+                    eprintln!("rpc_url: {}", rpc_url);
+                } else {
+                    println!("rpc_url: {}", rpc_url);
+                }
                 let bridge_metrics = Arc::new(BridgeMetrics::new_for_testing());
                 let sui_bridge_client = SuiBridgeClient::new(rpc_url, bridge_metrics).await?;
                 let bridge_arg = sui_bridge_client
@@ -692,28 +1370,30 @@ impl SuiCommand {
                         .bridge_authority_port_and_key_path
                         .len()
                 );
-                for node_config in network_config.validator_configs() {
-                    let account_kp = node_config.account_key_pair.keypair();
-                    context.add_account(None, account_kp.copy());
+                // Validators' keys are only needed to sign, which a dry run does not do.
+                if !dry_run {
+                    for node_config in network_config.validator_configs() {
+                        let account_kp = node_config.account_key_pair.keypair();
+                        context.add_account(None, account_kp.copy());
+                    }
                 }
 
                 let context = context;
                 let mut tasks = vec![];
-                for (node_config, (port, key_path)) in network_config
+                let mut dry_run_txs = vec![];
+                for (node_config, authority) in network_config
                     .validator_configs()
                     .iter()
                     .zip(bridge_committee_config.bridge_authority_port_and_key_path)
                 {
+                    let http_url = authority.url()?;
                     let account_kp = node_config.account_key_pair.keypair();
                     let sui_address = SuiAddress::from(&account_kp.public());
                     let gas_obj_ref = context
                         .get_one_gas_object_owned_by_address(sui_address)
                         .await?
                         .expect("Validator does not own any gas objects");
-                    let kp = match read_key(&key_path, true)? {
-                        SuiKeyPair::Secp256k1(key) => key,
-                        _ => unreachable!("we required secp256k1 key in `read_key`"),
-                    };
+                    let kp = read_bridge_authority_key(authority.key_path())?;
 
                     // build registration tx
                     let tx = build_committee_register_transaction(
@@ -721,14 +1401,38 @@ impl SuiCommand {
                         &gas_obj_ref,
                         bridge_arg,
                         kp.public().as_bytes().to_vec(),
-                        &format!("http://127.0.0.1:{port}"),
+                        &http_url,
                         rgp,
-                        1000000000,
+                        bridge_gas_budget,
                     )
                     .unwrap();
+
+                    if dry_run {
+                        dry_run_txs.push(json!({
+                            "validatorAddress": sui_address,
+                            "gasObject": {
+                                "objectId": gas_obj_ref.0,
+                                "version": gas_obj_ref.1,
+                                "digest": gas_obj_ref.2,
+                            },
+                            "bridgeArg": bridge_arg,
+                            "bridgeAuthorityPublicKey": Hex::encode(kp.public().as_bytes()),
+                            "httpUrl": http_url,
+                            "gasPrice": rgp,
+                            "gasBudget": bridge_gas_budget,
+                            "txBytes": Base64::encode(bcs::to_bytes(&tx)?),
+                        }));
+                        continue;
+                    }
+
                     let signed_tx = context.sign_transaction(&tx);
                     tasks.push(context.execute_transaction_must_succeed(signed_tx));
                 }
+
+                if dry_run {
+                    println!("{}", serde_json::to_string_pretty(&dry_run_txs)?);
+                    return Ok(());
+                }
                 futures::future::join_all(tasks).await;
                 Ok(())
             }
@@ -739,20 +1443,109 @@ impl SuiCommand {
             }
         }
     }
+
+    /// Whether the command was asked to output JSON, in which case errors from `execute` should
+    /// be output as JSON too, by `format_error_json`.
+    pub fn json_output(&self) -> bool {
+        match self {
+            SuiCommand::Network { json, .. }
+            | SuiCommand::Genesis { json, .. }
+            | SuiCommand::KeyTool { json, .. }
+            | SuiCommand::Client { json, .. }
+            | SuiCommand::Validator { json, .. } => *json,
+            _ => false,
+        }
+    }
 }
 
-/// Starts a local network with the given configuration.
-async fn start(
+/// The identifier of the chain started from `genesis`, derived from its genesis checkpoint, as
+/// reported by a node's `get_chain_identifier`.
+pub fn genesis_chain_identifier(genesis: &sui_config::genesis::Genesis) -> ChainIdentifier {
+    ChainIdentifier::from(*genesis.checkpoint().digest())
+}
+
+fn print_chain_identifier(
+    genesis: &sui_config::genesis::Genesis,
+    json: bool,
+) -> Result<(), anyhow::Error> {
+    let chain_id = genesis_chain_identifier(genesis);
+    if json {
+        let output = json!({
+            "chainIdentifier": chain_id.to_string(),
+            "genesisCheckpointDigest": genesis.checkpoint().digest().to_string(),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("{chain_id}");
+    }
+    Ok(())
+}
+
+/// Format an error from `SuiCommand::execute` as a JSON object of the form
+/// `{ "error": { "message": ..., "context": [...] } }`, where `message` is the outermost message
+/// of the error, and `context` lists the errors that caused it, outermost first.
+pub fn format_error_json(err: &anyhow::Error) -> String {
+    let context: Vec<String> = err.chain().skip(1).map(|e| e.to_string()).collect();
+    json!({
+        "error": {
+            "message": err.to_string(),
+            "context": context,
+        }
+    })
+    .to_string()
+}
+
+/// The options of `sui start`, as passed to `start`.
+struct StartArgs {
     config: Option<PathBuf>,
+    working_dir: Option<PathBuf>,
     with_faucet: Option<String>,
+    with_event_ws: Option<String>,
     indexer_feature_args: IndexerArgs,
     force_regenesis: bool,
     epoch_duration_ms: Option<u64>,
+    protocol_version: Option<ProtocolVersion>,
     fullnode_rpc_port: u16,
-    mut data_ingestion_dir: Option<PathBuf>,
+    fullnode_basic_auth: Option<String>,
+    data_ingestion_dir: Option<PathBuf>,
+    checkpoint_dump_format: Option<CheckpointDumpFormat>,
     no_full_node: bool,
     committee_size: Option<usize>,
-) -> Result<(), anyhow::Error> {
+    wait_for_ready: bool,
+    no_health_loop: bool,
+    dump_keys: Option<KeyDumpFormat>,
+    print_protocol_flags: bool,
+    fuzz: Option<(u64, usize)>,
+    rng_seed: Option<[u8; 32]>,
+    second_network_port_offset: Option<u16>,
+}
+
+/// Starts a local network with the given configuration.
+async fn start(args: StartArgs) -> Result<(), anyhow::Error> {
+    let StartArgs {
+        config,
+        working_dir,
+        with_faucet,
+        with_event_ws,
+        indexer_feature_args,
+        force_regenesis,
+        epoch_duration_ms,
+        protocol_version,
+        fullnode_rpc_port,
+        fullnode_basic_auth,
+        mut data_ingestion_dir,
+        checkpoint_dump_format,
+        no_full_node,
+        committee_size,
+        wait_for_ready,
+        no_health_loop,
+        dump_keys,
+        print_protocol_flags,
+        fuzz,
+        rng_seed,
+        second_network_port_offset,
+    } = args;
+
     if force_regenesis {
         ensure!(
             config.is_none(),
@@ -760,17 +1553,19 @@ async fn start(
         );
     }
 
+    let pg_address = indexer_feature_args.pg_address()?;
     let IndexerArgs {
         mut with_indexer,
         with_graphql,
-        pg_port,
-        pg_host,
-        pg_db_name,
-        pg_user,
-        pg_password,
+        graphql_config,
+        ..
     } = indexer_feature_args;
 
-    let pg_address = format!("postgres://{pg_user}:{pg_password}@{pg_host}:{pg_port}/{pg_db_name}");
+    // Read the GraphQL config up front, so that a bad config fails before anything starts.
+    let graphql_service_config = match graphql_config {
+        Some(path) => read_graphql_service_config(&path)?,
+        None => ServiceConfig::test_defaults(),
+    };
 
     if with_graphql.is_some() {
         with_indexer = Some(with_indexer.unwrap_or_default());
@@ -783,7 +1578,19 @@ async fn start(
         );
     }
 
-    if epoch_duration_ms.is_some() && genesis_blob_exists(config.clone()) && !force_regenesis {
+    let event_ws_address = with_event_ws
+        .map(|input| parse_host_port(input, DEFAULT_EVENT_WS_PORT))
+        .transpose()
+        .map_err(|_| anyhow!("Invalid event WebSocket host and port"))?;
+    if event_ws_address.is_some() {
+        ensure!(
+            !no_full_node,
+            "Cannot serve event subscriptions without a fullnode."
+        );
+    }
+
+    let genesis_dir = config.clone().or_else(|| working_dir.clone());
+    if epoch_duration_ms.is_some() && genesis_blob_exists(genesis_dir.clone()) && !force_regenesis {
         bail!(
             "Epoch duration can only be set when passing the `--force-regenesis` flag, or when \
             there is no genesis configuration in the default Sui configuration folder or the given \
@@ -791,7 +1598,15 @@ async fn start(
         );
     }
 
-    let mut swarm_builder = Swarm::builder();
+    if protocol_version.is_some() && genesis_blob_exists(genesis_dir) && !force_regenesis {
+        bail!(
+            "Protocol version can only be set when passing the `--force-regenesis` flag, or when \
+            there is no genesis configuration in the default Sui configuration folder or the given \
+            network.config argument.",
+        );
+    }
+
+    let mut swarm_builder = Swarm::builder().rng(genesis_rng(rng_seed)?);
 
     // If this is set, then no data will be persisted between runs, and a new genesis will be
     // generated each run.
@@ -806,6 +1621,9 @@ async fn start(
         swarm_builder = swarm_builder.with_genesis_config(genesis_config);
         let epoch_duration_ms = epoch_duration_ms.unwrap_or(DEFAULT_EPOCH_DURATION_MS);
         swarm_builder = swarm_builder.with_epoch_duration_ms(epoch_duration_ms);
+        if let Some(protocol_version) = protocol_version {
+            swarm_builder = swarm_builder.with_protocol_version(protocol_version);
+        }
         mysten_common::tempdir()?.keep()
     } else {
         // If the config path looks like a YAML file, it is treated as if it is the network.yaml
@@ -850,20 +1668,26 @@ async fn start(
             }
 
             None => {
-                let sui_config = sui_config_dir()?;
+                let sui_config = match working_dir {
+                    Some(dir) => {
+                        fs::create_dir_all(&dir).map_err(|err| {
+                            anyhow!(err)
+                                .context(format!("Cannot create working dir {}", dir.display()))
+                        })?;
+                        dir
+                    }
+                    None => sui_config_dir()?,
+                };
                 let network_config = sui_config.join(SUI_NETWORK_CONFIG);
 
                 if !network_config.exists() {
-                    genesis(
-                        None,
-                        None,
-                        None,
-                        false,
+                    genesis(GenesisArgs {
+                        working_dir: Some(sui_config.clone()),
                         epoch_duration_ms,
-                        None,
-                        false,
+                        protocol_version,
                         committee_size,
-                    )
+                        ..Default::default()
+                    })
                     .await
                     .map_err(|_| {
                         anyhow!(
@@ -913,8 +1737,27 @@ async fn start(
     // the indexer requires to set the fullnode's data ingestion directory
     // note that this overrides the default configuration that is set when running the genesis
     // command, which sets data_ingestion_dir to None.
-    if with_indexer.is_some() && data_ingestion_dir.is_none() {
-        data_ingestion_dir = Some(mysten_common::tempdir()?.keep())
+    match checkpoint_dump_format {
+        Some(CheckpointDumpFormat::None) => {
+            ensure!(
+                data_ingestion_dir.is_none(),
+                "Cannot set a data ingestion directory when not dumping checkpoints."
+            );
+            ensure!(
+                with_indexer.is_none(),
+                "Cannot start the indexer without dumping checkpoints, which it reads."
+            );
+        }
+        Some(CheckpointDumpFormat::Files) => {
+            if data_ingestion_dir.is_none() {
+                data_ingestion_dir = Some(mysten_common::tempdir()?.keep())
+            }
+        }
+        None => {
+            if with_indexer.is_some() && data_ingestion_dir.is_none() {
+                data_ingestion_dir = Some(mysten_common::tempdir()?.keep())
+            }
+        }
     }
 
     if let Some(ref dir) = data_ingestion_dir {
@@ -930,6 +1773,10 @@ async fn start(
         swarm_builder = swarm_builder
             .with_fullnode_count(1)
             .with_fullnode_rpc_addr(fullnode_url);
+        if event_ws_address.is_some() {
+            // Subscriptions are only served over WebSocket connections.
+            swarm_builder = swarm_builder.with_fullnode_jsonrpc_server_type(ServerType::Both);
+        }
     }
 
     let mut swarm = swarm_builder.build();
@@ -938,88 +1785,148 @@ async fn start(
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
     info!("Cluster started");
 
+    if let Some(format) = dump_keys {
+        let keys: Vec<_> = swarm
+            .config()
+            .account_keys
+            .iter()
+            .map(|kp| SuiKeyPair::Ed25519(kp.copy()))
+            .collect();
+        eprintln!(
+            "{}",
+            "[warning] The following private keys were generated for this temporary network and \
+             are printed in plain text. Only use them for testing, never to hold real funds."
+                .yellow()
+                .bold()
+        );
+        println!("{}", format_funded_keys(&keys, format)?);
+    }
+
+    if print_protocol_flags {
+        let version = swarm
+            .config()
+            .genesis
+            .sui_system_object()
+            .protocol_version();
+        let protocol_config =
+            ProtocolConfig::get_for_version(ProtocolVersion::new(version), Chain::Unknown);
+        println!("{}", format_protocol_flags(&protocol_config)?);
+    }
+
+    let event_ws_address = match event_ws_address {
+        Some(address) => {
+            info!("Starting the event WebSocket endpoint at {address}");
+            let address = start_event_ws_bridge(address, fullnode_url).await?;
+            if !wait_for_ready {
+                println!("Event WebSocket: ws://{address}");
+            }
+            Some(address)
+        }
+        None => None,
+    };
+
+    let second_network = match second_network_port_offset {
+        Some(offset) => {
+            let port = fullnode_rpc_port
+                .checked_add(offset)
+                .ok_or_else(|| anyhow!("The second network's fullnode RPC port is out of range"))?;
+            info!("Starting the second network");
+            let (second_swarm, address) = launch_second_network(
+                swarm.validator_nodes().count(),
+                epoch_duration_ms.unwrap_or(DEFAULT_EPOCH_DURATION_MS),
+                protocol_version,
+                port,
+            )
+            .await?;
+            if !wait_for_ready {
+                print_second_network(&second_swarm, address);
+            }
+            Some((second_swarm, address))
+        }
+        None => None,
+    };
+
     // the indexer requires a fullnode url with protocol specified
     let fullnode_url = format!("http://{}", fullnode_url);
     info!("Fullnode URL: {}", fullnode_url);
 
-    if let Some(input) = with_indexer {
-        let indexer_address = parse_host_port(input, DEFAULT_INDEXER_PORT)
-            .map_err(|_| anyhow!("Invalid indexer host and port"))?;
-        info!("Starting the indexer service at {indexer_address}");
-        // Start in reader mode
-        start_indexer_jsonrpc_for_testing(
-            pg_address.clone(),
-            fullnode_url.clone(),
-            indexer_address.to_string(),
-            None,
-        )
-        .await;
-        info!("Indexer started in reader mode");
-        start_indexer_writer_for_testing(
-            pg_address.clone(),
-            None,
-            None,
-            // We ensured above that this is set to something if --with-indexer is set
-            data_ingestion_dir,
-            None,
-            None, /* start_checkpoint */
-            None, /* end_checkpoint */
-        )
-        .await;
-        info!("Indexer started in writer mode");
-    }
-
-    if let Some(input) = with_graphql {
-        let graphql_address = parse_host_port(input, DEFAULT_GRAPHQL_PORT)
-            .map_err(|_| anyhow!("Invalid graphql host and port"))?;
-        tracing::info!("Starting the GraphQL service at {graphql_address}");
-        let graphql_connection_config = ConnectionConfig {
-            port: graphql_address.port(),
-            host: graphql_address.ip().to_string(),
-            db_url: pg_address,
-            ..Default::default()
-        };
+    // Services that only take a URL get the credentials inside of it, so it must not be logged.
+    let authenticated_fullnode_url = match &fullnode_basic_auth {
+        Some(basic_auth) => {
+            info!("Fullnode basic auth: {}", redact_basic_auth(basic_auth));
+            if !force_regenesis {
+                set_localnet_basic_auth(&config_dir, basic_auth)?;
+            }
+            url_with_basic_auth(&fullnode_url, basic_auth)?
+        }
+        None => fullnode_url.clone(),
+    };
 
-        start_graphql_server_with_fn_rpc(
-            graphql_connection_config,
-            Some(fullnode_url.clone()),
-            None, // it will be initialized by default
-            ServiceConfig::test_defaults(),
-        )
-        .await;
-        info!("GraphQL started");
+    if let Some((seed, rounds)) = fuzz {
+        let mut builder = SuiClientBuilder::default();
+        if let Some((username, password)) = fullnode_basic_auth
+            .as_ref()
+            .and_then(|basic_auth| basic_auth.split_once(':'))
+        {
+            builder = builder.basic_auth(username, password);
+        }
+        let client = builder.build(&fullnode_url).await?;
+        let keys: Vec<_> = swarm
+            .config()
+            .account_keys
+            .iter()
+            .map(|kp| SuiKeyPair::Ed25519(kp.copy()))
+            .collect();
+        run_fuzz(&client, &keys, seed, rounds).await?;
     }
 
-    if let Some(input) = with_faucet {
-        let faucet_address = parse_host_port(input, DEFAULT_FAUCET_PORT)
-            .map_err(|_| anyhow!("Invalid faucet host and port"))?;
+    let (indexer_address, graphql_address) = start_indexer_and_graphql(
+        with_indexer,
+        with_graphql,
+        pg_address,
+        &authenticated_fullnode_url,
+        // We ensured above that this is set to something if --with-indexer is set
+        data_ingestion_dir,
+        graphql_service_config,
+    )
+    .await?;
+
+    let faucet_address = with_faucet
+        .map(|input| parse_host_port(input, DEFAULT_FAUCET_PORT))
+        .transpose()
+        .map_err(|_| anyhow!("Invalid faucet host and port"))?;
+    let mut faucet_task = None;
+    if let Some(faucet_address) = faucet_address {
         tracing::info!("Starting the faucet service at {faucet_address}");
 
-        let host_ip = match faucet_address {
-            SocketAddr::V4(addr) => *addr.ip(),
-            _ => bail!("Faucet configuration requires an IPv4 address"),
-        };
-
         let config = FaucetConfig {
-            host_ip,
+            host_ip: faucet_address.ip(),
             port: faucet_address.port(),
             amount: DEFAULT_FAUCET_MIST_AMOUNT,
             ..Default::default()
         };
 
         if force_regenesis {
-            let kp = swarm.config_mut().account_keys.swap_remove(0);
+            // Import every account funded at genesis, so that all of them can be used, with the
+            // first one (which the faucet dispenses from) as the active address.
+            let account_keys = &swarm.config().account_keys;
             let keystore_path = config_dir.join(SUI_KEYSTORE_FILENAME);
             let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path).unwrap());
-            let address: SuiAddress = kp.public().into();
-            keystore.add_key(None, SuiKeyPair::Ed25519(kp)).unwrap();
+            let address: SuiAddress = account_keys
+                .first()
+                .context("No accounts were funded at genesis")?
+                .public()
+                .into();
+            for kp in account_keys {
+                keystore.add_key(None, SuiKeyPair::Ed25519(kp.copy()))?;
+            }
             SuiClientConfig {
                 keystore,
                 envs: vec![SuiEnv {
                     alias: "localnet".to_string(),
                     rpc: fullnode_url,
                     ws: None,
-                    basic_auth: None,
+                    basic_auth: fullnode_basic_auth.clone(),
                 }],
                 active_address: Some(address),
                 active_env: Some("localnet".to_string()),
@@ -1040,119 +1947,518 @@ async fn start(
             config,
         });
 
-        start_faucet(app_state).await?;
+        if wait_for_ready || no_health_loop {
+            // Serve the faucet in the background, so that we get to check readiness or print the
+            // summary below, keeping hold of it to notice if it stops.
+            faucet_task = Some(tokio::spawn(start_faucet(app_state)));
+        } else {
+            start_faucet(app_state).await?;
+        }
+    }
+
+    if wait_for_ready {
+        tokio::select! {
+            result = wait_for_services_ready(
+                &swarm,
+                indexer_address,
+                graphql_address,
+                faucet_address,
+            ) => result?,
+            err = faucet_stopped(&mut faucet_task) => return Err(err),
+        }
+        if let Some((second_swarm, _)) = &second_network {
+            wait_for_services_ready(second_swarm, None, None, None).await?;
+        }
+        println!("Network is ready.");
+    }
+
+    if wait_for_ready || no_health_loop {
+        println!("Validators: {}", swarm.validator_nodes().count());
+        if !no_full_node {
+            println!("Fullnode RPC: {fullnode_url}");
+        }
+        if let Some(address) = indexer_address {
+            println!("Indexer: http://{address}");
+        }
+        if let Some(address) = graphql_address {
+            println!("GraphQL: http://{address}");
+        }
+        if let Some(address) = event_ws_address {
+            println!("Event WebSocket: ws://{address}");
+        }
+        if let Some(address) = faucet_address {
+            println!("Faucet: http://{address}");
+        }
+        println!("Config directory: {}", config_dir.display());
+        if let Some((second_swarm, address)) = &second_network {
+            print_second_network(second_swarm, *address);
+        }
+    }
+
+    if wait_for_ready {
+        return Ok(());
+    }
+
+    if no_health_loop {
+        // Leave it to whoever supervises this process to notice if the network goes down.
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            err = faucet_stopped(&mut faucet_task) => return Err(err),
+        }
+        info!("Shutting down");
+        return Ok(());
     }
 
     let mut interval = tokio::time::interval(std::time::Duration::from_secs(3));
-    let mut unhealthy_cnt = 0;
+    let mut unhealthy_cycles = 0;
     loop {
-        for node in swarm.validator_nodes() {
-            if let Err(err) = node.health_check(true).await {
-                unhealthy_cnt += 1;
-                if unhealthy_cnt > 3 {
-                    // The network could temporarily go down during reconfiguration.
-                    // If we detect a failed validator 3 times in a row, give up.
-                    return Err(err.into());
-                }
-                // Break the inner loop so that we could retry latter.
-                break;
-            } else {
-                unhealthy_cnt = 0;
+        let second_validators = second_network
+            .iter()
+            .flat_map(|(second_swarm, _)| second_swarm.validator_nodes());
+        // Check every validator at once, so that a cycle takes as long as the slowest validator,
+        // rather than all of them together.
+        let checks = swarm
+            .validator_nodes()
+            .chain(second_validators)
+            .map(|node| async move { (node.name(), node.health_check(true).await) });
+        let mut unhealthy: Vec<_> = futures::future::join_all(checks)
+            .await
+            .into_iter()
+            .filter_map(|(name, result)| Some((name, result.err()?)))
+            .collect();
+
+        if unhealthy.is_empty() {
+            unhealthy_cycles = 0;
+        } else {
+            for (name, err) in &unhealthy {
+                tracing::warn!("Validator {} is unhealthy: {err}", name.concise());
+            }
+            unhealthy_cycles += 1;
+            if unhealthy_cycles > 3 {
+                // The network could temporarily go down during reconfiguration. If any validator
+                // failed its check in more than 3 cycles in a row, give up.
+                let (_, err) = unhealthy.swap_remove(0);
+                return Err(err.into());
             }
         }
 
-        interval.tick().await;
+        // Shut both networks down, rather than leaving it to the process exiting, so that the second
+        // network's temporary directory is removed.
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tokio::signal::ctrl_c() => {
+                info!("Shutting down");
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Launch the network for `sui start --second-network`: a network with its own genesis and
+/// `committee_size` validators, in a temporary directory that is removed when it shuts down, with
+/// a fullnode serving JSON-RPC on `fullnode_rpc_port`. Returns the swarm and the fullnode's
+/// JSON-RPC address.
+pub async fn launch_second_network(
+    committee_size: usize,
+    epoch_duration_ms: u64,
+    protocol_version: Option<ProtocolVersion>,
+    fullnode_rpc_port: u16,
+) -> Result<(Swarm, SocketAddr), anyhow::Error> {
+    let committee_size = NonZeroUsize::new(committee_size)
+        .ok_or_else(|| anyhow!("Committee size must be at least 1."))?;
+    let mut fullnode_address = sui_config::node::default_json_rpc_address();
+    fullnode_address.set_port(fullnode_rpc_port);
+
+    let mut swarm_builder = Swarm::builder()
+        .committee_size(committee_size)
+        .with_genesis_config(GenesisConfig::custom_genesis(1, 100))
+        .with_epoch_duration_ms(epoch_duration_ms)
+        .with_fullnode_count(1)
+        .with_fullnode_rpc_addr(fullnode_address);
+    if let Some(protocol_version) = protocol_version {
+        swarm_builder = swarm_builder.with_protocol_version(protocol_version);
     }
+
+    let mut swarm = swarm_builder.build();
+    swarm.launch().await?;
+    Ok((swarm, fullnode_address))
 }
 
-async fn genesis(
+fn print_second_network(swarm: &Swarm, fullnode_address: SocketAddr) {
+    println!("Second network fullnode RPC: http://{fullnode_address}");
+    println!("Second network config directory: {}", swarm.dir().display());
+}
+
+/// Starts the indexer, in reader and writer mode, and the GraphQL service, if they were requested,
+/// against the fullnode at `fullnode_url`. The indexer's writer reads the checkpoints that the
+/// fullnode dumps to `data_ingestion_dir`. Returns the addresses the services were started at.
+async fn start_indexer_and_graphql(
+    with_indexer: Option<String>,
+    with_graphql: Option<String>,
+    pg_address: String,
+    fullnode_url: &str,
+    data_ingestion_dir: Option<PathBuf>,
+    graphql_service_config: ServiceConfig,
+) -> Result<(Option<SocketAddr>, Option<SocketAddr>), anyhow::Error> {
+    let indexer_address = with_indexer
+        .map(|input| parse_host_port(input, DEFAULT_INDEXER_PORT))
+        .transpose()
+        .map_err(|_| anyhow!("Invalid indexer host and port"))?;
+    if let Some(indexer_address) = indexer_address {
+        info!("Starting the indexer service at {indexer_address}");
+        // Start in reader mode
+        start_indexer_jsonrpc_for_testing(
+            pg_address.clone(),
+            fullnode_url.to_string(),
+            indexer_address.to_string(),
+            None,
+        )
+        .await;
+        info!("Indexer started in reader mode");
+        start_indexer_writer_for_testing(
+            pg_address.clone(),
+            None,
+            None,
+            data_ingestion_dir,
+            None,
+            None, /* start_checkpoint */
+            None, /* end_checkpoint */
+        )
+        .await;
+        info!("Indexer started in writer mode");
+    }
+
+    let graphql_address = with_graphql
+        .map(|input| parse_host_port(input, DEFAULT_GRAPHQL_PORT))
+        .transpose()
+        .map_err(|_| anyhow!("Invalid graphql host and port"))?;
+    if let Some(graphql_address) = graphql_address {
+        tracing::info!("Starting the GraphQL service at {graphql_address}");
+        let graphql_connection_config = ConnectionConfig {
+            port: graphql_address.port(),
+            host: graphql_address.ip().to_string(),
+            db_url: pg_address,
+            ..Default::default()
+        };
+
+        start_graphql_server_with_fn_rpc(
+            graphql_connection_config,
+            Some(fullnode_url.to_string()),
+            None, // it will be initialized by default
+            graphql_service_config,
+        )
+        .await;
+        info!("GraphQL started");
+    }
+
+    Ok((indexer_address, graphql_address))
+}
+
+/// Starts only the indexer, and the GraphQL service if requested, against the existing fullnode at
+/// `fullnode_url`, for `sui start --indexer-only`, and runs until interrupted.
+async fn start_indexer_only(
+    fullnode_url: String,
+    fullnode_basic_auth: Option<String>,
+    indexer_feature_args: IndexerArgs,
+    data_ingestion_dir: Option<PathBuf>,
+) -> Result<(), anyhow::Error> {
+    let data_ingestion_dir = data_ingestion_dir.context(
+        "`--indexer-only` requires `--data-ingestion-dir`, set to the directory that the fullnode \
+        dumps checkpoints to, which the indexer reads.",
+    )?;
+
+    let pg_address = indexer_feature_args.pg_address()?;
+    let IndexerArgs {
+        with_indexer,
+        with_graphql,
+        graphql_config,
+        ..
+    } = indexer_feature_args;
+
+    let graphql_service_config = match graphql_config {
+        Some(path) => read_graphql_service_config(&path)?,
+        None => ServiceConfig::test_defaults(),
+    };
+
+    // The indexer is the point of this mode, so it is started even without `--with-indexer`.
+    let with_indexer = Some(with_indexer.unwrap_or_default());
+
+    info!("Fullnode URL: {}", fullnode_url);
+    let authenticated_fullnode_url = match &fullnode_basic_auth {
+        Some(basic_auth) => {
+            info!("Fullnode basic auth: {}", redact_basic_auth(basic_auth));
+            url_with_basic_auth(&fullnode_url, basic_auth)?
+        }
+        None => fullnode_url.clone(),
+    };
+
+    let (indexer_address, graphql_address) = start_indexer_and_graphql(
+        with_indexer,
+        with_graphql,
+        pg_address,
+        &authenticated_fullnode_url,
+        Some(data_ingestion_dir),
+        graphql_service_config,
+    )
+    .await?;
+
+    println!("Fullnode RPC: {fullnode_url}");
+    if let Some(address) = indexer_address {
+        println!("Indexer: http://{address}");
+    }
+    if let Some(address) = graphql_address {
+        println!("GraphQL: http://{address}");
+    }
+
+    tokio::signal::ctrl_c().await?;
+    info!("Shutting down");
+    Ok(())
+}
+
+/// Poll the validators, and the indexer, GraphQL and faucet services if they were started, until
+/// they all respond, giving up after `WAIT_FOR_READY_TIMEOUT`.
+async fn wait_for_services_ready(
+    swarm: &Swarm,
+    indexer_address: Option<SocketAddr>,
+    graphql_address: Option<SocketAddr>,
+    faucet_address: Option<SocketAddr>,
+) -> Result<(), anyhow::Error> {
+    let mut interval = tokio::time::interval(std::time::Duration::from_millis(500));
+    tokio::time::timeout(WAIT_FOR_READY_TIMEOUT, async {
+        loop {
+            interval.tick().await;
+            if services_ready(swarm, indexer_address, graphql_address, faucet_address).await {
+                return;
+            }
+        }
+    })
+    .await
+    .map_err(|_| {
+        anyhow!(
+            "Services did not report healthy within {} seconds",
+            WAIT_FOR_READY_TIMEOUT.as_secs()
+        )
+    })
+}
+
+async fn services_ready(
+    swarm: &Swarm,
+    indexer_address: Option<SocketAddr>,
+    graphql_address: Option<SocketAddr>,
+    faucet_address: Option<SocketAddr>,
+) -> bool {
+    for node in swarm.validator_nodes() {
+        if node.health_check(true).await.is_err() {
+            return false;
+        }
+    }
+
+    if let Some(address) = indexer_address {
+        if tokio::net::TcpStream::connect(local_probe_address(address))
+            .await
+            .is_err()
+        {
+            return false;
+        }
+    }
+
+    if let Some(address) = graphql_address {
+        let url = format!("http://{}/health", local_probe_address(address));
+        if !reqwest::get(url)
+            .await
+            .is_ok_and(|response| response.status().is_success())
+        {
+            return false;
+        }
+    }
+
+    if let Some(address) = faucet_address {
+        let url = format!("http://{}/", local_probe_address(address));
+        if !reqwest::get(url)
+            .await
+            .is_ok_and(|response| response.status().is_success())
+        {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Wait for the faucet that `start` serves in the background to stop, and return why it did. Never
+/// completes if there is no faucet.
+async fn faucet_stopped(
+    faucet_task: &mut Option<tokio::task::JoinHandle<Result<(), anyhow::Error>>>,
+) -> anyhow::Error {
+    let Some(task) = faucet_task else {
+        return std::future::pending().await;
+    };
+    match task.await {
+        Ok(Ok(())) => anyhow!("The faucet stopped unexpectedly"),
+        Ok(Err(err)) => err.context("The faucet stopped unexpectedly"),
+        Err(err) => anyhow!(err).context("The faucet stopped unexpectedly"),
+    }
+}
+
+/// Services bound to an unspecified address (e.g. 0.0.0.0) are probed through the loopback
+/// interface instead.
+fn local_probe_address(address: SocketAddr) -> SocketAddr {
+    match address.ip() {
+        IpAddr::V4(ip) if ip.is_unspecified() => {
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), address.port())
+        }
+        IpAddr::V6(ip) if ip.is_unspecified() => {
+            SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), address.port())
+        }
+        _ => address,
+    }
+}
+
+/// The options of `sui genesis`, as passed to `genesis`.
+#[derive(Default)]
+struct GenesisArgs {
     from_config: Option<PathBuf>,
     write_config: Option<PathBuf>,
     working_dir: Option<PathBuf>,
     force: bool,
     epoch_duration_ms: Option<u64>,
+    protocol_version: Option<ProtocolVersion>,
+    system_packages_dir: Option<PathBuf>,
     benchmark_ips: Option<Vec<String>>,
     with_faucet: bool,
     committee_size: Option<usize>,
-) -> Result<(), anyhow::Error> {
-    let sui_config_dir = &match working_dir {
+    genesis_json: Option<PathBuf>,
+    rng_seed: Option<[u8; 32]>,
+    stake_distribution: Option<PathBuf>,
+    validator_names: Option<Vec<String>>,
+    print_addresses: bool,
+    keep_network: bool,
+    dry_run: bool,
+    json: bool,
+}
+
+async fn genesis(args: GenesisArgs) -> Result<(), anyhow::Error> {
+    let GenesisArgs {
+        from_config,
+        write_config,
+        working_dir,
+        force,
+        epoch_duration_ms,
+        protocol_version,
+        system_packages_dir,
+        benchmark_ips,
+        with_faucet,
+        committee_size,
+        genesis_json,
+        rng_seed,
+        stake_distribution,
+        validator_names,
+        print_addresses,
+        keep_network,
+        dry_run,
+        json,
+    } = args;
+
+    // Read the system packages up front, so that a bad directory fails before anything is written.
+    let system_packages = system_packages_dir
+        .map(|dir| read_system_packages(&dir))
+        .transpose()?;
+
+    let (sui_config_dir, create_config_dir) = &match working_dir {
         // if a directory is specified, it must exist (it
         // will not be created)
-        Some(v) => v,
+        Some(v) => (v, false),
         // create default Sui config dir if not specified
         // on the command line and if it does not exist
         // yet
         None => {
             let config_path = sui_config_dir()?;
-            fs::create_dir_all(&config_path)?;
-            config_path
+            let missing = !config_path.exists();
+            (config_path, missing)
         }
     };
+    if *create_config_dir && !dry_run {
+        fs::create_dir_all(sui_config_dir)?;
+    }
 
     // if Sui config dir is not empty then either clean it
     // up (if --force/-f option was specified or report an
     // error
-    let dir = sui_config_dir.read_dir().map_err(|err| {
-        anyhow!(err).context(format!("Cannot open Sui config dir {:?}", sui_config_dir))
-    })?;
-    let files = dir.collect::<Result<Vec<_>, _>>()?;
+    let files = if *create_config_dir && dry_run {
+        vec![]
+    } else {
+        let dir = sui_config_dir.read_dir().map_err(|err| {
+            anyhow!(err).context(format!("Cannot open Sui config dir {:?}", sui_config_dir))
+        })?;
+        dir.collect::<Result<Vec<_>, _>>()?
+    };
 
     let client_path = sui_config_dir.join(SUI_CLIENT_CONFIG);
     let keystore_path = sui_config_dir.join(SUI_KEYSTORE_FILENAME);
+    let network_path = sui_config_dir.join(SUI_NETWORK_CONFIG);
+    let genesis_path = sui_config_dir.join(SUI_GENESIS_FILENAME);
 
+    let mut kept_network = if keep_network {
+        let paths: Vec<_> = files.iter().map(|file| file.path()).collect();
+        Some(read_network_to_keep(&network_path, &paths, committee_size)?)
+    } else {
+        None
+    };
+    let kept_files = kept_network
+        .as_ref()
+        .map_or(&[][..], |kept| kept.validator_files.as_slice());
+
+    let mut removed = vec![];
     if write_config.is_none() && !files.is_empty() {
         if force {
             // check old keystore and client.yaml is compatible
             let is_compatible = FileBasedKeystore::new(&keystore_path).is_ok()
                 && PersistedConfig::<SuiClientConfig>::read(&client_path).is_ok();
             // Keep keystore and client.yaml if they are compatible
-            if is_compatible {
-                for file in files {
-                    let path = file.path();
-                    if path != client_path && path != keystore_path {
-                        if path.is_file() {
-                            fs::remove_file(path)
-                        } else {
-                            fs::remove_dir_all(path)
-                        }
-                        .map_err(|err| {
-                            anyhow!(err).context(format!("Cannot remove file {:?}", file.path()))
-                        })?;
-                    }
-                }
-            } else {
-                fs::remove_dir_all(sui_config_dir).map_err(|err| {
-                    anyhow!(err)
-                        .context(format!("Cannot remove Sui config dir {:?}", sui_config_dir))
-                })?;
-                fs::create_dir(sui_config_dir).map_err(|err| {
-                    anyhow!(err)
-                        .context(format!("Cannot create Sui config dir {:?}", sui_config_dir))
-                })?;
-            }
+            removed = files
+                .iter()
+                .map(|file| file.path())
+                .filter(|path| !is_compatible || (*path != client_path && *path != keystore_path))
+                .filter(|path| !kept_files.contains(path))
+                .collect();
         } else if files.len() != 2 || !client_path.exists() || !keystore_path.exists() {
             bail!("Cannot run genesis with non-empty Sui config directory {}, please use the --force/-f option to remove the existing configuration", sui_config_dir.to_str().unwrap());
         }
     }
 
-    let network_path = sui_config_dir.join(SUI_NETWORK_CONFIG);
-    let genesis_path = sui_config_dir.join(SUI_GENESIS_FILENAME);
+    if !dry_run {
+        for path in &removed {
+            if path.is_file() {
+                fs::remove_file(path)
+            } else {
+                fs::remove_dir_all(path)
+            }
+            .map_err(|err| anyhow!(err).context(format!("Cannot remove file {:?}", path)))?;
+        }
+    }
+    let keystore_exists = keystore_path.exists() && !removed.contains(&keystore_path);
+    let client_exists = client_path.exists() && !removed.contains(&client_path);
+
+    let benchmark_keystore_path = benchmark_ips
+        .as_ref()
+        .map(|_| sui_config_dir.join(SUI_BENCHMARK_GENESIS_GAS_KEYSTORE_FILENAME));
 
     let mut genesis_conf = match from_config {
         Some(path) => PersistedConfig::read(&path)?,
         None => {
             if let Some(ips) = benchmark_ips {
                 // Make a keystore containing the key for the genesis gas object.
-                let path = sui_config_dir.join(SUI_BENCHMARK_GENESIS_GAS_KEYSTORE_FILENAME);
-                let mut keystore = FileBasedKeystore::new(&path)?;
-                for gas_key in GenesisConfig::benchmark_gas_keys(ips.len()) {
-                    keystore.add_key(None, gas_key)?;
+                if let Some(path) = benchmark_keystore_path.as_ref().filter(|_| !dry_run) {
+                    let mut keystore = FileBasedKeystore::new(path)?;
+                    for gas_key in GenesisConfig::benchmark_gas_keys(ips.len()) {
+                        keystore.add_key(None, gas_key)?;
+                    }
+                    keystore.save()?;
                 }
-                keystore.save()?;
 
                 // Make a new genesis config from the provided ip addresses.
                 GenesisConfig::new_for_benchmarks(&ips)
-            } else if keystore_path.exists() {
+            } else if keystore_exists {
                 let existing_keys = FileBasedKeystore::new(&keystore_path)?.addresses();
                 GenesisConfig::for_local_testing_with_addresses(existing_keys)
             } else {
@@ -1167,7 +2473,16 @@ async fn genesis(
         genesis_conf = genesis_conf.add_faucet_account();
     }
 
+    if let Some(protocol_version) = protocol_version {
+        genesis_conf.parameters.protocol_version = protocol_version;
+    }
+
     if let Some(path) = write_config {
+        if dry_run {
+            let plan = GenesisPlan::new(&[], vec![path]);
+            println!("{}", format_genesis_plan(&plan, None, json)?);
+            return Ok(());
+        }
         let persisted = genesis_conf.persisted(&path);
         persisted.save()?;
         return Ok(());
@@ -1176,7 +2491,11 @@ async fn genesis(
     let validator_info = genesis_conf.validator_config_info.take();
     let ssfn_info = genesis_conf.ssfn_config_info.take();
 
-    let builder = ConfigBuilder::new(sui_config_dir);
+    let mut rng = genesis_rng(rng_seed)?;
+    let mut builder = ConfigBuilder::new(sui_config_dir).rng(&mut rng);
+    if let Some(packages) = &system_packages {
+        builder = builder.with_objects(packages.iter().map(SystemPackage::genesis_object));
+    }
     if let Some(epoch_duration_ms) = epoch_duration_ms {
         genesis_conf.parameters.epoch_duration_ms = epoch_duration_ms;
     }
@@ -1186,11 +2505,43 @@ async fn genesis(
     }
     .ok_or_else(|| anyhow!("Committee size must be at least 1."))?;
 
+    if let Some(path) = stake_distribution {
+        let stakes = read_stake_distribution(&path)?;
+        let validators = validator_info
+            .as_ref()
+            .map_or(committee_size.get(), Vec::len);
+        ensure!(
+            stakes.len() == validators,
+            "Stake distribution in {} has {} stakes, but the committee has {validators} validators",
+            path.display(),
+            stakes.len(),
+        );
+        builder = builder.with_validator_stakes(stakes);
+    }
+
+    if let Some(names) = &validator_names {
+        let validators = validator_info
+            .as_ref()
+            .map_or(committee_size.get(), Vec::len);
+        ensure!(
+            names.len() == validators,
+            "Got {} validator names, but the committee has {validators} validators",
+            names.len(),
+        );
+        check_validator_names(names)?;
+        builder = builder.with_validator_names(names.clone());
+    }
+
     let mut network_config = if let Some(validators) = validator_info {
         builder
             .with_genesis_config(genesis_conf)
             .with_validators(validators)
             .build()
+    } else if let Some(kept) = &mut kept_network {
+        builder
+            .with_genesis_config(genesis_conf)
+            .with_validators(std::mem::take(&mut kept.validators))
+            .build()
     } else {
         builder
             .committee_size(committee_size)
@@ -1198,13 +2549,92 @@ async fn genesis(
             .build()
     };
 
+    // The new genesis is for the same validators, so their existing configs stay valid.
+    if let Some(kept) = kept_network {
+        network_config.validator_configs = kept.config.validator_configs;
+    }
+
+    let fullnode_config = FullnodeConfigBuilder::new()
+        .with_config_directory(FULL_NODE_DB_PATH.into())
+        .with_rpc_addr(sui_config::node::default_json_rpc_address())
+        .build(&mut rng, &network_config);
+    let fullnode_peer_id = anemo::PeerId(fullnode_config.network_key_pair().public().0.to_bytes());
+
+    // The keystore lists its addresses in order, whether they were already in it or are added
+    // now.
+    let mut account_addresses: BTreeSet<SuiAddress> = if keystore_exists {
+        FileBasedKeystore::new(&keystore_path)?
+            .addresses()
+            .into_iter()
+            .collect()
+    } else {
+        BTreeSet::new()
+    };
+    account_addresses.extend(
+        network_config
+            .account_keys
+            .iter()
+            .map(|key| SuiAddress::from(key.public())),
+    );
+    let account_addresses: Vec<_> = account_addresses.into_iter().collect();
+    let active_address = account_addresses.last().copied();
+    let validator_addresses: Vec<_> = network_config
+        .validator_configs()
+        .iter()
+        .map(|v| v.sui_address())
+        .collect();
+
+    if dry_run {
+        let mut written = vec![genesis_path, network_path];
+        written.extend(genesis_json.clone());
+        // Keystores are saved along with a file of their aliases.
+        for path in std::iter::once(keystore_path).chain(benchmark_keystore_path) {
+            written.push(path.with_extension("aliases"));
+            written.push(path);
+        }
+        written.push(sui_config_dir.join(SUI_FULLNODE_CONFIG));
+        if let Some(ssfn_info) = &ssfn_info {
+            written.extend(ssfn_info.iter().enumerate().map(|(i, ssfn)| {
+                sui_config_dir.join(sui_config::ssfn_config_file(ssfn.p2p_address.clone(), i))
+            }));
+        }
+        if !keep_network {
+            written.extend(network_config.validator_configs().iter().enumerate().map(
+                |(i, validator)| {
+                    sui_config_dir.join(validator_config_file(
+                        validator,
+                        validator_names.as_deref(),
+                        i,
+                    ))
+                },
+            ));
+        }
+        written.push(client_path);
+        if *create_config_dir {
+            written.insert(0, sui_config_dir.clone());
+        }
+
+        let plan = GenesisPlan::new(&removed, written);
+        let addresses = (
+            account_addresses.as_slice(),
+            validator_addresses.as_slice(),
+            &fullnode_peer_id,
+        );
+        println!("{}", format_genesis_plan(&plan, Some(addresses), json)?);
+        return Ok(());
+    }
+
     let mut keystore = FileBasedKeystore::new(&keystore_path)?;
     for key in &network_config.account_keys {
         keystore.add_key(None, SuiKeyPair::Ed25519(key.copy()))?;
     }
-    let active_address = keystore.addresses().pop();
 
     network_config.genesis.save(&genesis_path)?;
+    if let Some(path) = genesis_json {
+        fs::write(&path, format_genesis_json(&network_config.genesis)?)
+            .with_context(|| format!("Unable to write genesis JSON to {}", path.display()))?;
+        info!("Genesis JSON is stored in {:?}.", path);
+    }
     for validator in &mut network_config.validator_configs {
         validator.genesis = sui_config::node::Genesis::new_from_file(&genesis_path);
     }
@@ -1215,12 +2645,8 @@ async fn genesis(
 
     info!("Client keystore is stored in {:?}.", keystore_path);
 
-    let fullnode_config = FullnodeConfigBuilder::new()
-        .with_config_directory(FULL_NODE_DB_PATH.into())
-        .with_rpc_addr(sui_config::node::default_json_rpc_address())
-        .build(&mut OsRng, &network_config);
-
     fullnode_config.save(sui_config_dir.join(SUI_FULLNODE_CONFIG))?;
+    info!("Fullnode peer ID is {fullnode_peer_id}.");
     let mut ssfn_nodes = vec![];
     if let Some(ssfn_info) = ssfn_info {
         for (i, ssfn) in ssfn_info.into_iter().enumerate() {
@@ -1238,7 +2664,7 @@ async fn genesis(
                 .with_admin_interface_port(1337)
                 .with_json_rpc_address("0.0.0.0:9000".parse().unwrap())
                 .with_genesis(Genesis::new_from_file("/opt/sui/config/genesis.blob"))
-                .build(&mut OsRng, &network_config);
+                .build(&mut rng, &network_config);
             ssfn_nodes.push(ssfn_config.clone());
             ssfn_config.save(path)?;
         }
@@ -1258,30 +2684,34 @@ async fn genesis(
             .into_iter()
             .enumerate()
         {
-            let path = sui_config_dir.join(sui_config::validator_config_file(
-                validator.network_address.clone(),
+            let path = sui_config_dir.join(validator_config_file(
+                &validator,
+                validator_names.as_deref(),
                 i,
             ));
             let mut val_p2p = validator.p2p_config.clone();
             val_p2p.seed_peers = ssfn_seed_peers.clone();
             validator.p2p_config = val_p2p;
-            validator.save(path)?;
+            validator.save(&path)?;
+            log_validator_config(validator_names.as_deref(), i, &path);
         }
-    } else {
+    } else if !keep_network {
         for (i, validator) in network_config
             .into_validator_configs()
             .into_iter()
             .enumerate()
         {
-            let path = sui_config_dir.join(sui_config::validator_config_file(
-                validator.network_address.clone(),
+            let path = sui_config_dir.join(validator_config_file(
+                &validator,
+                validator_names.as_deref(),
                 i,
             ));
-            validator.save(path)?;
+            validator.save(&path)?;
+            log_validator_config(validator_names.as_deref(), i, &path);
         }
     }
 
-    let mut client_config = if client_path.exists() {
+    let mut client_config = if client_exists {
         PersistedConfig::read(&client_path)?
     } else {
         SuiClientConfig::new(keystore.into())
@@ -1318,25 +2748,53 @@ async fn genesis(
     client_config.save(&client_path)?;
     info!("Client config file is stored in {:?}.", client_path);
 
+    if print_addresses {
+        println!(
+            "{}",
+            format_genesis_addresses(
+                &account_addresses,
+                &validator_addresses,
+                &fullnode_peer_id,
+                json
+            )?
+        );
+    }
+
     Ok(())
 }
 
 async fn prompt_if_no_config(
     wallet_conf_path: &Path,
+    rpc: Option<String>,
     accept_defaults: bool,
+    key_scheme: Option<SignatureScheme>,
 ) -> Result<(), anyhow::Error> {
-    // Prompt user for connect to devnet fullnode if config does not exist.
+    // Prompt user for connect to devnet fullnode if config does not exist, unless an RPC URL is
+    // given by the `--rpc` flag or, failing that, the environment.
     if !wallet_conf_path.exists() {
-        let env = match std::env::var_os("SUI_CONFIG_WITH_RPC_URL") {
-            Some(v) => Some(SuiEnv {
+        let rpc = match rpc {
+            Some(rpc) => Some(rpc),
+            None => std::env::var_os("SUI_CONFIG_WITH_RPC_URL")
+                .map(|v| {
+                    v.into_string()
+                        .map_err(|_| anyhow!("SUI_CONFIG_WITH_RPC_URL is not valid unicode"))
+                })
+                .transpose()?,
+        };
+        let env = match rpc {
+            Some(rpc) => Some(SuiEnv {
                 alias: "custom".to_string(),
-                rpc: v.into_string().unwrap(),
+                rpc,
                 ws: None,
                 basic_auth: None,
             }),
             None => {
                 if accept_defaults {
-                    print!("Creating config file [{:?}] with default (devnet) Full node server and ed25519 key scheme.", wallet_conf_path);
+                    print!(
+                        "Creating config file [{:?}] with default (devnet) Full node server and {} key scheme.",
+                        wallet_conf_path,
+                        key_scheme.unwrap_or(SignatureScheme::ED25519),
+                    );
                 } else {
                     print!(
                         "Config file [{:?}] doesn't exist, do you want to connect to a Sui Full node server [y/N]?",
@@ -1400,7 +2858,7 @@ async fn prompt_if_no_config(
 
             let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path)?);
             let key_scheme = if accept_defaults {
-                SignatureScheme::ED25519
+                key_scheme.unwrap_or(SignatureScheme::ED25519)
             } else {
                 println!("Select key scheme to generate keypair (0 for ed25519, 1 for secp256k1, 2: for secp256r1):");
                 match SignatureScheme::from_flag(read_line()?.trim()) {
@@ -1437,130 +2895,777 @@ fn read_line() -> Result<String, anyhow::Error> {
     Ok(s.trim_end().to_string())
 }
 
+/// Run `request`, a request to the network, failing with an error that names the `operation` if it
+/// takes longer than `timeout`.
+async fn with_network_timeout<T, E: Into<anyhow::Error>>(
+    timeout: std::time::Duration,
+    operation: &str,
+    request: impl std::future::Future<Output = Result<T, E>>,
+) -> anyhow::Result<T> {
+    match tokio::time::timeout(timeout, request).await {
+        Ok(result) => result.map_err(Into::into),
+        Err(_) => bail!(
+            "Timed out after {}s while {operation}. Use `--timeout` to wait longer.",
+            timeout.as_secs()
+        ),
+    }
+}
+
 /// Get the currently configured client, and the chain ID for that client.
 async fn get_chain_id_and_client(
     client_config: SuiEnvConfig,
     command_err_string: &str,
+    timeout: std::time::Duration,
 ) -> anyhow::Result<(Option<String>, Option<SuiClient>)> {
     let config = client_config
         .config
         .unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
-    prompt_if_no_config(&config, false).await?;
+    prompt_if_no_config(&config, client_config.rpc, false, None).await?;
     let mut context = WalletContext::new(&config)?;
 
     if let Some(env_override) = client_config.env {
         context = context.with_env_override(env_override);
     }
 
-    let Ok(client) = context.get_client().await else {
-        bail!(
+    let connect = with_network_timeout(timeout, "connecting to the network", context.get_client());
+    let client = match connect.await {
+        Ok(client) => client,
+        Err(err) => bail!(
             "`{command_err_string}` requires a connection to the network. \
-             Current active network is {} but failed to connect to it.",
+             Current active network is {} but failed to connect to it: {err}",
             context.config.active_env.as_ref().unwrap()
-        );
+        ),
     };
 
     if let Err(e) = client.check_api_version() {
         eprintln!("{}", format!("[warning] {e}").yellow().bold());
     }
 
-    Ok((
-        client.read_api().get_chain_identifier().await.ok(),
-        Some(client),
-    ))
+    // Only a timeout is an error here: commands do without the chain identifier if the RPC
+    // fails to return it.
+    let chain_id = with_network_timeout(timeout, "fetching the chain identifier", async {
+        anyhow::Ok(client.read_api().get_chain_identifier().await.ok())
+    })
+    .await?;
+    Ok((chain_id, Some(client)))
 }
 
-/// Try to resolve an ObjectID to a MovePackage
-async fn resolve_package(reader: &ReadApi, package_id: ObjectID) -> anyhow::Result<MovePackage> {
-    let object = reader
-        .get_object_with_options(package_id, SuiObjectDataOptions::bcs_lossless())
-        .await?
-        .into_object()?;
+/// Parse a 32-byte seed for `--rng-seed`, hex-encoded with or without a 0x prefix.
+fn parse_rng_seed(s: &str) -> Result<[u8; 32], String> {
+    let bytes =
+        Hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|e| format!("Invalid hex: {e}"))?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("Expected 32 bytes, got {}", bytes.len()))
+}
 
-    let Some(SuiRawData::Package(package)) = object.bcs else {
-        bail!("Object {} is not a package.", package_id);
+/// Summarize the on-chain package `package_id` for `sui move summary --package-id` into
+/// `output_directory`, following the rest of the options in `s`, and return its metadata. The
+/// package is downloaded with `read_api`, which must be set unless `s.offline` is.
+async fn summarize_on_chain_package(
+    s: &sui_move::summary::Summary,
+    package_id: ObjectID,
+    output_directory: String,
+    build_config: BuildConfig,
+    read_api: Option<&ReadApi>,
+    timeout: std::time::Duration,
+) -> Result<PackageSummaryMetadata, anyhow::Error> {
+    let summary = sui_move::summary::Summary {
+        summary: move_cli::base::summary::Summary {
+            output_format: s.summary.output_format,
+            output_directory,
+            bytecode: s.summary.bytecode,
+        },
+        package_id: vec![package_id],
+        keep_going: s.keep_going,
+        package_version: s.package_version,
+        download_dir: s.download_dir.clone(),
+        offline: s.offline,
+        from_dir: s.from_dir.clone(),
+        cache: s.cache,
     };
 
-    Ok(MovePackage::new(
-        package.id,
-        package.version,
-        package.module_map,
-        // This package came from on-chain and the tool runs locally, so don't worry about
-        // trying to enforce the package size limit.
-        u64::MAX,
-        package.type_origin_table,
-        package.linkage_table,
-    )?)
+    // With `--offline`, summarize the bytes downloaded by a previous run under `--from-dir`.
+    if s.offline {
+        let path = s
+            .from_dir
+            .as_ref()
+            .context("`--offline` requires `--from-dir`")?
+            .join(package_id.to_canonical_string(/* with_prefix */ true));
+        let package_metadata = read_downloaded_package(&path)?;
+        if package_metadata.root_package_id != Some(package_id) {
+            bail!(
+                "{} contains a download of a different package than {package_id}",
+                path.display()
+            );
+        }
+
+        execute_move_command(
+            Some(&path),
+            build_config,
+            sui_move::Command::Summary(summary),
+            Some(sui_move::CommandMeta::Summary(package_metadata.clone())),
+        )?;
+        return Ok(package_metadata);
+    }
+
+    let read_api = read_api.context("A client is required unless `--offline` is set")?;
+    let cache = if s.cache {
+        let chain_id = with_network_timeout(
+            timeout,
+            "fetching the chain identifier",
+            read_api.get_chain_identifier(),
+        )
+        .await?;
+        Some(PackageCache::new(
+            sui_config_dir()?.join(PACKAGE_CACHE_DIR),
+            &chain_id,
+        ))
+    } else {
+        None
+    };
+
+    // Download the package bytes to a directory for this package under `--download-dir` if one
+    // was given, reusing previously downloaded bytes unless `--force` or `--package-version` is
+    // set (bytes from a previous run may be of another version). Otherwise download them to a
+    // tempdir that is removed once the summary is produced.
+    let package_bytes_location;
+    let (path, reuse_existing) = match &s.download_dir {
+        Some(dir) => {
+            let path = dir.join(package_id.to_canonical_string(/* with_prefix */ true));
+            fs::create_dir_all(&path)?;
+            let reuse_existing = !build_config.force_recompilation && s.package_version.is_none();
+            (path, reuse_existing)
+        }
+        None => {
+            package_bytes_location = tempdir()?;
+            (package_bytes_location.path().to_path_buf(), false)
+        }
+    };
+    let package_metadata = with_network_timeout(
+        timeout,
+        &format!("downloading package {package_id}"),
+        download_package_and_deps_under(
+            read_api,
+            &path,
+            package_id,
+            s.package_version.map(SequenceNumber::from_u64),
+            reuse_existing,
+            cache.as_ref(),
+        ),
+    )
+    .await?;
+
+    // Now produce the summary, pointing at the directory containing the package bytes.
+    execute_move_command(
+        Some(&path),
+        build_config,
+        sui_move::Command::Summary(summary),
+        Some(sui_move::CommandMeta::Summary(package_metadata.clone())),
+    )?;
+    Ok(package_metadata)
 }
 
-/// Download the package's modules and its dependencies to the specified path.
-async fn download_package_and_deps_under(
-    read_api: &ReadApi,
-    path: &Path,
-    package_id: ObjectID,
-) -> anyhow::Result<PackageSummaryMetadata> {
-    let mut dependencies = BTreeMap::new();
-    let mut linkage = BTreeMap::new();
-    let mut type_origins = BTreeMap::new();
-
-    let root_package = resolve_package(read_api, package_id).await?;
-    for (original_id, pkg_info) in root_package.linkage_table().iter() {
-        let package = resolve_package(read_api, pkg_info.upgraded_id).await?;
-        let relative_package_path = package
-            .id()
-            .deref()
-            .to_canonical_string(/* with_prefix */ true);
-
-        let package_path = path.join(&relative_package_path);
-        fs::create_dir_all(&package_path)?;
-        for (m_name, module) in package.serialized_module_map() {
-            let mut file = fs::File::create(
-                package_path
-                    .join(m_name)
-                    .with_extension(MOVE_COMPILED_EXTENSION),
-            )?;
-            file.write_all(module)?;
-        }
-
-        dependencies.insert(*original_id, PathBuf::from(relative_package_path));
-        linkage.insert(*original_id, pkg_info.clone());
-        type_origins.insert(*original_id, package.type_origin_table().clone());
-    }
-
-    let package_path = path.join(
-        root_package
-            .id()
-            .deref()
-            .to_canonical_string(/* with_prefix */ true),
+/// Read the GraphQL service config for `sui start --graphql-config` from the TOML file at `path`.
+pub fn read_graphql_service_config(path: &Path) -> Result<ServiceConfig, anyhow::Error> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Cannot read GraphQL config file {}", path.display()))?;
+    ServiceConfig::read(&contents)
+        .with_context(|| format!("Invalid GraphQL config file {}", path.display()))
+}
+
+/// Check `config` for problems that would stop its network from starting or reaching consensus,
+/// returning a description of each one. The config is consistent if this returns nothing.
+pub fn validate_network_config(config: &NetworkConfig) -> Vec<String> {
+    let mut problems = vec![];
+    let validators = config.validator_configs();
+    if validators.is_empty() {
+        problems.push("The network has no validators".to_string());
+    }
+
+    // Report each key or address shared by more than one validator, naming the validators by
+    // their position in the config.
+    fn check_distinct<T: Eq + std::hash::Hash>(
+        problems: &mut Vec<String>,
+        what: &str,
+        values: impl IntoIterator<Item = T>,
+    ) {
+        let mut seen = HashMap::new();
+        for (i, value) in values.into_iter().enumerate() {
+            if let Some(first) = seen.get(&value) {
+                problems.push(format!("Validators {first} and {i} have the same {what}"));
+            } else {
+                seen.insert(value, i);
+            }
+        }
+    }
+
+    check_distinct(
+        &mut problems,
+        "protocol key",
+        validators.iter().map(|v| v.protocol_public_key()),
     );
-    fs::create_dir_all(&package_path)?;
-    for (m_name, module) in root_package.serialized_module_map() {
-        let file_path = package_path
-            .join(m_name)
-            .with_extension(MOVE_COMPILED_EXTENSION);
-        let mut file = fs::File::create(&file_path)?;
-        file.write_all(module).with_context(|| {
-            format!(
-                "Unable to write module {m_name} for package {} to {}",
-                root_package
-                    .id()
-                    .deref()
-                    .to_canonical_string(/* with_prefix */ true),
-                file_path.display(),
-            )
-        })?;
+    check_distinct(
+        &mut problems,
+        "account address",
+        validators.iter().map(|v| v.sui_address()),
+    );
+    check_distinct(
+        &mut problems,
+        "network key",
+        validators
+            .iter()
+            .map(|v| v.network_key_pair.keypair().public().as_ref().to_vec()),
+    );
+    check_distinct(
+        &mut problems,
+        "worker key",
+        validators
+            .iter()
+            .map(|v| v.worker_key_pair.keypair().public().as_ref().to_vec()),
+    );
+    check_distinct(
+        &mut problems,
+        "network address",
+        validators.iter().map(|v| v.network_address().clone()),
+    );
+
+    for (i, validator) in validators.iter().enumerate() {
+        for (what, key) in [
+            ("network", validator.network_key_pair.keypair()),
+            ("worker", validator.worker_key_pair.keypair()),
+        ] {
+            if !matches!(key, SuiKeyPair::Ed25519(_)) {
+                problems.push(format!(
+                    "Validator {i} has a {} {what} key, but only ed25519 is allowed",
+                    key.public().scheme(),
+                ));
+            }
+        }
+
+        if let Err(e) = validator.network_address().to_socket_addr() {
+            problems.push(format!(
+                "Validator {i} has an unusable network address {}: {e}",
+                validator.network_address(),
+            ));
+        }
+        if let Some(address) = &validator.p2p_config.external_address {
+            if let Err(e) = address.to_anemo_address() {
+                problems.push(format!(
+                    "Validator {i} has an unusable P2P external address {address}: {e}"
+                ));
+            }
+        }
+    }
+
+    match config.genesis.committee() {
+        Ok(committee) => {
+            for (i, validator) in validators.iter().enumerate() {
+                if !committee.authority_exists(&validator.protocol_public_key()) {
+                    problems.push(format!(
+                        "Validator {i} is not a member of the genesis committee"
+                    ));
+                }
+            }
+            if committee.num_members() != validators.len() {
+                problems.push(format!(
+                    "The genesis committee has {} members, but the network has {} validators",
+                    committee.num_members(),
+                    validators.len(),
+                ));
+            }
+        }
+        Err(e) => problems.push(format!("Cannot read the genesis committee: {e}")),
+    }
+
+    problems
+}
+
+/// Read the bridge authority key at `path`, which must be a secp256k1 key.
+pub fn read_bridge_authority_key(path: &Path) -> Result<Secp256k1KeyPair, anyhow::Error> {
+    let key = read_key(&path.to_path_buf(), /* require_secp256k1 */ false)
+        .with_context(|| format!("Cannot read bridge authority key file {}", path.display()))?;
+    match key {
+        SuiKeyPair::Secp256k1(key) => Ok(key),
+        key => bail!(
+            "Bridge authority key file {} contains a {} key, but bridge authority keys must be {}",
+            path.display(),
+            key.public().scheme(),
+            SignatureScheme::Secp256k1,
+        ),
     }
+}
+
+/// Parse `username:password` credentials for `--fullnode-basic-auth`. The password cannot
+/// contain a colon, as client configs split credentials on the first and only colon.
+fn parse_basic_auth(s: &str) -> Result<String, String> {
+    match s.split(':').collect::<Vec<_>>()[..] {
+        [username, _] if !username.is_empty() => Ok(s.to_string()),
+        _ => Err("Basic auth should be in the format `username:password`".to_string()),
+    }
+}
+
+/// Hide the password in `username:password` credentials, for logging them.
+pub fn redact_basic_auth(basic_auth: &str) -> String {
+    match basic_auth.split_once(':') {
+        Some((username, _)) => format!("{username}:<redacted>"),
+        None => "<redacted>".to_string(),
+    }
+}
 
-    Ok(PackageSummaryMetadata {
-        root_package_id: Some(root_package.id()),
-        root_package_original_id: Some(root_package.original_package_id()),
-        root_package_version: Some(root_package.version().value()),
-        type_origins: Some(type_origins),
-        dependencies: Some(dependencies),
-        linkage: Some(linkage),
+/// Add `username:password` credentials to `url`, for services that are only configured with a
+/// URL. The result contains the password, so it must not be logged.
+pub fn url_with_basic_auth(url: &str, basic_auth: &str) -> Result<String, anyhow::Error> {
+    let (username, password) = basic_auth
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Basic auth should be in the format `username:password`"))?;
+    let mut url = url::Url::parse(url)?;
+    url.set_username(username)
+        .and_then(|()| url.set_password(Some(password)))
+        .map_err(|()| anyhow!("Cannot add credentials to {url}"))?;
+    Ok(url.to_string())
+}
+
+/// Set the credentials of the `localnet` environment in the client config in `config_dir`, if
+/// there is one, to `basic_auth`.
+fn set_localnet_basic_auth(config_dir: &Path, basic_auth: &str) -> Result<(), anyhow::Error> {
+    let client_path = config_dir.join(SUI_CLIENT_CONFIG);
+    if !client_path.exists() {
+        return Ok(());
+    }
+
+    let mut client_config: SuiClientConfig = PersistedConfig::read(&client_path)?;
+    let Some(env) = client_config
+        .envs
+        .iter_mut()
+        .find(|env| env.alias == "localnet")
+    else {
+        return Ok(());
+    };
+    if env.basic_auth.as_deref() != Some(basic_auth) {
+        env.basic_auth = Some(basic_auth.to_string());
+        client_config.save(&client_path)?;
+    }
+    Ok(())
+}
+
+/// Parse a human-readable duration for `--epoch-duration` into milliseconds.
+fn parse_epoch_duration(s: &str) -> Result<u64, String> {
+    let duration =
+        humantime::parse_duration(s).map_err(|e| format!("Invalid epoch duration: {e}"))?;
+    let ms = u64::try_from(duration.as_millis())
+        .map_err(|_| format!("Epoch duration is too long: {s}"))?;
+    if ms == 0 {
+        return Err("Epoch duration must be at least one millisecond".to_string());
+    }
+    Ok(ms)
+}
+
+/// Parse a protocol version for `--protocol-version`, which must be supported by this binary.
+fn parse_protocol_version(s: &str) -> Result<ProtocolVersion, String> {
+    let version: u64 = s
+        .parse()
+        .map_err(|e| format!("Invalid protocol version: {e}"))?;
+    if !(ProtocolVersion::MIN.as_u64()..=ProtocolVersion::MAX.as_u64()).contains(&version) {
+        return Err(format!(
+            "Protocol version {version} is not supported: this binary supports versions {} to {}",
+            ProtocolVersion::MIN.as_u64(),
+            ProtocolVersion::MAX.as_u64(),
+        ));
+    }
+    Ok(ProtocolVersion::new(version))
+}
+
+/// The source of randomness for generating a network's keys: seeded from `seed` for
+/// reproducible test networks, or from the operating system otherwise.
+fn genesis_rng(seed: Option<[u8; 32]>) -> Result<ChaCha20Rng, anyhow::Error> {
+    Ok(match seed {
+        Some(seed) => ChaCha20Rng::from_seed(seed),
+        None => ChaCha20Rng::from_rng(OsRng)?,
     })
 }
 
+/// Check that validator names for `sui genesis --validator-names` are unique and can be used in
+/// file names.
+fn check_validator_names(names: &[String]) -> Result<(), anyhow::Error> {
+    let mut seen = HashSet::new();
+    for name in names {
+        ensure!(
+            !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+            "Invalid validator name {name:?}: names may only contain ASCII letters, digits, '-' \
+             and '_'"
+        );
+        ensure!(seen.insert(name), "Duplicate validator name {name:?}");
+    }
+    Ok(())
+}
+
+/// The network that `sui genesis --keep-network` builds a new genesis for.
+struct KeptNetwork {
+    config: NetworkConfig,
+    /// The genesis configs of the network's validators, with the keys, addresses and on-chain
+    /// settings they were given at genesis.
+    validators: Vec<ValidatorGenesisConfig>,
+    /// The config files of the network's validators.
+    validator_files: Vec<PathBuf>,
+}
+
+/// Read the network configured in `network_path` for `sui genesis --keep-network`, and find the
+/// config file of each of its validators among `files`. Fails if the network does not have
+/// `committee_size` validators, when that is set, or if a validator's config file is missing.
+fn read_network_to_keep(
+    network_path: &Path,
+    files: &[PathBuf],
+    committee_size: Option<usize>,
+) -> Result<KeptNetwork, anyhow::Error> {
+    let config: NetworkConfig = PersistedConfig::read(network_path).map_err(|err| {
+        err.context(format!(
+            "Cannot keep the network: unable to read its config at {}",
+            network_path.display()
+        ))
+    })?;
+
+    let size = config.validator_configs().len();
+    if let Some(committee_size) = committee_size {
+        ensure!(
+            committee_size == size,
+            "Cannot keep the network: it has {size} validators, but a committee size of \
+            {committee_size} was requested"
+        );
+    }
+
+    let summary = config
+        .genesis
+        .sui_system_object()
+        .into_sui_system_state_summary();
+    let mut validators = vec![];
+    let mut validator_files = vec![];
+    for validator in config.validator_configs() {
+        let protocol_key = validator.protocol_public_key();
+        let on_chain = summary
+            .active_validators
+            .iter()
+            .find(|v| {
+                AuthorityPublicKeyBytes::from_bytes(&v.protocol_pubkey_bytes)
+                    .is_ok_and(|key| key == protocol_key)
+            })
+            .with_context(|| {
+                format!(
+                    "Cannot keep the network: validator {} is not in its genesis committee",
+                    validator.sui_address()
+                )
+            })?;
+
+        // The validator's config file is the one with the same protocol key.
+        let file = files
+            .iter()
+            .filter(|path| path.extension().is_some_and(|ext| ext == "yaml"))
+            .find(|path| {
+                PersistedConfig::<NodeConfig>::read(path)
+                    .is_ok_and(|c| c.protocol_public_key() == protocol_key)
+            })
+            .with_context(|| {
+                format!(
+                    "Cannot keep the network: the config file of validator {} is missing",
+                    on_chain.name
+                )
+            })?;
+        validator_files.push(file.clone());
+
+        let parse_address = |address: &str| {
+            address.parse::<Multiaddr>().map_err(|err| {
+                anyhow!(
+                    "Cannot keep the network: invalid address {address:?} of validator {}: {err}",
+                    on_chain.name
+                )
+            })
+        };
+        let p2p_address = match &validator.p2p_config.external_address {
+            Some(address) => address.clone(),
+            None => parse_address(&on_chain.p2p_address)?,
+        };
+        validators.push(ValidatorGenesisConfig {
+            key_pair: validator.protocol_key_pair().copy(),
+            worker_key_pair: validator.worker_key_pair().copy(),
+            account_key_pair: validator.account_key_pair.keypair().copy(),
+            network_key_pair: validator.network_key_pair().copy(),
+            network_address: validator.network_address.clone(),
+            p2p_address,
+            p2p_listen_address: Some(validator.p2p_config.listen_address),
+            metrics_address: validator.metrics_address,
+            // Not part of the genesis, nor of the validator's config.
+            narwhal_metrics_address: Multiaddr::empty(),
+            gas_price: on_chain.gas_price,
+            commission_rate: on_chain.commission_rate,
+            narwhal_primary_address: parse_address(&on_chain.primary_address)?,
+            narwhal_worker_address: parse_address(&on_chain.worker_address)?,
+            consensus_address: Multiaddr::empty(),
+            stake: on_chain.staking_pool_sui_balance,
+            name: Some(on_chain.name.clone()),
+        });
+    }
+
+    Ok(KeptNetwork {
+        config,
+        validators,
+        validator_files,
+    })
+}
+
+/// The file name for the config of the `i`th validator, using its name if validators are named.
+fn validator_config_file(validator: &NodeConfig, names: Option<&[String]>, i: usize) -> String {
+    match names {
+        Some(names) => sui_config::named_validator_config_file(&names[i]),
+        None => sui_config::validator_config_file(validator.network_address.clone(), i),
+    }
+}
+
+fn log_validator_config(names: Option<&[String]>, i: usize, path: &Path) {
+    match names {
+        Some(names) => info!("Validator {} config is stored in {:?}.", names[i], path),
+        None => info!("Validator {i} config is stored in {:?}.", path),
+    }
+}
+
+/// Read the list of validator stakes, in MIST, for `sui genesis --stake-distribution`. The file
+/// may be YAML or JSON.
+pub fn read_stake_distribution(path: &Path) -> Result<Vec<u64>, anyhow::Error> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Unable to read stake distribution from {}", path.display()))?;
+    let stakes: Vec<u64> = serde_yaml::from_str(&contents)
+        .with_context(|| format!("Invalid stake distribution in {}", path.display()))?;
+    ensure!(
+        stakes.iter().all(|stake| *stake > 0),
+        "Stake distribution in {} contains a zero stake",
+        path.display()
+    );
+    Ok(stakes)
+}
+
+/// Read the system packages for `sui genesis --system-packages-dir`, which must include every
+/// system package that is built into this binary.
+pub fn read_system_packages(dir: &Path) -> Result<Vec<SystemPackage>, anyhow::Error> {
+    let packages = load_bytecode_snapshot_from_dir(dir)
+        .with_context(|| format!("Unable to read system packages from {}", dir.display()))?;
+    for expected in BuiltInFramework::iter_system_package_metadata() {
+        ensure!(
+            packages.iter().any(|p| p.id == expected.compiled.id),
+            "System packages in {} are missing the {} package ({})",
+            dir.display(),
+            expected.name,
+            expected.compiled.id,
+        );
+    }
+    Ok(packages)
+}
+
+/// Summarize the contents of `genesis` as JSON for `sui genesis --genesis-json`: the checkpoint
+/// digest, the system state (including the validators and their stake), and the ID, version,
+/// owner and type of every genesis object.
+pub fn format_genesis_json(
+    genesis: &sui_config::genesis::Genesis,
+) -> Result<String, anyhow::Error> {
+    let objects: Vec<_> = genesis
+        .objects()
+        .iter()
+        .map(|object| {
+            let type_ = match object.struct_tag() {
+                Some(tag) => tag.to_canonical_string(/* with_prefix */ true),
+                None => "package".to_string(),
+            };
+            json!({
+                "objectId": object.id(),
+                "version": object.version(),
+                "owner": object.owner,
+                "type": type_,
+            })
+        })
+        .collect();
+
+    Ok(serde_json::to_string_pretty(&json!({
+        "checkpointDigest": genesis.checkpoint().digest(),
+        "systemState": genesis.sui_system_object().into_sui_system_state_summary(),
+        "objects": objects,
+    }))?)
+}
+
+/// Format the feature flags of `config`, and whether each one is enabled, as JSON for
+/// `sui start --print-protocol-flags`.
+pub fn format_protocol_flags(config: &ProtocolConfig) -> Result<String, anyhow::Error> {
+    Ok(serde_json::to_string_pretty(&json!({
+        "protocolVersion": config.version.as_u64(),
+        "featureFlags": config.feature_map(),
+    }))?)
+}
+
+/// The changes that `sui genesis` makes to the filesystem, as reported by `--dry-run`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct GenesisPlan {
+    /// Files (and the config directory) that do not exist yet.
+    pub create: Vec<PathBuf>,
+    /// Files that exist, and are replaced or updated.
+    pub overwrite: Vec<PathBuf>,
+    /// Files and directories that are removed from the config directory, and not written again.
+    pub delete: Vec<PathBuf>,
+}
+
+impl GenesisPlan {
+    /// The plan for removing `removed`, and then writing `written`, as things stand on disk now.
+    pub fn new(removed: &[PathBuf], written: Vec<PathBuf>) -> Self {
+        let delete = removed
+            .iter()
+            .filter(|path| !written.contains(path))
+            .cloned()
+            .collect();
+        let (overwrite, create) = written.into_iter().partition(|path| path.exists());
+        Self {
+            create,
+            overwrite,
+            delete,
+        }
+    }
+}
+
+/// Format the output of `sui genesis --dry-run`: `plan`, followed by the addresses that
+/// `--print-addresses` would print, if they are known.
+pub fn format_genesis_plan(
+    plan: &GenesisPlan,
+    addresses: Option<(&[SuiAddress], &[SuiAddress], &anemo::PeerId)>,
+    json: bool,
+) -> Result<String, anyhow::Error> {
+    if json {
+        let mut output = json!({
+            "create": plan.create,
+            "overwrite": plan.overwrite,
+            "delete": plan.delete,
+        });
+        if let Some((accounts, validators, fullnode_peer_id)) = addresses {
+            output["accounts"] = json!(accounts);
+            output["validators"] = json!(validators);
+            output["fullnodePeerId"] = json!(fullnode_peer_id.to_string());
+        }
+        return Ok(serde_json::to_string_pretty(&output)?);
+    }
+
+    let mut output = vec![];
+    for (heading, paths) in [
+        ("Would create:", &plan.create),
+        ("Would overwrite:", &plan.overwrite),
+        ("Would delete:", &plan.delete),
+    ] {
+        if !paths.is_empty() {
+            output.push(heading.to_string());
+            output.extend(paths.iter().map(|p| format!("  {}", p.display())));
+        }
+    }
+    if let Some((accounts, validators, fullnode_peer_id)) = addresses {
+        output.push(format_genesis_addresses(
+            accounts,
+            validators,
+            fullnode_peer_id,
+            false,
+        )?);
+    }
+    Ok(output.join("\n"))
+}
+
+/// Format the account and validator addresses, and the fullnode's peer ID, for
+/// `sui genesis --print-addresses`.
+pub fn format_genesis_addresses(
+    accounts: &[SuiAddress],
+    validators: &[SuiAddress],
+    fullnode_peer_id: &anemo::PeerId,
+    json: bool,
+) -> Result<String, anyhow::Error> {
+    if json {
+        return Ok(serde_json::to_string_pretty(&json!({
+            "accounts": accounts,
+            "validators": validators,
+            "fullnodePeerId": fullnode_peer_id.to_string(),
+        }))?);
+    }
+
+    let mut output = vec!["Accounts:".to_string()];
+    output.extend(accounts.iter().map(|a| format!("  {a}")));
+    output.push("Validators:".to_string());
+    output.extend(validators.iter().map(|v| format!("  {v}")));
+    output.push("Fullnode peer ID:".to_string());
+    output.push(format!("  {fullnode_peer_id}"));
+    Ok(output.join("\n"))
+}
+
+/// Format the network address and protocol public key of each validator in `config`, for
+/// `sui network --dump-addresses`.
+pub fn format_validator_addresses(
+    config: &NetworkConfig,
+    json: bool,
+) -> Result<String, anyhow::Error> {
+    let validators = config.validator_configs();
+    if json {
+        let validators: Vec<_> = validators
+            .iter()
+            .map(|validator| {
+                json!({
+                    "network_address": validator.network_address().to_string(),
+                    "protocol_public_key": validator.protocol_key_pair().public().to_string(),
+                })
+            })
+            .collect();
+        return Ok(serde_json::to_string_pretty(&validators)?);
+    }
+
+    Ok(validators
+        .iter()
+        .map(|validator| {
+            format!(
+                "{} - {}",
+                validator.network_address(),
+                validator.protocol_key_pair().public(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Format the addresses and private keys of `keys` for `sui start --dump-keys`.
+pub fn format_funded_keys(
+    keys: &[SuiKeyPair],
+    format: KeyDumpFormat,
+) -> Result<String, anyhow::Error> {
+    let mut output = vec![];
+    match format {
+        KeyDumpFormat::Env => {
+            for (i, key) in keys.iter().enumerate() {
+                let address = SuiAddress::from(&key.public());
+                let private_key = key.encode().map_err(|e| anyhow!("{e}"))?;
+                output.push(format!("SUI_TEST_ADDRESS_{i}={address}"));
+                output.push(format!("SUI_TEST_PRIVATE_KEY_{i}={private_key}"));
+            }
+        }
+        KeyDumpFormat::Json => {
+            let mut accounts = vec![];
+            for key in keys {
+                accounts.push(json!({
+                    "address": SuiAddress::from(&key.public()),
+                    "privateKey": key.encode().map_err(|e| anyhow!("{e}"))?,
+                }));
+            }
+            output.push(serde_json::to_string_pretty(&accounts)?);
+        }
+        KeyDumpFormat::Hex => {
+            for key in keys {
+                let address = SuiAddress::from(&key.public());
+                let private_key = Hex::encode(key.to_bytes_no_flag());
+                output.push(format!("{address} 0x{private_key}"));
+            }
+        }
+    }
+    Ok(output.join("\n"))
+}
+
 /// Parse the input string into a SocketAddr, with a default port if none is provided.
 pub fn parse_host_port(
     input: String,
@@ -6,7 +6,9 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use crate::upgrade_compatibility::{compare_packages, missing_module_diag, FormattedField};
+use crate::upgrade_compatibility::{
+    compare_packages, load_downloaded_modules, missing_module_diag, FormattedField,
+};
 
 use move_binary_format::normalized::{Field, Type};
 use move_binary_format::CompiledModule;
@@ -17,6 +19,7 @@ use move_core_types::account_address::AccountAddress;
 use move_core_types::identifier::Identifier;
 use sui_move_build::BuildConfig;
 use sui_move_build::CompiledPackage;
+use sui_protocol_config::ProtocolConfig;
 use sui_types::move_package::UpgradePolicy;
 
 #[test]
@@ -279,6 +282,31 @@ fn positional_formatting() {
     assert_eq!(format!("{}", ff), "'bool' at position 999");
 }
 
+#[test]
+fn test_load_downloaded_modules() {
+    let (mods_v1, _, _) = get_packages("all");
+    let dir = tempfile::tempdir().unwrap();
+    for module in &mods_v1 {
+        let mut bytes = vec![];
+        module
+            .serialize_with_version(module.version, &mut bytes)
+            .unwrap();
+        let file_name = format!("{}.mv", module.self_id().name());
+        fs::write(dir.path().join(file_name), bytes).unwrap();
+    }
+    // Files other than compiled modules are ignored.
+    fs::write(dir.path().join("README.md"), "not a module").unwrap();
+
+    let loaded =
+        load_downloaded_modules(dir.path(), &ProtocolConfig::get_for_max_version_UNSAFE()).unwrap();
+    let mut expected: Vec<_> = mods_v1.iter().map(|m| m.self_id()).collect();
+    expected.sort_by(|a, b| a.name().cmp(b.name()));
+    assert_eq!(
+        loaded.iter().map(|m| m.self_id()).collect::<Vec<_>>(),
+        expected
+    );
+}
+
 fn get_packages(name: &str) -> (Vec<CompiledModule>, CompiledPackage, PathBuf) {
     let mut path: PathBuf = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("src/unit_tests/fixtures/upgrade_errors/");
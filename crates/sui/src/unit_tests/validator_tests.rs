@@ -6,6 +6,7 @@ use crate::validator_commands::{
 };
 use anyhow::Ok;
 use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::traits::{KeyPair, ToFromBytes};
 use shared_crypto::intent::{Intent, IntentMessage};
 use sui_types::crypto::SuiKeyPair;
 use sui_types::transaction::TransactionData;
@@ -65,3 +66,55 @@ async fn test_print_raw_rgp_txn() -> Result<(), anyhow::Error> {
     assert_eq!(summary.next_epoch_gas_price, 42);
     Ok(())
 }
+
+#[tokio::test]
+async fn test_display_committee() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let response = SuiValidatorCommand::DisplayCommittee
+        .execute(&mut test_cluster.wallet)
+        .await?;
+    let SuiValidatorCommandResponse::DisplayCommittee(committee) = &response else {
+        panic!("Expected DisplayCommittee");
+    };
+    assert_eq!(committee.epoch, 0);
+
+    // Every validator is in the committee, with its own keys.
+    let mut expected_keys: Vec<_> = test_cluster
+        .swarm
+        .config()
+        .validator_configs
+        .iter()
+        .map(|config| config.protocol_key_pair().public().as_bytes().to_vec())
+        .collect();
+    let mut keys: Vec<_> = committee
+        .validators
+        .iter()
+        .map(|v| Base64::decode(&v.protocol_public_key).unwrap())
+        .collect();
+    expected_keys.sort();
+    keys.sort();
+    assert_eq!(keys, expected_keys);
+    assert_eq!(
+        committee
+            .validators
+            .iter()
+            .map(|v| v.voting_power)
+            .sum::<u64>(),
+        10_000
+    );
+
+    let json = serde_json::to_value(&response)?;
+    let validator = &json["validators"][0];
+    for field in [
+        "name",
+        "suiAddress",
+        "netAddress",
+        "protocolPublicKey",
+        "workerPublicKey",
+        "networkPublicKey",
+        "votingPower",
+    ] {
+        assert!(validator.get(field).is_some(), "missing {field}");
+    }
+    Ok(())
+}
@@ -8,10 +8,12 @@ mod upgrade_compatibility_tests;
 
 use formatting::{format_list, format_param, singular_or_plural, FormattedField};
 
+use crate::package_download::download_package_and_deps_under;
+
 use anyhow::{anyhow, Context, Error};
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -27,7 +29,7 @@ use move_binary_format::{
     normalized, CompiledModule,
 };
 use move_bytecode_source_map::source_map::SourceName;
-use move_command_line_common::files::FileHash;
+use move_command_line_common::files::{FileHash, MOVE_COMPILED_EXTENSION};
 use move_compiler::diagnostics::codes::DiagnosticInfo;
 use move_compiler::{
     diagnostics::{
@@ -726,6 +728,67 @@ pub(crate) async fn check_compatibility(
     )
 }
 
+/// Check that `new_package` is an upgrade of the on-chain package `package_id` that is compatible
+/// under `policy`. The on-chain package and its dependencies are first downloaded under
+/// `download_dir`.
+pub(crate) async fn check_compatibility_against_download(
+    read_api: &ReadApi,
+    package_id: ObjectID,
+    new_package: CompiledPackage,
+    package_path: PathBuf,
+    policy: UpgradePolicy,
+    protocol_config: &ProtocolConfig,
+    download_dir: &Path,
+) -> Result<(), Error> {
+    let metadata =
+        download_package_and_deps_under(read_api, download_dir, package_id, None, false, None)
+            .await?;
+    let original_id = metadata
+        .root_package_original_id
+        .ok_or_else(|| anyhow!("Unable to get original ID of package {package_id}"))?;
+
+    let existing_modules = load_downloaded_modules(
+        &download_dir.join(package_id.to_canonical_string(/* with_prefix */ true)),
+        protocol_config,
+    )?;
+
+    compare_packages(
+        *original_id,
+        existing_modules,
+        new_package,
+        package_path,
+        policy,
+    )
+}
+
+/// Deserialize the modules that `download_package_and_deps_under` wrote to the package directory
+/// `path`, in order of module name.
+pub(crate) fn load_downloaded_modules(
+    path: &Path,
+    protocol_config: &ProtocolConfig,
+) -> Result<Vec<CompiledModule>, Error> {
+    let mut module_paths = fs::read_dir(path)
+        .with_context(|| format!("Unable to read downloaded package at {}", path.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    module_paths.retain(|path| {
+        path.extension()
+            .is_some_and(|ext| ext == MOVE_COMPILED_EXTENSION)
+    });
+    module_paths.sort();
+
+    let binary_config = to_binary_config(protocol_config);
+    module_paths
+        .iter()
+        .map(|path| {
+            let bytes = fs::read(path)
+                .with_context(|| format!("Unable to read module {}", path.display()))?;
+            CompiledModule::deserialize_with_config(&bytes, &binary_config)
+                .with_context(|| format!("Unable to deserialize module {}", path.display()))
+        })
+        .collect()
+}
+
 /// Collect all the errors into a single error message.
 fn compare_packages(
     package_id: AccountAddress,
@@ -105,6 +105,10 @@ pub enum SuiValidatorCommand {
         #[clap(name = "json", long)]
         json: Option<bool>,
     },
+    /// Print the validators in the current committee, with their network addresses, public keys
+    /// and voting power, along with the current epoch. Use --json for machine-readable output.
+    #[clap(name = "display-committee")]
+    DisplayCommittee,
     #[clap(name = "update-metadata")]
     UpdateMetadata {
         #[clap(subcommand)]
@@ -214,6 +218,7 @@ pub enum SuiValidatorCommand {
 pub enum SuiValidatorCommandResponse {
     MakeValidatorInfo,
     DisplayMetadata,
+    DisplayCommittee(CommitteeMetadata),
     BecomeCandidate(SuiTransactionBlockResponse),
     JoinCommittee(SuiTransactionBlockResponse),
     LeaveCommittee(SuiTransactionBlockResponse),
@@ -235,6 +240,49 @@ pub enum SuiValidatorCommandResponse {
     },
 }
 
+/// The validators in the committee of an epoch, as output by `display-committee`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitteeMetadata {
+    pub epoch: u64,
+    pub validators: Vec<CommitteeMember>,
+}
+
+/// A validator in `CommitteeMetadata`. Public keys are Base64 encoded.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitteeMember {
+    pub name: String,
+    pub sui_address: SuiAddress,
+    pub net_address: String,
+    pub protocol_public_key: String,
+    pub worker_public_key: String,
+    pub network_public_key: String,
+    pub voting_power: u64,
+}
+
+impl From<SuiSystemStateSummary> for CommitteeMetadata {
+    fn from(summary: SuiSystemStateSummary) -> Self {
+        let validators = summary
+            .active_validators
+            .into_iter()
+            .map(|v| CommitteeMember {
+                name: v.name,
+                sui_address: v.sui_address,
+                net_address: v.net_address,
+                protocol_public_key: Base64::encode(&v.protocol_pubkey_bytes),
+                worker_public_key: Base64::encode(&v.worker_pubkey_bytes),
+                network_public_key: Base64::encode(&v.network_pubkey_bytes),
+                voting_power: v.voting_power,
+            })
+            .collect();
+        Self {
+            epoch: summary.epoch,
+            validators,
+        }
+    }
+}
+
 fn make_key_files(
     file_name: PathBuf,
     is_protocol_key: bool,
@@ -423,6 +471,16 @@ impl SuiValidatorCommand {
                 SuiValidatorCommandResponse::DisplayMetadata
             }
 
+            SuiValidatorCommand::DisplayCommittee => {
+                let summary = context
+                    .get_client()
+                    .await?
+                    .governance_api()
+                    .get_latest_sui_system_state()
+                    .await?;
+                SuiValidatorCommandResponse::DisplayCommittee(summary.into())
+            }
+
             SuiValidatorCommand::UpdateMetadata {
                 metadata,
                 gas_budget,
@@ -890,6 +948,22 @@ impl Display for SuiValidatorCommandResponse {
         match self {
             SuiValidatorCommandResponse::MakeValidatorInfo => {}
             SuiValidatorCommandResponse::DisplayMetadata => {}
+            SuiValidatorCommandResponse::DisplayCommittee(committee) => {
+                writeln!(
+                    writer,
+                    "Epoch {}: {} validators",
+                    committee.epoch,
+                    committee.validators.len()
+                )?;
+                for v in &committee.validators {
+                    writeln!(writer, "\n{} ({})", v.name, v.sui_address)?;
+                    writeln!(writer, "  Net address: {}", v.net_address)?;
+                    writeln!(writer, "  Protocol public key: {}", v.protocol_public_key)?;
+                    writeln!(writer, "  Worker public key: {}", v.worker_public_key)?;
+                    writeln!(writer, "  Network public key: {}", v.network_public_key)?;
+                    writeln!(writer, "  Voting power: {}", v.voting_power)?;
+                }
+            }
             SuiValidatorCommandResponse::BecomeCandidate(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
@@ -30,9 +30,12 @@ use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
 use tracing_subscriber::{filter, fmt, layer::SubscriberExt, reload, EnvFilter, Layer, Registry};
 
 use crate::file_exporter::{CachedOpenFile, FileExporter};
+pub use crate::system_log::SyslogFacility;
+use crate::system_log::SystemLog;
 
 mod file_exporter;
 pub mod span_latency_prom;
+mod system_log;
 
 /// Alias for a type-erased error type.
 pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
@@ -65,6 +68,10 @@ pub struct TelemetryConfig {
     pub sample_rate: f64,
     /// Add directive to include trace logs with provided target
     pub trace_target: Option<Vec<String>>,
+    /// Also write logs to the systemd journal (Linux only)
+    pub journald: bool,
+    /// Also write logs to the local syslog daemon under this facility (Unix only)
+    pub syslog_facility: Option<SyslogFacility>,
 }
 
 #[must_use]
@@ -298,6 +305,16 @@ impl TelemetryConfig {
         self
     }
 
+    pub fn with_journald(mut self) -> Self {
+        self.journald = true;
+        self
+    }
+
+    pub fn with_syslog(mut self, facility: SyslogFacility) -> Self {
+        self.syslog_facility = Some(facility);
+        self
+    }
+
     pub fn with_env(mut self) -> Self {
         if env::var("CRASH_ON_PANIC").is_ok() {
             self.crash_on_panic = true
@@ -345,8 +362,9 @@ impl TelemetryConfig {
                 directives.push_str(&format!(",{}=trace", target));
             }
         }
-        let env_filter =
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(directives));
+        let new_log_filter =
+            || EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&directives));
+        let env_filter = new_log_filter();
         let (log_filter, reload_handle) = reload::Layer::new(env_filter);
         let log_filter_handle = FilterHandle(reload_handle);
 
@@ -466,6 +484,30 @@ impl TelemetryConfig {
             layers.push(fmt_layer);
         }
 
+        // Mirror the console output to the system log. These layers get their own copy of the log
+        // filter, so they are not affected by reloading it through the `TracingHandle`.
+        let mut system_logs = Vec::new();
+        if config.journald {
+            system_logs.push(("journald", SystemLog::journald()));
+        }
+        if let Some(facility) = config.syslog_facility {
+            system_logs.push(("syslog", SystemLog::syslog(facility)));
+        }
+        for (name, system_log) in system_logs {
+            match system_log {
+                Ok(system_log) => {
+                    let system_log_layer = fmt::layer()
+                        .with_ansi(false)
+                        .without_time()
+                        .with_writer(system_log)
+                        .with_filter(new_log_filter())
+                        .boxed();
+                    layers.push(system_log_layer);
+                }
+                Err(e) => eprintln!("Not logging to {name}, falling back to console only: {e}"),
+            }
+        }
+
         let subscriber = tracing_subscriber::registry().with(layers);
         ::tracing::subscriber::set_global_default(subscriber)
             .expect("unable to initialize tracing subscriber");
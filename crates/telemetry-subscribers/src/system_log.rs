@@ -0,0 +1,253 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Log output to the system log: the systemd journal (using its native datagram protocol) or a
+//! local syslog daemon (using RFC 3164 messages). The journal is only available on Linux and
+//! syslog only on Unix. When a system log is unavailable, `TelemetryConfig::init` prints a warning
+//! and carries on logging to the console alone.
+
+use std::io;
+use tracing::{Level, Metadata};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Name that messages are tagged with in the system log.
+const IDENTIFIER: &str = "sui";
+
+/// The syslog facility that messages are logged under.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SyslogFacility {
+    User,
+    Daemon,
+    Local0,
+    Local1,
+    Local2,
+    Local3,
+    Local4,
+    Local5,
+    Local6,
+    Local7,
+}
+
+impl SyslogFacility {
+    fn code(self) -> u8 {
+        match self {
+            SyslogFacility::User => 1,
+            SyslogFacility::Daemon => 3,
+            SyslogFacility::Local0 => 16,
+            SyslogFacility::Local1 => 17,
+            SyslogFacility::Local2 => 18,
+            SyslogFacility::Local3 => 19,
+            SyslogFacility::Local4 => 20,
+            SyslogFacility::Local5 => 21,
+            SyslogFacility::Local6 => 22,
+            SyslogFacility::Local7 => 23,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Protocol {
+    Journald,
+    Syslog(SyslogFacility),
+}
+
+/// A `MakeWriter` that sends each formatted event to the system log as a single datagram.
+#[derive(Clone)]
+pub(crate) struct SystemLog {
+    protocol: Protocol,
+    #[cfg(unix)]
+    socket: std::sync::Arc<std::os::unix::net::UnixDatagram>,
+}
+
+impl SystemLog {
+    /// Connect to the systemd journal.
+    pub(crate) fn journald() -> io::Result<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            Self::connect(Protocol::Journald, &["/run/systemd/journal/socket"])
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "journald is only available on Linux",
+            ))
+        }
+    }
+
+    /// Connect to the local syslog daemon.
+    pub(crate) fn syslog(facility: SyslogFacility) -> io::Result<Self> {
+        #[cfg(unix)]
+        {
+            Self::connect(
+                Protocol::Syslog(facility),
+                &["/dev/log", "/var/run/syslog", "/var/run/log"],
+            )
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = facility;
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "syslog is only available on Unix",
+            ))
+        }
+    }
+
+    #[cfg(unix)]
+    fn connect(protocol: Protocol, paths: &[&str]) -> io::Result<Self> {
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        let mut last_err = None;
+        for path in paths {
+            match socket.connect(path) {
+                Ok(()) => {
+                    return Ok(Self {
+                        protocol,
+                        socket: std::sync::Arc::new(socket),
+                    })
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| io::Error::from(io::ErrorKind::NotFound)))
+    }
+
+    fn encode(&self, level: Level, message: &[u8]) -> Vec<u8> {
+        let message = message.strip_suffix(b"\n").unwrap_or(message);
+        let severity = severity(level);
+        match self.protocol {
+            Protocol::Journald => {
+                // MESSAGE uses the length-prefixed field encoding, which allows it to contain
+                // newlines.
+                let mut datagram =
+                    format!("PRIORITY={severity}\nSYSLOG_IDENTIFIER={IDENTIFIER}\nMESSAGE\n")
+                        .into_bytes();
+                datagram.extend_from_slice(&(message.len() as u64).to_le_bytes());
+                datagram.extend_from_slice(message);
+                datagram.push(b'\n');
+                datagram
+            }
+            Protocol::Syslog(facility) => {
+                let priority = facility.code() * 8 + severity;
+                let mut datagram =
+                    format!("<{priority}>{IDENTIFIER}[{}]: ", std::process::id()).into_bytes();
+                datagram.extend_from_slice(message);
+                datagram
+            }
+        }
+    }
+
+    fn send(&self, datagram: &[u8]) {
+        // Losing a log line is preferable to failing the operation that logged it.
+        #[cfg(unix)]
+        let _ = self.socket.send(datagram);
+        #[cfg(not(unix))]
+        let _ = datagram;
+    }
+}
+
+/// The syslog severity that events at `level` are logged with.
+fn severity(level: Level) -> u8 {
+    match level {
+        Level::ERROR => 3,
+        Level::WARN => 4,
+        Level::INFO => 6,
+        Level::DEBUG | Level::TRACE => 7,
+    }
+}
+
+/// Buffers one formatted event and sends it to the system log when dropped.
+pub(crate) struct SystemLogWriter<'a> {
+    log: &'a SystemLog,
+    level: Level,
+    buf: Vec<u8>,
+}
+
+impl io::Write for SystemLogWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for SystemLogWriter<'_> {
+    fn drop(&mut self) {
+        if !self.buf.is_empty() {
+            self.log.send(&self.log.encode(self.level, &self.buf));
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for SystemLog {
+    type Writer = SystemLogWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        SystemLogWriter {
+            log: self,
+            level: Level::INFO,
+            buf: Vec::new(),
+        }
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        SystemLogWriter {
+            log: self,
+            level: *meta.level(),
+            buf: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn system_log(protocol: Protocol) -> SystemLog {
+        SystemLog {
+            protocol,
+            #[cfg(unix)]
+            socket: std::sync::Arc::new(std::os::unix::net::UnixDatagram::unbound().unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_encode_journald() {
+        let log = system_log(Protocol::Journald);
+        let message = b"first line\nsecond line";
+
+        // MESSAGE is followed by its length, as a little-endian u64, instead of `=`.
+        let mut expected = b"PRIORITY=4\nSYSLOG_IDENTIFIER=sui\nMESSAGE\n".to_vec();
+        expected.extend_from_slice(&[22, 0, 0, 0, 0, 0, 0, 0]);
+        expected.extend_from_slice(message);
+        expected.push(b'\n');
+        assert_eq!(log.encode(Level::WARN, message), expected);
+
+        // The trailing newline of a formatted event is not part of the message.
+        assert_eq!(
+            log.encode(Level::WARN, b"first line\nsecond line\n"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_encode_syslog() {
+        let pid = std::process::id();
+        for (facility, level, priority) in [
+            (SyslogFacility::User, Level::ERROR, 11),
+            (SyslogFacility::Daemon, Level::INFO, 30),
+            (SyslogFacility::Local0, Level::DEBUG, 135),
+            (SyslogFacility::Local7, Level::TRACE, 191),
+            (SyslogFacility::Local3, Level::WARN, 156),
+        ] {
+            let log = system_log(Protocol::Syslog(facility));
+            assert_eq!(
+                log.encode(level, b"hello\n"),
+                format!("<{priority}>sui[{pid}]: hello").into_bytes(),
+            );
+        }
+    }
+}
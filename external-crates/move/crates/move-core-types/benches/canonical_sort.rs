@@ -0,0 +1,57 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{BatchSize, Criterion, black_box, criterion_group, criterion_main};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag, format_sorted_batch},
+};
+
+/// A `Coin<T>`-like tag with a distinct type parameter for each `i`, so that tags only differ
+/// towards the end of their canonical strings.
+fn coin_tag(i: u16) -> TypeTag {
+    let param = TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::from_suffix(i),
+        module: Identifier::new("m").unwrap(),
+        name: Identifier::new("T").unwrap(),
+        type_params: vec![],
+    }));
+    TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::TWO,
+        module: Identifier::new("coin").unwrap(),
+        name: Identifier::new("Coin").unwrap(),
+        type_params: vec![TypeTag::Vector(Box::new(param))],
+    }))
+}
+
+fn canonical_sort(c: &mut Criterion) {
+    // Reversed, so that the sort has work to do.
+    let tags: Vec<_> = (0..1024).rev().map(coin_tag).collect();
+
+    let mut group = c.benchmark_group("canonical_sort");
+    group.bench_function("sort_by_canonical_string", |b| {
+        b.iter_batched(
+            || tags.clone(),
+            |mut tags| {
+                tags.sort_by(|a, b| {
+                    a.to_canonical_string(true)
+                        .cmp(&b.to_canonical_string(true))
+                });
+                black_box(
+                    tags.iter()
+                        .map(|tag| tag.to_canonical_string(true))
+                        .collect::<Vec<_>>(),
+                )
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("format_sorted_batch", |b| {
+        b.iter(|| black_box(format_sorted_batch(&tags, true)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, canonical_sort);
+criterion_main!(benches);
@@ -0,0 +1,56 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+    struct_tag_matcher::StructTagMatcher,
+};
+
+/// A `Coin<T>`-like tag whose type parameter is nested `depth` levels deep, so that equality checks
+/// have to walk the whole tree before they can tell two tags apart.
+fn nested_tag(i: u16, depth: usize) -> StructTag {
+    let mut ty = TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::from_suffix(i),
+        module: Identifier::new("m").unwrap(),
+        name: Identifier::new("T").unwrap(),
+        type_params: vec![],
+    }));
+    for _ in 0..depth {
+        ty = TypeTag::Vector(Box::new(ty));
+    }
+    StructTag {
+        address: AccountAddress::TWO,
+        module: Identifier::new("coin").unwrap(),
+        name: Identifier::new("Coin").unwrap(),
+        type_params: vec![ty],
+    }
+}
+
+fn struct_tag_matching(c: &mut Criterion) {
+    let targets: Vec<_> = (0..64).map(|i| nested_tag(i, 16)).collect();
+    let candidates: Vec<_> = (0..128).map(|i| nested_tag(i, 16)).collect();
+    let matcher = StructTagMatcher::new(targets.clone());
+
+    let mut group = c.benchmark_group("struct_tag_matching");
+    group.bench_function("naive", |b| {
+        b.iter(|| {
+            for tag in &candidates {
+                black_box(targets.iter().position(|t| t == tag));
+            }
+        })
+    });
+    group.bench_function("matcher", |b| {
+        b.iter(|| {
+            for tag in &candidates {
+                black_box(matcher.matches_any(tag));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, struct_tag_matching);
+criterion_main!(benches);
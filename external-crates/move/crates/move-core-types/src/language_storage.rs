@@ -5,23 +5,62 @@
 use crate::{
     account_address::AccountAddress,
     gas_algebra::{AbstractMemorySize, BOX_ABSTRACT_SIZE, ENUM_BASE_ABSTRACT_SIZE},
+    ident_str,
     identifier::{IdentStr, Identifier},
-    parsing::types::{ParsedModuleId, ParsedStructType, ParsedType},
+    parsing::{
+        parser::{MAX_TYPE_DEPTH, MAX_TYPE_NODE_COUNT},
+        types::{ParsedModuleId, ParsedStructType, ParsedType},
+    },
 };
+use anyhow::{bail, ensure};
+use blake2::{Blake2b, Digest, digest::consts::U32};
 use indexmap::IndexSet;
 use move_proc_macros::test_variant_order;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de};
 use std::{
+    borrow::Borrow,
+    cell::Cell,
+    cmp::Ordering,
     fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+    ops::Deref,
     str::FromStr,
 };
 
 /// Hex address: 0x1
 pub const CORE_CODE_ADDRESS: AccountAddress = AccountAddress::ONE;
 
+/// Version of the scheme used by `StructTag::stable_dedup_key`, which is its first byte. Bumped
+/// whenever the scheme changes, which should never happen for any other reason.
+pub const STABLE_DEDUP_KEY_VERSION: u8 = 1;
+
+/// How addresses are written by `TypeTag::to_display` and `StructTag::to_display`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AddressFormat {
+    /// Hex-encoded and zero-padded to their full length, as in canonical strings.
+    Full,
+    /// Hex-encoded with leading zeroes removed, as in `AccountAddress::short_str_lossless`.
+    Short,
+}
+
+impl AddressFormat {
+    fn fmt(
+        self,
+        address: &AccountAddress,
+        f: &mut Formatter<'_>,
+        with_prefix: bool,
+    ) -> std::fmt::Result {
+        match self {
+            AddressFormat::Full => write!(f, "{}", address.to_canonical_display(with_prefix)),
+            AddressFormat::Short if with_prefix => write!(f, "0x{}", address.short_str_lossless()),
+            AddressFormat::Short => write!(f, "{}", address.short_str_lossless()),
+        }
+    }
+}
+
 /// Rough estimate of abstract size for TypeTag
 pub static TYPETAG_ENUM_ABSTRACT_SIZE: Lazy<AbstractMemorySize> =
     Lazy::new(|| ENUM_BASE_ABSTRACT_SIZE + BOX_ABSTRACT_SIZE);
@@ -42,7 +81,11 @@ pub enum TypeTag {
     Address,
     #[serde(rename = "signer", alias = "Signer")]
     Signer,
-    #[serde(rename = "vector", alias = "Vector")]
+    #[serde(
+        rename = "vector",
+        alias = "Vector",
+        deserialize_with = "deserialize_vector_element"
+    )]
     Vector(Box<TypeTag>),
     #[serde(rename = "struct", alias = "Struct")]
     Struct(Box<StructTag>),
@@ -57,6 +100,79 @@ pub enum TypeTag {
 }
 
 impl TypeTag {
+    /// Returns true for the ground types that can be passed by value: `bool`, the unsigned
+    /// integer types and `address`. Note that `address` counts as a primitive but `signer` does
+    /// not, as a signer cannot be constructed from a value; see `is_signer`.
+    pub fn is_primitive(&self) -> bool {
+        match self {
+            TypeTag::Bool
+            | TypeTag::U8
+            | TypeTag::U16
+            | TypeTag::U32
+            | TypeTag::U64
+            | TypeTag::U128
+            | TypeTag::U256
+            | TypeTag::Address => true,
+            TypeTag::Signer | TypeTag::Vector(_) | TypeTag::Struct(_) => false,
+        }
+    }
+
+    /// Returns true for `signer`, which `is_primitive` does not count as a primitive.
+    pub fn is_signer(&self) -> bool {
+        matches!(self, TypeTag::Signer)
+    }
+
+    pub fn is_vector(&self) -> bool {
+        matches!(self, TypeTag::Vector(_))
+    }
+
+    pub fn is_struct(&self) -> bool {
+        matches!(self, TypeTag::Struct(_))
+    }
+
+    /// The struct type, if this is one.
+    ///
+    /// ```
+    /// # use move_core_types::language_storage::TypeTag;
+    /// let coin: TypeTag = "0x2::coin::Coin<0x2::sui::SUI>".parse()?;
+    /// assert_eq!(coin.as_struct().unwrap().name.as_str(), "Coin");
+    /// assert!(TypeTag::U64.as_struct().is_none());
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn as_struct(&self) -> Option<&StructTag> {
+        match self {
+            TypeTag::Struct(tag) => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// `<framework>::coin::Coin<inner>`: the type of a coin of `inner`, for the Sui framework
+    /// published at `framework` (`0x2` on Sui).
+    pub fn coin_of(framework: AccountAddress, inner: TypeTag) -> TypeTag {
+        TypeTag::Struct(Box::new(StructTag {
+            address: framework,
+            module: ident_str!("coin").to_owned(),
+            name: ident_str!("Coin").to_owned(),
+            type_params: vec![inner],
+        }))
+    }
+
+    /// `<framework>::sui::SUI`: the type of SUI, for the Sui framework published at `framework`.
+    pub fn sui(framework: AccountAddress) -> TypeTag {
+        TypeTag::Struct(Box::new(StructTag {
+            address: framework,
+            module: ident_str!("sui").to_owned(),
+            name: ident_str!("SUI").to_owned(),
+            type_params: vec![],
+        }))
+    }
+
+    /// `<framework>::coin::Coin<<framework>::sui::SUI>`: the type of a SUI coin, for the Sui
+    /// framework published at `framework`.
+    pub fn sui_coin(framework: AccountAddress) -> TypeTag {
+        TypeTag::coin_of(framework, TypeTag::sui(framework))
+    }
+
     /// Return a canonical string representation of the type. All types are represented using their
     /// source syntax:
     ///
@@ -79,12 +195,24 @@ impl TypeTag {
 
     /// Implements the canonical string representation of the type with optional prefix 0x
     pub fn to_canonical_display(&self, with_prefix: bool) -> impl std::fmt::Display + '_ {
-        struct CanonicalDisplay<'a> {
+        self.to_display(with_prefix, AddressFormat::Full)
+    }
+
+    /// Displays the type like `to_canonical_display`, but with addresses written in `format`.
+    /// Only `AddressFormat::Full` gives the canonical string, which is stable: `Short` is for
+    /// human-readable output, like logs.
+    pub fn to_display(
+        &self,
+        with_prefix: bool,
+        format: AddressFormat,
+    ) -> impl std::fmt::Display + '_ {
+        struct TypeDisplay<'a> {
             data: &'a TypeTag,
             with_prefix: bool,
+            format: AddressFormat,
         }
 
-        impl std::fmt::Display for CanonicalDisplay<'_> {
+        impl std::fmt::Display for TypeDisplay<'_> {
             fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
                 match self.data {
                     TypeTag::Bool => write!(f, "bool"),
@@ -97,16 +225,17 @@ impl TypeTag {
                     TypeTag::Address => write!(f, "address"),
                     TypeTag::Signer => write!(f, "signer"),
                     TypeTag::Vector(t) => {
-                        write!(f, "vector<{}>", t.to_canonical_display(self.with_prefix))
+                        write!(f, "vector<{}>", t.to_display(self.with_prefix, self.format))
                     }
-                    TypeTag::Struct(s) => write!(f, "{}", s.to_canonical_display(self.with_prefix)),
+                    TypeTag::Struct(s) => s.fmt_with(f, self.with_prefix, self.format),
                 }
             }
         }
 
-        CanonicalDisplay {
+        TypeDisplay {
             data: self,
             with_prefix,
+            format,
         }
     }
 
@@ -114,30 +243,106 @@ impl TypeTag {
     /// This size might be imperfect but should be consistent across platforms
     /// TODO (ade): use macro to enfornce determinism
     pub fn abstract_size_for_gas_metering(&self) -> AbstractMemorySize {
-        *TYPETAG_ENUM_ABSTRACT_SIZE
-            + match self {
-                TypeTag::Bool
-                | TypeTag::U8
-                | TypeTag::U64
-                | TypeTag::U128
-                | TypeTag::Address
-                | TypeTag::Signer
-                | TypeTag::U16
-                | TypeTag::U32
-                | TypeTag::U256 => AbstractMemorySize::new(0),
-                TypeTag::Vector(x) => x.abstract_size_for_gas_metering(),
-                TypeTag::Struct(y) => y.abstract_size_for_gas_metering(),
-            }
+        let mut meter = AbstractSizeMeter(AbstractMemorySize::new(0));
+        self.visit(&mut meter);
+        meter.0
+    }
+
+    /// Serialize the type tag to BCS.
+    pub fn to_bcs_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    /// Serialize the type tag to BCS, failing if the encoding would be longer than `max_len`
+    /// bytes. The length is checked with `serialized_size_estimate` before anything is encoded.
+    pub fn to_bcs_bytes_bounded(&self, max_len: usize) -> anyhow::Result<Vec<u8>> {
+        let size = self.serialized_size_estimate()?;
+        ensure!(
+            size <= max_len,
+            "BCS encoding of type tag is {size} bytes long, exceeding the limit of {max_len} bytes",
+        );
+        self.to_bcs_bytes()
     }
 
-    /// Return all of the addresses used inside of the type.
+    /// The length of the type tag's BCS encoding, computed without allocating the encoding
+    /// itself.
+    pub fn serialized_size_estimate(&self) -> anyhow::Result<usize> {
+        Ok(bcs::serialized_size(self)?)
+    }
+
+    /// Return all of the addresses used inside of the type, in the order that they are first
+    /// reached by `visit`: a struct's address comes before the addresses in its type parameters,
+    /// which come in order. Callers may rely on this order.
     pub fn all_addresses(&self) -> IndexSet<AccountAddress> {
         let mut account_addresses = IndexSet::new();
-        self.find_addresses_internal(&mut account_addresses);
+        self.visit(&mut AddressCollector(&mut account_addresses));
         account_addresses
     }
 
-    pub(crate) fn find_addresses_internal(&self, account_addresses: &mut IndexSet<AccountAddress>) {
+    /// Walk the type in pre-order, calling `visitor` for every node: a vector or struct is
+    /// visited before its element type or type parameters, and type parameters are visited in
+    /// order. The walk uses an explicit work stack, so deeply nested types cannot overflow the
+    /// stack.
+    pub fn visit<V: TypeTagVisitor>(&self, visitor: &mut V) {
+        visit_types([self], visitor)
+    }
+
+    /// How deeply the type is nested. The element type of a vector and the type parameters of a
+    /// struct are one level deeper than the vector or struct, so `u8` has a depth of 0 and
+    /// `vector<0x1::m::S<u8>>` has a depth of 2. This is the depth limited by
+    /// `from_str_with_depth_limit` and `TypeTagLimits::max_depth`. The type is walked without
+    /// recursion.
+    pub fn generic_depth(&self) -> u64 {
+        let mut max_depth = 0;
+        let mut stack = vec![(self, 0)];
+        while let Some((ty, depth)) = stack.pop() {
+            max_depth = max_depth.max(depth);
+            match ty {
+                TypeTag::Vector(inner) => stack.push((inner, depth + 1)),
+                TypeTag::Struct(tag) => {
+                    stack.extend(tag.type_params.iter().map(|param| (param, depth + 1)))
+                }
+                _ => {}
+            }
+        }
+        max_depth
+    }
+
+    /// The number of nodes in the type: one for each primitive, vector and struct type inside of
+    /// it, including the type itself. This is the count limited by `TypeTagLimits::max_nodes`.
+    /// The type is walked without recursion.
+    pub fn node_count(&self) -> u64 {
+        let mut counter = NodeCounter(0);
+        self.visit(&mut counter);
+        counter.0
+    }
+
+    /// The first struct type called `module::name` inside of the type (including the type
+    /// itself), in the order that `visit` walks the type.
+    pub fn find_struct(&self, module: &str, name: &str) -> Option<&StructTag> {
+        structs_in([self]).find(|tag| tag.module.as_str() == module && tag.name.as_str() == name)
+    }
+
+    /// Every struct type called `module::name` inside of the type (including the type itself), in
+    /// the order that `visit` walks the type.
+    pub fn find_all_structs(&self, module: &str, name: &str) -> Vec<&StructTag> {
+        structs_in([self])
+            .filter(|tag| tag.module.as_str() == module && tag.name.as_str() == name)
+            .collect()
+    }
+
+    /// Every struct type inside of the type (including the type itself), at any depth, in the
+    /// order that `visit` walks the type. Unlike `all_addresses`, this keeps duplicates.
+    pub fn struct_tags(&self) -> Vec<&StructTag> {
+        structs_in([self]).collect()
+    }
+
+    /// Check that every address inside of the type fits in an address width of `width_bytes`, so
+    /// that the type can be shared with a chain whose addresses are that wide. Addresses are
+    /// stored zero-extended to `AccountAddress::LENGTH` bytes, so this fails if any address has
+    /// non-zero bytes that do not fit in the target width.
+    pub fn check_address_width(&self, width_bytes: usize) -> anyhow::Result<()> {
+        check_width(width_bytes)?;
         match self {
             TypeTag::Bool
             | TypeTag::U8
@@ -147,13 +352,79 @@ impl TypeTag {
             | TypeTag::U32
             | TypeTag::U256
             | TypeTag::Address
-            | TypeTag::Signer => (),
-            TypeTag::Vector(inner) => inner.find_addresses_internal(account_addresses),
-            TypeTag::Struct(tag) => {
-                tag.all_addresses_internal(account_addresses);
-            }
+            | TypeTag::Signer => Ok(()),
+            TypeTag::Vector(inner) => inner.check_address_width(width_bytes),
+            TypeTag::Struct(tag) => tag.check_address_width(width_bytes),
         }
     }
+
+    /// Rewrite every address inside of the type with `f`, leaving module and struct names as they
+    /// are.
+    pub fn map_addresses(&self, f: impl Fn(AccountAddress) -> AccountAddress) -> TypeTag {
+        self.map_addresses_internal(&f)
+    }
+
+    fn map_addresses_internal(&self, f: &impl Fn(AccountAddress) -> AccountAddress) -> TypeTag {
+        match self {
+            TypeTag::Bool
+            | TypeTag::U8
+            | TypeTag::U64
+            | TypeTag::U128
+            | TypeTag::U16
+            | TypeTag::U32
+            | TypeTag::U256
+            | TypeTag::Address
+            | TypeTag::Signer => self.clone(),
+            TypeTag::Vector(inner) => TypeTag::Vector(Box::new(inner.map_addresses_internal(f))),
+            TypeTag::Struct(tag) => TypeTag::Struct(Box::new(tag.map_addresses_internal(f))),
+        }
+    }
+
+    /// Check that every module and struct name inside of the type is at most `max_ident_len`
+    /// bytes long, failing with the first identifier (in pre-order) that is too long.
+    pub fn check_identifier_limits(&self, max_ident_len: usize) -> anyhow::Result<()> {
+        match self {
+            TypeTag::Bool
+            | TypeTag::U8
+            | TypeTag::U64
+            | TypeTag::U128
+            | TypeTag::U16
+            | TypeTag::U32
+            | TypeTag::U256
+            | TypeTag::Address
+            | TypeTag::Signer => Ok(()),
+            TypeTag::Vector(inner) => inner.check_identifier_limits(max_ident_len),
+            TypeTag::Struct(tag) => tag.check_identifier_limits(max_ident_len),
+        }
+    }
+
+    /// Parse a type tag, failing if any part of it is nested more than `max_depth` levels deep
+    /// (the element type of `vector<u8>` is one level deep). `FromStr` parses with a limit of
+    /// [`MAX_TYPE_DEPTH`](crate::parsing::parser::MAX_TYPE_DEPTH).
+    pub fn from_str_with_depth_limit(s: &str, max_depth: u64) -> anyhow::Result<TypeTag> {
+        ParsedType::parse_with_depth_limit(s, max_depth)?.into_type_tag(&|_| None)
+    }
+
+    /// Parse a type tag as users tend to write it, in its display or canonical form: addresses
+    /// may be short or full width, with or without their `0x` prefix, and whitespace around
+    /// `::`, `<`, `>` and `,` is ignored. Unlike `FromStr`, addresses are always read as hex, so
+    /// `12::m::S` is `0x12::m::S`, matching `AccountAddress::short_str_lossless`.
+    pub fn from_runtime_string(s: &str) -> anyhow::Result<TypeTag> {
+        TypeTag::from_str(&normalize_runtime_type_string(s))
+    }
+}
+
+/// Callbacks for `TypeTag::visit` and `StructTag::visit`. Every callback defaults to doing
+/// nothing, so visitors only implement the ones they need.
+pub trait TypeTagVisitor {
+    /// Called for every type other than a vector or a struct.
+    fn visit_primitive(&mut self, _ty: &TypeTag) {}
+
+    /// Called for every vector, with its element type, before the element type is visited.
+    fn visit_vector(&mut self, _element: &TypeTag) {}
+
+    /// Called for every struct, before its type parameters are visited.
+    fn visit_struct(&mut self, _tag: &StructTag) {}
 }
 
 impl FromStr for TypeTag {
@@ -170,11 +441,20 @@ pub struct StructTag {
     pub module: Identifier,
     pub name: Identifier,
     // alias for compatibility with old json serialized data.
-    #[serde(rename = "type_args", alias = "type_params")]
+    #[serde(
+        rename = "type_args",
+        alias = "type_params",
+        deserialize_with = "deserialize_type_params"
+    )]
     pub type_params: Vec<TypeTag>,
 }
 
 impl StructTag {
+    /// Start building a `StructTag` piece by piece. See `StructTagBuilder`.
+    pub fn builder() -> StructTagBuilder {
+        StructTagBuilder::default()
+    }
+
     /// Returns true if this is a `StructTag` for an `std::ascii::String` struct defined in the
     /// standard library at address `move_std_addr`.
     pub fn is_ascii_string(&self, move_std_addr: &AccountAddress) -> bool {
@@ -195,6 +475,26 @@ impl StructTag {
         ModuleId::new(self.address, self.module.to_owned())
     }
 
+    /// This struct type as a `TypeTag`, like `TypeTag::from`. The inverse of `TypeTag::as_struct`.
+    ///
+    /// ```
+    /// # use move_core_types::language_storage::{StructTag, TypeTag};
+    /// let sui: StructTag = "0x2::sui::SUI".parse()?;
+    /// let ty = sui.clone().into_type_tag();
+    /// assert_eq!(ty, TypeTag::Struct(Box::new(sui.clone())));
+    /// assert_eq!(ty.as_struct(), Some(&sui));
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn into_type_tag(self) -> TypeTag {
+        TypeTag::Struct(Box::new(self))
+    }
+
+    /// Returns true if this struct is defined in a module published at one of `framework_addrs`.
+    /// Only the struct itself is checked, not its type parameters. See `ModuleId::is_framework`.
+    pub fn is_framework_type(&self, framework_addrs: &[AccountAddress]) -> bool {
+        framework_addrs.contains(&self.address)
+    }
+
     /// Return a canonical string representation of the struct.
     ///
     /// - Structs are represented as fully qualified type names, with or without the prefix "0x"
@@ -213,75 +513,254 @@ impl StructTag {
 
     /// Implements the canonical string representation of the StructTag with optional prefix 0x
     pub fn to_canonical_display(&self, with_prefix: bool) -> impl std::fmt::Display + '_ {
-        struct CanonicalDisplay<'a> {
+        self.to_display(with_prefix, AddressFormat::Full)
+    }
+
+    /// Displays the struct type like `to_canonical_display`, but with addresses written in
+    /// `format`. Only `AddressFormat::Full` gives the canonical string, which is stable: `Short`
+    /// is for human-readable output, like logs.
+    pub fn to_display(
+        &self,
+        with_prefix: bool,
+        format: AddressFormat,
+    ) -> impl std::fmt::Display + '_ {
+        struct StructDisplay<'a> {
             data: &'a StructTag,
             with_prefix: bool,
+            format: AddressFormat,
         }
 
-        impl std::fmt::Display for CanonicalDisplay<'_> {
+        impl std::fmt::Display for StructDisplay<'_> {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                write!(
-                    f,
-                    "{}::{}::{}",
-                    self.data.address.to_canonical_display(self.with_prefix),
-                    self.data.module,
-                    self.data.name
-                )?;
-
-                if let Some(first_ty) = self.data.type_params.first() {
-                    write!(f, "<")?;
-                    write!(f, "{}", first_ty.to_canonical_display(self.with_prefix))?;
-                    for ty in self.data.type_params.iter().skip(1) {
-                        // Note that unlike Display for StructTag, there is no space between the comma and canonical display.
-                        // This follows the original to_canonical_string() implementation.
-                        write!(f, ",{}", ty.to_canonical_display(self.with_prefix))?;
-                    }
-                    write!(f, ">")?;
-                }
-                Ok(())
+                self.data.fmt_with(f, self.with_prefix, self.format)
             }
         }
 
-        CanonicalDisplay {
+        StructDisplay {
             data: self,
             with_prefix,
+            format,
         }
     }
 
+    /// The struct's fully qualified name, `address::module::name`, with the address written as in
+    /// the canonical string (full-width, with an optional "0x" prefix), but without the type
+    /// parameters that `to_canonical_string` appends.
+    pub fn qualified_name(&self, with_prefix: bool) -> String {
+        format!(
+            "{}::{}::{}",
+            self.address.to_canonical_string(with_prefix),
+            self.module,
+            self.name
+        )
+    }
+
+    /// Re-parse the struct type from its canonical string, checking that this gives back an equal
+    /// struct type.
+    pub fn normalize(&self) -> anyhow::Result<StructTag> {
+        let canonical = self.to_canonical_string(/* with_prefix */ true);
+        let parsed = StructTag::from_str(&canonical)?;
+        ensure!(
+            parsed == *self,
+            "Canonical string {canonical} does not round-trip, it parses as {}",
+            parsed.to_canonical_display(/* with_prefix */ true),
+        );
+        Ok(parsed)
+    }
+
+    /// Write the canonical string representation of the StructTag, with optional prefix 0x,
+    /// directly into `f`. Useful for writing struct tags from inside another `Display` impl.
+    pub fn fmt_canonical(&self, f: &mut Formatter<'_>, with_prefix: bool) -> std::fmt::Result {
+        self.fmt_with(f, with_prefix, AddressFormat::Full)
+    }
+
+    fn fmt_with(
+        &self,
+        f: &mut Formatter<'_>,
+        with_prefix: bool,
+        format: AddressFormat,
+    ) -> std::fmt::Result {
+        format.fmt(&self.address, f, with_prefix)?;
+        write!(f, "::{}::{}", self.module, self.name)?;
+
+        if let Some(first_ty) = self.type_params.first() {
+            write!(f, "<")?;
+            write!(f, "{}", first_ty.to_display(with_prefix, format))?;
+            for ty in self.type_params.iter().skip(1) {
+                // Note that unlike Display for StructTag, there is no space between the comma and canonical display.
+                // This follows the original to_canonical_string() implementation.
+                write!(f, ",{}", ty.to_display(with_prefix, format))?;
+            }
+            write!(f, ">")?;
+        }
+        Ok(())
+    }
+
     /// Return the abstract size we use for gas metering
     /// This size might be imperfect but should be consistent across platforms
     /// TODO (ade): use macro to enfornce determinism
     pub fn abstract_size_for_gas_metering(&self) -> AbstractMemorySize {
+        let mut meter = AbstractSizeMeter(self.fields_abstract_size_for_gas_metering());
+        visit_types(&self.type_params, &mut meter);
+        meter.0
+    }
+
+    /// The abstract size of the struct's own fields, excluding its type parameters.
+    fn fields_abstract_size_for_gas_metering(&self) -> AbstractMemorySize {
         // TODO: make this more robust as struct size changes
         self.address.abstract_size_for_gas_metering()
             + self.module.abstract_size_for_gas_metering()
             + self.name.abstract_size_for_gas_metering()
-            + self
-                .type_params
-                .iter()
-                .fold(AbstractMemorySize::new(0), |accum, val| {
-                    accum + val.abstract_size_for_gas_metering()
-                })
     }
 
+    /// Return all of the addresses used inside of the struct type, in the same order as
+    /// `TypeTag::all_addresses`.
     pub fn all_addresses(&self) -> IndexSet<AccountAddress> {
         let mut account_addresses = IndexSet::new();
         self.all_addresses_internal(&mut account_addresses);
         account_addresses
     }
 
+    /// Check that every address inside of the struct type fits in an address width of
+    /// `width_bytes`. See `TypeTag::check_address_width`.
+    pub fn check_address_width(&self, width_bytes: usize) -> anyhow::Result<()> {
+        check_width(width_bytes)?;
+        let significant = AccountAddress::LENGTH - width_bytes;
+        if self.address[..significant].iter().any(|b| *b != 0) {
+            bail!(
+                "Address {} of {}::{} does not fit in {width_bytes} bytes",
+                self.address.to_hex_literal(),
+                self.module,
+                self.name,
+            );
+        }
+
+        self.type_params
+            .iter()
+            .try_for_each(|ty| ty.check_address_width(width_bytes))
+    }
+
+    /// Rewrite every address inside of the struct type, including those in its type parameters,
+    /// with `f`. See `TypeTag::map_addresses`.
+    pub fn map_addresses(&self, f: impl Fn(AccountAddress) -> AccountAddress) -> StructTag {
+        self.map_addresses_internal(&f)
+    }
+
+    fn map_addresses_internal(&self, f: &impl Fn(AccountAddress) -> AccountAddress) -> StructTag {
+        StructTag {
+            address: f(self.address),
+            module: self.module.clone(),
+            name: self.name.clone(),
+            type_params: self
+                .type_params
+                .iter()
+                .map(|ty| ty.map_addresses_internal(f))
+                .collect(),
+        }
+    }
+
+    /// Check that every module and struct name inside of the struct type is at most
+    /// `max_ident_len` bytes long. See `TypeTag::check_identifier_limits`.
+    pub fn check_identifier_limits(&self, max_ident_len: usize) -> anyhow::Result<()> {
+        for ident in [&self.module, &self.name] {
+            ensure!(
+                ident.len() <= max_ident_len,
+                "Identifier '{ident}' in {}::{}::{} is {} bytes long, exceeding the limit of \
+                 {max_ident_len} bytes",
+                self.address.to_hex_literal(),
+                self.module,
+                self.name,
+                ident.len(),
+            );
+        }
+        self.type_params
+            .iter()
+            .try_for_each(|ty| ty.check_identifier_limits(max_ident_len))
+    }
+
+    /// The number of type parameters the struct type is instantiated with.
+    pub fn type_param_arity(&self) -> usize {
+        self.type_params.len()
+    }
+
+    /// Check that the struct type is instantiated with exactly `expected` type parameters.
+    pub fn check_arity(&self, expected: usize) -> anyhow::Result<()> {
+        let arity = self.type_param_arity();
+        ensure!(
+            arity == expected,
+            "{}::{}::{} has {arity} type parameter(s), expected {expected}",
+            self.address.to_hex_literal(),
+            self.module,
+            self.name,
+        );
+        Ok(())
+    }
+
+    /// Serialize the struct type in a fixed layout that does not depend on how `Identifier`,
+    /// `AccountAddress` or `TypeTag` are serialized by serde, so that persisted bytes stay
+    /// readable if their representations change. The layout is:
+    ///
+    /// - A struct type is its address (32 bytes), its module name, its struct name, the number of
+    ///   type parameters, and then each type parameter.
+    /// - A name is its length in bytes followed by its UTF-8 bytes.
+    /// - A type is a one-byte tag: 0 `bool`, 1 `u8`, 2 `u64`, 3 `u128`, 4 `address`, 5 `signer`,
+    ///   6 `vector` (followed by the element type), 7 struct (followed by the struct type), 8
+    ///   `u16`, 9 `u32` or 10 `u256`.
+    /// - Lengths and counts are ULEB128-encoded.
+    ///
+    /// This is the same as the BCS encoding of `StructTag` at the time of writing, but unlike
+    /// BCS, it must never change.
+    pub fn to_stable_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        write_stable_struct_tag(&mut bytes, self);
+        bytes
+    }
+
+    /// Deserialize a struct type written by `to_stable_bytes`. Fails if the bytes do not contain
+    /// exactly one struct type, or if it exceeds `TypeTagLimits::DEFAULT`.
+    pub fn from_stable_bytes(bytes: &[u8]) -> anyhow::Result<StructTag> {
+        let mut reader = StableBytesReader { bytes, nodes: 0 };
+        let tag = reader.read_struct_tag(0)?;
+        ensure!(
+            reader.bytes.is_empty(),
+            "{} unexpected trailing byte(s) after struct tag",
+            reader.bytes.len(),
+        );
+        Ok(tag)
+    }
+
+    /// A key identifying this struct type that is guaranteed to be the same across processes and
+    /// crate upgrades, for persisting as a deduplication key. It is the hex encoding of a version
+    /// byte (`STABLE_DEDUP_KEY_VERSION`), followed by the BLAKE2b-256 hash of `to_stable_bytes`.
+    ///
+    /// The key for a given struct type only changes if the version byte is bumped, which only
+    /// happens if the hash function or the hashed layout has to change. Keys produced under
+    /// different versions never compare equal, so a store that holds keys of both versions needs
+    /// to rehash its entries (or look up both keys) when upgrading.
+    pub fn stable_dedup_key(&self) -> String {
+        let mut key = vec![STABLE_DEDUP_KEY_VERSION];
+        key.extend_from_slice(&Blake2b::<U32>::digest(self.to_stable_bytes()));
+        hex::encode(key)
+    }
+
     pub fn all_addresses_internal(&self, addrs: &mut IndexSet<AccountAddress>) {
-        let StructTag {
-            address,
-            module: _,
-            name: _,
-            type_params,
-        } = self;
         // Traverse in a pre-order manner. So the address is added first, then the type parameters.
-        addrs.insert(*address);
-        for tag in type_params {
-            tag.find_addresses_internal(addrs);
-        }
+        self.visit(&mut AddressCollector(addrs));
+    }
+
+    /// Every struct type inside of this struct type, at any depth, starting with the struct type
+    /// itself, in the order that `visit` walks the type. Equivalent to `TypeTag::struct_tags` for
+    /// this struct type.
+    pub fn leaf_structs(&self) -> Vec<&StructTag> {
+        std::iter::once(self)
+            .chain(structs_in(&self.type_params))
+            .collect()
+    }
+
+    /// Walk the struct type in pre-order, starting with the struct itself. See `TypeTag::visit`.
+    pub fn visit<V: TypeTagVisitor>(&self, visitor: &mut V) {
+        visitor.visit_struct(self);
+        visit_types(&self.type_params, visitor)
     }
 }
 
@@ -293,6 +772,370 @@ impl FromStr for StructTag {
     }
 }
 
+/// Builds a `StructTag` from its parts, without spelling out `Identifier`s. The address, module
+/// and name are required, and the module and name are only checked to be valid identifiers when
+/// the tag is built.
+///
+/// ```
+/// # use move_core_types::{account_address::AccountAddress, language_storage::{StructTag, TypeTag}};
+/// let sui = StructTag::builder()
+///     .address(AccountAddress::TWO)
+///     .module("sui")
+///     .name("SUI")
+///     .build()?;
+///
+/// let coin = StructTag::builder()
+///     .address(AccountAddress::ONE)
+///     .module("coin")
+///     .name("Coin")
+///     .type_param(TypeTag::Struct(Box::new(sui)))
+///     .build()?;
+///
+/// assert_eq!(coin, "0x1::coin::Coin<0x2::sui::SUI>".parse::<StructTag>()?);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StructTagBuilder {
+    address: Option<AccountAddress>,
+    module: Option<String>,
+    name: Option<String>,
+    type_params: Vec<TypeTag>,
+}
+
+impl StructTagBuilder {
+    pub fn address(mut self, address: AccountAddress) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    pub fn module(mut self, module: &str) -> Self {
+        self.module = Some(module.to_owned());
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Add `type_param` after any type parameters added so far.
+    pub fn type_param(mut self, type_param: TypeTag) -> Self {
+        self.type_params.push(type_param);
+        self
+    }
+
+    /// Add each of `type_params`, in order, after any type parameters added so far.
+    pub fn type_params(mut self, type_params: impl IntoIterator<Item = TypeTag>) -> Self {
+        self.type_params.extend(type_params);
+        self
+    }
+
+    /// Build the `StructTag`, failing if its address, module or name is missing, or if its
+    /// module or name is not a valid identifier.
+    pub fn build(self) -> anyhow::Result<StructTag> {
+        let Self {
+            address,
+            module,
+            name,
+            type_params,
+        } = self;
+        let Some(address) = address else {
+            bail!("Struct tag is missing its address");
+        };
+        let Some(module) = module else {
+            bail!("Struct tag is missing its module");
+        };
+        let Some(name) = name else {
+            bail!("Struct tag is missing its name");
+        };
+        Ok(StructTag {
+            address,
+            module: Identifier::new(module)?,
+            name: Identifier::new(name)?,
+            type_params,
+        })
+    }
+}
+
+/// Rewrite `s` into a form that `ParsedType` accepts with the same meaning: whitespace next to
+/// punctuation is dropped, and every address (an identifier or number directly followed by `::`,
+/// which is not itself a module or type name) that is a bare run of hex digits gains a `0x`
+/// prefix.
+fn normalize_runtime_type_string(s: &str) -> String {
+    let is_punct = |c: char| matches!(c, ':' | '<' | '>' | ',');
+
+    let mut compact = String::with_capacity(s.len());
+    let mut pending_space = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space && !is_punct(c) && compact.ends_with(|p: char| !is_punct(p)) {
+            compact.push(' ');
+        }
+        pending_space = false;
+        compact.push(c);
+    }
+
+    let mut normalized = String::with_capacity(compact.len() + 2);
+    let mut rest = compact.as_str();
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric() || c == '_') {
+        let (before, word) = rest.split_at(start);
+        let len = word
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(word.len());
+        let (word, after) = word.split_at(len);
+
+        let is_address = after.starts_with("::") && !before.ends_with("::");
+        normalized.push_str(before);
+        if is_address && !word.starts_with("0x") && word.bytes().all(|b| b.is_ascii_hexdigit()) {
+            normalized.push_str("0x");
+        }
+        normalized.push_str(word);
+        rest = after;
+    }
+    normalized.push_str(rest);
+    normalized
+}
+
+/// Visit each of `types` in turn, in pre-order, using an explicit work stack rather than
+/// recursion.
+fn visit_types<'a, V: TypeTagVisitor>(
+    types: impl IntoIterator<Item = &'a TypeTag, IntoIter: DoubleEndedIterator>,
+    visitor: &mut V,
+) {
+    // Children are pushed in reverse so that they are popped, and so visited, in order.
+    let mut stack: Vec<&TypeTag> = types.into_iter().rev().collect();
+    while let Some(ty) = stack.pop() {
+        match ty {
+            TypeTag::Bool
+            | TypeTag::U8
+            | TypeTag::U64
+            | TypeTag::U128
+            | TypeTag::Address
+            | TypeTag::Signer
+            | TypeTag::U16
+            | TypeTag::U32
+            | TypeTag::U256 => visitor.visit_primitive(ty),
+            TypeTag::Vector(inner) => {
+                visitor.visit_vector(inner);
+                stack.push(inner);
+            }
+            TypeTag::Struct(tag) => {
+                visitor.visit_struct(tag);
+                stack.extend(tag.type_params.iter().rev());
+            }
+        }
+    }
+}
+
+/// Every struct type inside of `types`, in pre-order, found using an explicit work stack like
+/// `visit_types`.
+fn structs_in<'a>(
+    types: impl IntoIterator<Item = &'a TypeTag, IntoIter: DoubleEndedIterator>,
+) -> impl Iterator<Item = &'a StructTag> {
+    let mut stack: Vec<&TypeTag> = types.into_iter().rev().collect();
+    std::iter::from_fn(move || {
+        while let Some(ty) = stack.pop() {
+            match ty {
+                TypeTag::Vector(inner) => stack.push(inner),
+                TypeTag::Struct(tag) => {
+                    stack.extend(tag.type_params.iter().rev());
+                    return Some(&**tag);
+                }
+                _ => (),
+            }
+        }
+        None
+    })
+}
+
+/// Collects the addresses of every struct in a type, in the order they are visited.
+struct AddressCollector<'a>(&'a mut IndexSet<AccountAddress>);
+
+impl TypeTagVisitor for AddressCollector<'_> {
+    fn visit_struct(&mut self, tag: &StructTag) {
+        self.0.insert(tag.address);
+    }
+}
+
+/// Counts every node in a type.
+struct NodeCounter(u64);
+
+impl TypeTagVisitor for NodeCounter {
+    fn visit_primitive(&mut self, _ty: &TypeTag) {
+        self.0 += 1;
+    }
+
+    fn visit_vector(&mut self, _element: &TypeTag) {
+        self.0 += 1;
+    }
+
+    fn visit_struct(&mut self, _tag: &StructTag) {
+        self.0 += 1;
+    }
+}
+
+/// Sums the abstract size of every node in a type. Additions saturate, so the result does not
+/// depend on the order in which nodes are visited.
+struct AbstractSizeMeter(AbstractMemorySize);
+
+impl TypeTagVisitor for AbstractSizeMeter {
+    fn visit_primitive(&mut self, _ty: &TypeTag) {
+        self.0 += *TYPETAG_ENUM_ABSTRACT_SIZE;
+    }
+
+    fn visit_vector(&mut self, _element: &TypeTag) {
+        self.0 += *TYPETAG_ENUM_ABSTRACT_SIZE;
+    }
+
+    fn visit_struct(&mut self, tag: &StructTag) {
+        self.0 += *TYPETAG_ENUM_ABSTRACT_SIZE + tag.fields_abstract_size_for_gas_metering();
+    }
+}
+
+fn write_uleb128(bytes: &mut Vec<u8>, mut n: usize) {
+    while n >= 0x80 {
+        bytes.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    bytes.push(n as u8);
+}
+
+fn write_stable_struct_tag(bytes: &mut Vec<u8>, tag: &StructTag) {
+    bytes.extend_from_slice(tag.address.as_ref());
+    for ident in [&tag.module, &tag.name] {
+        write_uleb128(bytes, ident.len());
+        bytes.extend_from_slice(ident.as_bytes());
+    }
+    write_uleb128(bytes, tag.type_params.len());
+    for ty in &tag.type_params {
+        write_stable_type_tag(bytes, ty);
+    }
+}
+
+fn write_stable_type_tag(bytes: &mut Vec<u8>, ty: &TypeTag) {
+    match ty {
+        TypeTag::Bool => bytes.push(0),
+        TypeTag::U8 => bytes.push(1),
+        TypeTag::U64 => bytes.push(2),
+        TypeTag::U128 => bytes.push(3),
+        TypeTag::Address => bytes.push(4),
+        TypeTag::Signer => bytes.push(5),
+        TypeTag::Vector(element) => {
+            bytes.push(6);
+            write_stable_type_tag(bytes, element);
+        }
+        TypeTag::Struct(tag) => {
+            bytes.push(7);
+            write_stable_struct_tag(bytes, tag);
+        }
+        TypeTag::U16 => bytes.push(8),
+        TypeTag::U32 => bytes.push(9),
+        TypeTag::U256 => bytes.push(10),
+    }
+}
+
+/// Reads the layout written by `StructTag::to_stable_bytes`, counting nodes and nesting depth
+/// against `TypeTagLimits::DEFAULT`.
+struct StableBytesReader<'a> {
+    bytes: &'a [u8],
+    nodes: u64,
+}
+
+impl<'a> StableBytesReader<'a> {
+    fn read_bytes(&mut self, len: usize) -> anyhow::Result<&'a [u8]> {
+        ensure!(
+            len <= self.bytes.len(),
+            "Unexpected end of struct tag bytes"
+        );
+        let (read, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(read)
+    }
+
+    fn read_uleb128(&mut self) -> anyhow::Result<usize> {
+        let mut n: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_bytes(1)?[0];
+            n |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(usize::try_from(n)?);
+            }
+        }
+        bail!("ULEB128 value does not fit in 64 bits")
+    }
+
+    fn read_identifier(&mut self) -> anyhow::Result<Identifier> {
+        let len = self.read_uleb128()?;
+        let ident = std::str::from_utf8(self.read_bytes(len)?)?;
+        Identifier::new(ident)
+    }
+
+    fn enter(&mut self, depth: u64) -> anyhow::Result<()> {
+        let TypeTagLimits {
+            max_depth,
+            max_nodes,
+        } = TypeTagLimits::DEFAULT;
+        self.nodes += 1;
+        ensure!(
+            depth <= max_depth && self.nodes <= max_nodes,
+            "Type exceeds maximum nesting depth or node count"
+        );
+        Ok(())
+    }
+
+    fn read_struct_tag(&mut self, depth: u64) -> anyhow::Result<StructTag> {
+        self.enter(depth)?;
+        let address = AccountAddress::from_bytes(self.read_bytes(AccountAddress::LENGTH)?)?;
+        let module = self.read_identifier()?;
+        let name = self.read_identifier()?;
+        let count = self.read_uleb128()?;
+        let mut type_params = vec![];
+        for _ in 0..count {
+            type_params.push(self.read_type_tag(depth + 1)?);
+        }
+        Ok(StructTag {
+            address,
+            module,
+            name,
+            type_params,
+        })
+    }
+
+    fn read_type_tag(&mut self, depth: u64) -> anyhow::Result<TypeTag> {
+        // A struct type counts as one node, however it is reached.
+        let tag = self.read_bytes(1)?[0];
+        if tag != 7 {
+            self.enter(depth)?;
+        }
+        Ok(match tag {
+            0 => TypeTag::Bool,
+            1 => TypeTag::U8,
+            2 => TypeTag::U64,
+            3 => TypeTag::U128,
+            4 => TypeTag::Address,
+            5 => TypeTag::Signer,
+            6 => TypeTag::Vector(Box::new(self.read_type_tag(depth + 1)?)),
+            7 => TypeTag::Struct(Box::new(self.read_struct_tag(depth)?)),
+            8 => TypeTag::U16,
+            9 => TypeTag::U32,
+            10 => TypeTag::U256,
+            tag => bail!("Unknown type tag {tag}"),
+        })
+    }
+}
+
+fn check_width(width_bytes: usize) -> anyhow::Result<()> {
+    ensure!(
+        0 < width_bytes && width_bytes <= AccountAddress::LENGTH,
+        "Address width must be between 1 and {} bytes, got {width_bytes}",
+        AccountAddress::LENGTH,
+    );
+    Ok(())
+}
+
 /// Represents the initial key into global storage where we first index by the address, and then
 /// the struct tag
 #[derive(Serialize, Deserialize, Debug, PartialEq, Hash, Eq, Clone, PartialOrd, Ord)]
@@ -322,6 +1165,28 @@ impl ModuleId {
         &self.address
     }
 
+    /// Borrow the module's address and name together, e.g. to destructure them without cloning.
+    pub fn as_parts(&self) -> (&AccountAddress, &IdentStr) {
+        (&self.address, &self.name)
+    }
+
+    /// Returns true if this module is published at one of `framework_addrs`, e.g. the standard
+    /// library's `CORE_CODE_ADDRESS` and the addresses of any other system packages.
+    pub fn is_framework(&self, framework_addrs: &[AccountAddress]) -> bool {
+        framework_addrs.contains(&self.address)
+    }
+
+    /// The struct type called `name` in this module, instantiated with `type_params`. The inverse
+    /// of `StructTag::module_id`.
+    pub fn struct_tag(&self, name: Identifier, type_params: Vec<TypeTag>) -> StructTag {
+        StructTag {
+            address: self.address,
+            module: self.name.clone(),
+            name,
+            type_params,
+        }
+    }
+
     pub fn to_canonical_string(&self, with_prefix: bool) -> String {
         self.to_canonical_display(with_prefix).to_string()
     }
@@ -416,6 +1281,317 @@ impl From<StructTag> for TypeTag {
     }
 }
 
+/// A `StructTag` that remembers its canonical strings once they have been computed, for code that
+/// renders the same tag many times. It derefs to the tag it wraps, and serializes, compares and
+/// hashes exactly like it: whether the strings have been computed yet makes no difference.
+///
+/// The tag cannot be modified in place, as that would leave the cached strings stale. Use
+/// `into_inner` to get it back out.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct CachedStructTag {
+    tag: StructTag,
+    #[serde(skip)]
+    with_prefix: OnceCell<String>,
+    #[serde(skip)]
+    without_prefix: OnceCell<String>,
+}
+
+impl CachedStructTag {
+    pub fn new(tag: StructTag) -> Self {
+        Self {
+            tag,
+            with_prefix: OnceCell::new(),
+            without_prefix: OnceCell::new(),
+        }
+    }
+
+    /// The same string as `StructTag::to_canonical_string`, computed on first use.
+    pub fn canonical_string(&self, with_prefix: bool) -> &str {
+        let cell = if with_prefix {
+            &self.with_prefix
+        } else {
+            &self.without_prefix
+        };
+        cell.get_or_init(|| self.tag.to_canonical_string(with_prefix))
+    }
+
+    pub fn into_inner(self) -> StructTag {
+        self.tag
+    }
+}
+
+impl From<StructTag> for CachedStructTag {
+    fn from(tag: StructTag) -> Self {
+        Self::new(tag)
+    }
+}
+
+impl From<CachedStructTag> for StructTag {
+    fn from(tag: CachedStructTag) -> Self {
+        tag.into_inner()
+    }
+}
+
+impl Deref for CachedStructTag {
+    type Target = StructTag;
+
+    fn deref(&self) -> &StructTag {
+        &self.tag
+    }
+}
+
+impl Borrow<StructTag> for CachedStructTag {
+    fn borrow(&self) -> &StructTag {
+        &self.tag
+    }
+}
+
+impl PartialEq for CachedStructTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl Eq for CachedStructTag {}
+
+impl Hash for CachedStructTag {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tag.hash(state)
+    }
+}
+
+impl PartialOrd for CachedStructTag {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CachedStructTag {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.tag.cmp(&other.tag)
+    }
+}
+
+impl Display for CachedStructTag {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        self.tag.fmt(f)
+    }
+}
+
+/// The canonical strings of `tags` (see `TypeTag::to_canonical_string`), in sorted order. Each tag
+/// is formatted once, which is cheaper than sorting the tags by their canonical strings, as that
+/// formats both sides of every comparison.
+pub fn format_sorted_batch(tags: &[TypeTag], with_prefix: bool) -> Vec<String> {
+    let mut strings: Vec<_> = tags
+        .iter()
+        .map(|tag| tag.to_canonical_string(with_prefix))
+        .collect();
+    strings.sort_unstable();
+    strings
+}
+
+/// Bounds on the shape of a `TypeTag` that is being deserialized, checked as it is decoded so
+/// that hostile payloads cannot cause unbounded recursion. Depth is counted as in
+/// `TypeTag::from_str_with_depth_limit`, and the node count includes the outermost type.
+///
+/// Deserializing a `TypeTag` directly uses `TypeTagLimits::DEFAULT`. Use `BoundedTypeTag` to
+/// deserialize with other limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TypeTagLimits {
+    pub max_depth: u64,
+    pub max_nodes: u64,
+}
+
+impl TypeTagLimits {
+    /// The same limits that apply when parsing a type tag from a string.
+    pub const DEFAULT: TypeTagLimits = TypeTagLimits {
+        max_depth: MAX_TYPE_DEPTH,
+        max_nodes: MAX_TYPE_NODE_COUNT,
+    };
+}
+
+/// A `TypeTag` that is deserialized with a nesting depth of at most `MAX_DEPTH` and at most
+/// `MAX_NODES` nodes, rather than `TypeTagLimits::DEFAULT`. It serializes exactly like the
+/// `TypeTag` it wraps.
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(transparent)]
+pub struct BoundedTypeTag<const MAX_DEPTH: u64, const MAX_NODES: u64>(pub TypeTag);
+
+impl<'de, const MAX_DEPTH: u64, const MAX_NODES: u64> Deserialize<'de>
+    for BoundedTypeTag<MAX_DEPTH, MAX_NODES>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let limits = TypeTagLimits {
+            max_depth: MAX_DEPTH,
+            max_nodes: MAX_NODES,
+        };
+        let previous = TYPE_TAG_LIMITS.with(|l| l.replace(limits));
+        let tag = TypeTag::deserialize(deserializer);
+        TYPE_TAG_LIMITS.with(|l| l.set(previous));
+        Ok(BoundedTypeTag(tag?))
+    }
+}
+
+thread_local! {
+    /// The limits that apply to type tags being deserialized on this thread.
+    static TYPE_TAG_LIMITS: Cell<TypeTagLimits> = const { Cell::new(TypeTagLimits::DEFAULT) };
+
+    /// The depth and node count reached so far in the type tag being deserialized on this
+    /// thread. Both are zero when no type tag is being deserialized.
+    static TYPE_TAG_NESTING: Cell<(u64, u64)> = const { Cell::new((0, 0)) };
+}
+
+/// Tracks descent into the element type of a vector or the type parameters of a struct being
+/// deserialized, and returns to the enclosing depth when dropped. Structs without type parameters
+/// are not descended into, so they do not add to the depth.
+struct NestingGuard;
+
+impl NestingGuard {
+    fn enter<E: de::Error>() -> Result<Self, E> {
+        TYPE_TAG_NESTING.with(|nesting| {
+            let (depth, nodes) = nesting.get();
+            // Every type is nested in the outermost one, which is counted on the way in.
+            let nodes = if depth == 0 { 1 } else { nodes };
+            nesting.set((depth + 1, nodes));
+        });
+        let guard = NestingGuard;
+
+        let (depth, _) = TYPE_TAG_NESTING.with(Cell::get);
+        let max_depth = TYPE_TAG_LIMITS.with(Cell::get).max_depth;
+        if depth > max_depth {
+            return Err(E::custom(format!(
+                "Type tag exceeds maximum nesting depth of {max_depth}"
+            )));
+        }
+        Ok(guard)
+    }
+
+    fn count_node<E: de::Error>(&self) -> Result<(), E> {
+        let (depth, nodes) = TYPE_TAG_NESTING.with(Cell::get);
+        TYPE_TAG_NESTING.with(|nesting| nesting.set((depth, nodes + 1)));
+        let max_nodes = TYPE_TAG_LIMITS.with(Cell::get).max_nodes;
+        if nodes + 1 > max_nodes {
+            return Err(E::custom(format!(
+                "Type tag exceeds maximum node count of {max_nodes}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for NestingGuard {
+    fn drop(&mut self) {
+        TYPE_TAG_NESTING.with(|nesting| {
+            let (depth, nodes) = nesting.get();
+            let depth = depth - 1;
+            nesting.set((depth, if depth == 0 { 0 } else { nodes }));
+        });
+    }
+}
+
+fn deserialize_vector_element<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Box<TypeTag>, D::Error> {
+    let guard = NestingGuard::enter()?;
+    guard.count_node()?;
+    Box::<TypeTag>::deserialize(deserializer)
+}
+
+fn deserialize_type_params<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<TypeTag>, D::Error> {
+    struct TypeParamsVisitor;
+
+    impl<'de> de::Visitor<'de> for TypeParamsVisitor {
+        type Value = Vec<TypeTag>;
+
+        fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+            write!(f, "a sequence of type tags")
+        }
+
+        fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            // Only descend a level once there turns out to be a type parameter.
+            let mut guard = None;
+            let mut type_params = Vec::new();
+            while let Some(ty) = seq.next_element_seed(CountedTypeTag(&mut guard))? {
+                type_params.push(ty);
+            }
+            Ok(type_params)
+        }
+    }
+
+    /// Counts each type parameter as a node before deserializing it.
+    struct CountedTypeTag<'a>(&'a mut Option<NestingGuard>);
+
+    impl<'de> de::DeserializeSeed<'de> for CountedTypeTag<'_> {
+        type Value = TypeTag;
+
+        fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<TypeTag, D::Error> {
+            let guard = match self.0 {
+                Some(guard) => guard,
+                None => self.0.insert(NestingGuard::enter()?),
+            };
+            guard.count_node()?;
+            TypeTag::deserialize(deserializer)
+        }
+    }
+
+    deserializer.deserialize_seq(TypeParamsVisitor)
+}
+
+/// Serde helpers that represent a `TypeTag` as its canonical string, for use with
+/// `#[serde(with = "type_tag_canonical_json")]`.
+///
+/// The derived implementation renders a type tag as nested objects, with addresses as bare hex
+/// and primitive types as lowercase names, which is a shape specific to this crate. Prefer this
+/// module for JSON (or other human-readable output) shared with other services, where a type is
+/// expected to be written the same way everywhere: as the output of
+/// `TypeTag::to_canonical_string(true)`, e.g. `0x000...0002::coin::Coin<0x000...0002::sui::SUI>`.
+///
+/// Deserialization is lenient. It accepts any string that `TypeTag::from_str` does, including
+/// short addresses such as `0x2::sui::SUI`, as well as the derived representation. Formats that
+/// are not human-readable, such as BCS, use the derived representation unchanged.
+pub mod type_tag_canonical_json {
+    use super::TypeTag;
+    use serde::{Deserialize, Deserializer, Serializer, de};
+    use std::{fmt::Formatter, str::FromStr};
+
+    pub fn serialize<S: Serializer>(tag: &TypeTag, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&tag.to_canonical_display(true))
+        } else {
+            serde::Serialize::serialize(tag, serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TypeTag, D::Error> {
+        if !deserializer.is_human_readable() {
+            return TypeTag::deserialize(deserializer);
+        }
+
+        struct CanonicalVisitor;
+
+        impl<'de> de::Visitor<'de> for CanonicalVisitor {
+            type Value = TypeTag;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                write!(f, "a type tag string")
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<TypeTag, E> {
+                TypeTag::from_str(s).map_err(E::custom)
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<TypeTag, A::Error> {
+                TypeTag::deserialize(de::value::MapAccessDeserializer::new(map))
+            }
+        }
+
+        deserializer.deserialize_any(CanonicalVisitor)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{ModuleId, TypeTag};
@@ -439,6 +1615,22 @@ mod tests {
         assert_eq!(mem::size_of::<TypeTag>(), 16);
     }
 
+    #[test]
+    fn test_struct_tag_builder_serde() {
+        let a = TypeTag::Struct(Box::new(
+            StructTag::builder()
+                .address(AccountAddress::ONE)
+                .module("abc")
+                .name("abc")
+                .type_param(TypeTag::U8)
+                .build()
+                .unwrap(),
+        ));
+        let b = serde_json::to_string(&a).unwrap();
+        let c: TypeTag = serde_json::from_str(&b).unwrap();
+        assert_eq!(a, c);
+    }
+
     #[test]
     fn test_module_id_display() {
         let id = ModuleId::new(AccountAddress::ONE, ident_str!("foo").to_owned());
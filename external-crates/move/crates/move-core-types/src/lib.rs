@@ -20,6 +20,8 @@ pub mod parsing;
 pub mod proptest_types;
 pub mod resolver;
 pub mod runtime_value;
+#[cfg(feature = "struct-tag-matcher")]
+pub mod struct_tag_matcher;
 pub mod u256;
 #[cfg(test)]
 mod unit_tests;
@@ -13,8 +13,10 @@ use crate::{
 use anyhow::{Result, anyhow, bail};
 use std::{fmt::Display, iter::Peekable, num::ParseIntError};
 
-const MAX_TYPE_DEPTH: u64 = 128;
-const MAX_TYPE_NODE_COUNT: u64 = 256;
+/// The maximum nesting depth of a type parsed without an explicit depth limit.
+pub const MAX_TYPE_DEPTH: u64 = 128;
+/// The maximum number of nodes in a type parsed from a string.
+pub const MAX_TYPE_NODE_COUNT: u64 = 256;
 // See: https://stackoverflow.com/questions/43787672/the-max-number-of-digits-in-an-int-based-on-number-of-bits
 const U256_MAX_DECIMAL_DIGITS: usize = 241 * AccountAddress::LENGTH / 100 + 1;
 
@@ -40,6 +42,11 @@ impl ParsedType {
     pub fn parse(s: &str) -> Result<ParsedType> {
         parse(s, |parser| parser.parse_type())
     }
+
+    /// Parse a type, failing if any part of it is nested more than `max_depth` levels deep.
+    pub fn parse_with_depth_limit(s: &str, max_depth: u64) -> Result<ParsedType> {
+        parse(s, |parser| parser.parse_type_with_depth_limit(max_depth))
+    }
 }
 
 impl ParsedModuleId {
@@ -164,7 +171,11 @@ impl<'a, I: Iterator<Item = (TypeToken, &'a str)>> Parser<'a, TypeToken, I> {
     }
 
     pub fn parse_type(&mut self) -> Result<ParsedType> {
-        self.parse_type_impl(0)
+        self.parse_type_with_depth_limit(MAX_TYPE_DEPTH)
+    }
+
+    pub fn parse_type_with_depth_limit(&mut self, max_depth: u64) -> Result<ParsedType> {
+        self.parse_type_impl(0, max_depth)
     }
 
     pub fn parse_module_id_impl(
@@ -194,10 +205,10 @@ impl<'a, I: Iterator<Item = (TypeToken, &'a str)>> Parser<'a, TypeToken, I> {
         Ok(ParsedFqName { module, name })
     }
 
-    fn parse_type_impl(&mut self, depth: u64) -> Result<ParsedType> {
+    fn parse_type_impl(&mut self, depth: u64, max_depth: u64) -> Result<ParsedType> {
         self.count += 1;
 
-        if depth > MAX_TYPE_DEPTH || self.count > MAX_TYPE_NODE_COUNT {
+        if depth > max_depth || self.count > MAX_TYPE_NODE_COUNT {
             bail!("Type exceeds maximum nesting depth or node count")
         }
 
@@ -213,7 +224,7 @@ impl<'a, I: Iterator<Item = (TypeToken, &'a str)>> Parser<'a, TypeToken, I> {
             (TypeToken::Ident, "signer") => ParsedType::Signer,
             (TypeToken::Ident, "vector") => {
                 self.advance(TypeToken::Lt)?;
-                let ty = self.parse_type_impl(depth + 1)?;
+                let ty = self.parse_type_impl(depth + 1, max_depth)?;
                 self.advance(TypeToken::Gt)?;
                 ParsedType::Vector(Box::new(ty))
             }
@@ -224,7 +235,7 @@ impl<'a, I: Iterator<Item = (TypeToken, &'a str)>> Parser<'a, TypeToken, I> {
                     Some(TypeToken::Lt) => {
                         self.advance(TypeToken::Lt)?;
                         let type_args = self.parse_list(
-                            |parser| parser.parse_type_impl(depth + 1),
+                            |parser| parser.parse_type_impl(depth + 1, max_depth),
                             TypeToken::Comma,
                             TypeToken::Gt,
                             true,
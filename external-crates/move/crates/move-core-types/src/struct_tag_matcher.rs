@@ -0,0 +1,51 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A helper for matching many `StructTag`s against a fixed set of target tags, without comparing
+//! each candidate against every target in turn.
+
+use crate::language_storage::StructTag;
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+/// Matches struct tags against a set of target tags. The targets' hashes are computed up front, so
+/// that a lookup hashes the candidate once and only falls back to a full (deep) equality check
+/// against the targets that share its hash.
+#[derive(Debug, Clone)]
+pub struct StructTagMatcher {
+    targets: Vec<StructTag>,
+    by_hash: HashMap<u64, Vec<usize>>,
+}
+
+impl StructTagMatcher {
+    pub fn new(targets: impl IntoIterator<Item = StructTag>) -> Self {
+        let targets: Vec<_> = targets.into_iter().collect();
+        let mut by_hash: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (i, target) in targets.iter().enumerate() {
+            by_hash.entry(content_hash(target)).or_default().push(i);
+        }
+        Self { targets, by_hash }
+    }
+
+    /// The targets this matcher was built with, in their original order.
+    pub fn targets(&self) -> &[StructTag] {
+        &self.targets
+    }
+
+    /// Return the index (into `targets`) of the first target equal to `tag`, if there is one.
+    pub fn matches_any(&self, tag: &StructTag) -> Option<usize> {
+        self.by_hash
+            .get(&content_hash(tag))?
+            .iter()
+            .copied()
+            .find(|i| &self.targets[*i] == tag)
+    }
+}
+
+fn content_hash(tag: &StructTag) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tag.hash(&mut hasher);
+    hasher.finish()
+}
@@ -6,16 +6,121 @@ use crate::{
     account_address::AccountAddress,
     gas_algebra::AbstractMemorySize,
     identifier::{IdentStr, Identifier},
-    language_storage::{ModuleId, StructTag, TYPETAG_ENUM_ABSTRACT_SIZE, TypeTag},
+    language_storage::{
+        AddressFormat, BoundedTypeTag, CORE_CODE_ADDRESS, CachedStructTag, ModuleId,
+        STABLE_DEDUP_KEY_VERSION, StructTag, TYPETAG_ENUM_ABSTRACT_SIZE, TypeTag, TypeTagLimits,
+        TypeTagVisitor, format_sorted_batch, type_tag_canonical_json,
+    },
+    parsing::parser::MAX_TYPE_DEPTH,
 };
 use bcs::test_helpers::assert_canonical_encode_decode;
 use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashSet, hash_map::DefaultHasher},
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct CanonicalJson {
+    #[serde(with = "type_tag_canonical_json")]
+    tag: TypeTag,
+}
+
+fn arb_type_tag() -> impl Strategy<Value = TypeTag> {
+    let leaf = prop_oneof![
+        Just(TypeTag::Bool),
+        Just(TypeTag::U8),
+        Just(TypeTag::U16),
+        Just(TypeTag::U32),
+        Just(TypeTag::U64),
+        Just(TypeTag::U128),
+        Just(TypeTag::U256),
+        Just(TypeTag::Address),
+        Just(TypeTag::Signer),
+    ];
+    leaf.prop_recursive(4, 32, 3, |inner| {
+        prop_oneof![
+            inner.clone().prop_map(|t| TypeTag::Vector(Box::new(t))),
+            arb_struct_tag_with(inner).prop_map(|s| TypeTag::Struct(Box::new(s))),
+        ]
+    })
+}
+
+fn arb_struct_tag_with(
+    type_param: impl Strategy<Value = TypeTag>,
+) -> impl Strategy<Value = StructTag> {
+    (
+        any::<AccountAddress>(),
+        any::<Identifier>(),
+        any::<Identifier>(),
+        prop::collection::vec(type_param, 0..3),
+    )
+        .prop_map(|(address, module, name, type_params)| StructTag {
+            address,
+            module,
+            name,
+            type_params,
+        })
+}
 
 proptest! {
     #[test]
     fn test_module_id_canonical_roundtrip(module_id in any::<ModuleId>()) {
         assert_canonical_encode_decode(module_id);
     }
+
+    #[test]
+    fn test_module_id_string_roundtrip(module_id in any::<ModuleId>()) {
+        // `Display` is left out: it omits the 0x prefix, so its addresses are not read back as
+        // hex.
+        for s in [
+            module_id.short_str_lossless(),
+            module_id.to_canonical_string(/* with_prefix */ true),
+        ] {
+            prop_assert_eq!(ModuleId::from_str(&s).unwrap(), module_id.clone());
+        }
+    }
+
+    #[test]
+    fn test_struct_tag_canonical_string_roundtrip(tag in arb_struct_tag_with(arb_type_tag())) {
+        let canonical = tag.to_canonical_string(/* with_prefix */ true);
+        prop_assert_eq!(StructTag::from_str(&canonical).unwrap(), tag.clone());
+        prop_assert_eq!(tag.normalize().unwrap(), tag);
+    }
+
+    #[test]
+    fn test_struct_tag_stable_bytes_roundtrip(tag in arb_struct_tag_with(arb_type_tag())) {
+        let bytes = tag.to_stable_bytes();
+        prop_assert_eq!(StructTag::from_stable_bytes(&bytes).unwrap(), tag.clone());
+        // Matches BCS, for now.
+        prop_assert_eq!(bytes, bcs::to_bytes(&tag).unwrap());
+    }
+
+    #[test]
+    fn test_type_tag_canonical_string_roundtrip(tag in arb_type_tag()) {
+        let canonical = tag.to_canonical_string(/* with_prefix */ true);
+        prop_assert_eq!(TypeTag::from_str(&canonical).unwrap(), tag);
+    }
+
+    #[test]
+    fn test_type_tag_canonical_json_roundtrip(tag in arb_type_tag()) {
+        let value = CanonicalJson { tag };
+        let json = serde_json::to_string(&value).unwrap();
+        prop_assert_eq!(serde_json::from_str::<CanonicalJson>(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn test_format_sorted_batch(
+        mut tags in proptest::collection::vec(arb_type_tag(), 0..16),
+        with_prefix in any::<bool>(),
+    ) {
+        let batch = format_sorted_batch(&tags, with_prefix);
+        tags.sort_by_key(|tag| tag.to_canonical_string(with_prefix));
+        let naive: Vec<_> = tags.iter().map(|tag| tag.to_canonical_string(with_prefix)).collect();
+        prop_assert_eq!(batch, naive);
+    }
 }
 
 #[test]
@@ -127,3 +232,1277 @@ fn test_type_tag_deserialize_case_insensitive() {
 
     assert_eq!(current_json, lower_case_json);
 }
+
+#[test]
+fn test_type_tag_check_address_width() {
+    // An address taken from a chain with 20-byte addresses, zero-extended to 32 bytes.
+    let mut bytes = [0u8; AccountAddress::LENGTH];
+    bytes[AccountAddress::LENGTH - 20..].copy_from_slice(&[0xab; 20]);
+    let narrow = AccountAddress::new(bytes);
+
+    let tag = TypeTag::Vector(Box::new(TypeTag::Struct(Box::new(StructTag {
+        address: narrow,
+        module: Identifier::new("coin").unwrap(),
+        name: Identifier::new("Coin").unwrap(),
+        type_params: vec![TypeTag::U64],
+    }))));
+
+    // Every address fits in 32 bytes.
+    assert!(tag.check_address_width(32).is_ok());
+    // The address fits in exactly 20 bytes, but not in 19.
+    assert!(tag.check_address_width(20).is_ok());
+    assert!(tag.check_address_width(19).is_err());
+
+    // Narrowing would truncate significant bytes of a full-width address.
+    let mut bytes = [0u8; AccountAddress::LENGTH];
+    bytes[0] = 1;
+    let wide = StructTag {
+        address: AccountAddress::new(bytes),
+        module: Identifier::new("coin").unwrap(),
+        name: Identifier::new("Coin").unwrap(),
+        type_params: vec![],
+    };
+    assert!(wide.check_address_width(20).is_err());
+
+    // Addresses in type parameters are checked too.
+    let nested = StructTag {
+        address: narrow,
+        module: Identifier::new("coin").unwrap(),
+        name: Identifier::new("Coin").unwrap(),
+        type_params: vec![TypeTag::Struct(Box::new(wide))],
+    };
+    assert!(nested.check_address_width(20).is_err());
+
+    assert!(TypeTag::U8.check_address_width(0).is_err());
+    assert!(TypeTag::U8.check_address_width(33).is_err());
+}
+
+#[test]
+fn test_type_tag_check_identifier_limits() {
+    let coin = StructTag {
+        address: AccountAddress::TWO,
+        module: Identifier::new("coin").unwrap(),
+        name: Identifier::new("Coin").unwrap(),
+        type_params: vec![TypeTag::U64],
+    };
+    let valid = TypeTag::Vector(Box::new(TypeTag::Struct(Box::new(coin.clone()))));
+    assert!(valid.check_identifier_limits(4).is_ok());
+    assert!(valid.check_identifier_limits(3).is_err());
+
+    // A too-long identifier nested in a type parameter is reported by name.
+    let long_name = "L".repeat(300);
+    let nested = TypeTag::Struct(Box::new(StructTag {
+        type_params: vec![TypeTag::Struct(Box::new(StructTag {
+            name: Identifier::new(long_name.clone()).unwrap(),
+            ..coin.clone()
+        }))],
+        ..coin
+    }));
+    let err = nested.check_identifier_limits(128).unwrap_err();
+    assert!(err.to_string().contains(&long_name));
+    assert!(nested.check_identifier_limits(300).is_ok());
+}
+
+#[test]
+fn test_type_tag_from_str_with_depth_limit() {
+    fn nested_vectors(depth: usize) -> String {
+        format!("{}u8{}", "vector<".repeat(depth), ">".repeat(depth))
+    }
+
+    let at_limit = TypeTag::from_str_with_depth_limit(&nested_vectors(4), 4).unwrap();
+    assert_eq!(at_limit, TypeTag::from_str(&nested_vectors(4)).unwrap());
+    assert!(TypeTag::from_str_with_depth_limit(&nested_vectors(5), 4).is_err());
+
+    // Type arguments count towards the depth too.
+    assert!(TypeTag::from_str_with_depth_limit("0x2::coin::Coin<vector<u8>>", 2).is_ok());
+    assert!(TypeTag::from_str_with_depth_limit("0x2::coin::Coin<vector<u8>>", 1).is_err());
+
+    // The default limit is finite.
+    assert!(TypeTag::from_str(&nested_vectors(MAX_TYPE_DEPTH as usize)).is_ok());
+    assert!(TypeTag::from_str(&nested_vectors(MAX_TYPE_DEPTH as usize + 1)).is_err());
+}
+
+/// The structurally recursive definition of `abstract_size_for_gas_metering`, which the iterative
+/// implementation must match exactly.
+fn recursive_abstract_size(ty: &TypeTag) -> AbstractMemorySize {
+    *TYPETAG_ENUM_ABSTRACT_SIZE
+        + match ty {
+            TypeTag::Vector(inner) => recursive_abstract_size(inner),
+            TypeTag::Struct(tag) => recursive_struct_abstract_size(tag),
+            _ => AbstractMemorySize::new(0),
+        }
+}
+
+fn recursive_struct_abstract_size(tag: &StructTag) -> AbstractMemorySize {
+    tag.address.abstract_size_for_gas_metering()
+        + tag.module.abstract_size_for_gas_metering()
+        + tag.name.abstract_size_for_gas_metering()
+        + tag
+            .type_params
+            .iter()
+            .fold(AbstractMemorySize::new(0), |accum, ty| {
+                accum + recursive_abstract_size(ty)
+            })
+}
+
+#[test]
+fn test_abstract_size_matches_recursive() {
+    let mut nested = TypeTag::U64;
+    for _ in 0..500 {
+        nested = TypeTag::Vector(Box::new(nested));
+    }
+    assert_eq!(
+        nested.abstract_size_for_gas_metering(),
+        recursive_abstract_size(&nested)
+    );
+
+    let wide = StructTag {
+        address: AccountAddress::TWO,
+        module: Identifier::new("wide").unwrap(),
+        name: Identifier::new("Wide").unwrap(),
+        type_params: (0..1000)
+            .map(|i| match i % 3 {
+                0 => TypeTag::U8,
+                1 => TypeTag::Vector(Box::new(TypeTag::Address)),
+                _ => TypeTag::Struct(Box::new(StructTag {
+                    address: AccountAddress::from_suffix(i),
+                    module: Identifier::new(format!("m{i}")).unwrap(),
+                    name: Identifier::new("T").unwrap(),
+                    type_params: vec![nested.clone()],
+                })),
+            })
+            .collect(),
+    };
+    assert_eq!(
+        wide.abstract_size_for_gas_metering(),
+        recursive_struct_abstract_size(&wide)
+    );
+    let wide = TypeTag::Struct(Box::new(wide));
+    assert_eq!(
+        wide.abstract_size_for_gas_metering(),
+        recursive_abstract_size(&wide)
+    );
+}
+
+#[test]
+fn test_abstract_size_deeply_nested() {
+    const DEPTH: u64 = 10_000;
+    let mut nested = TypeTag::U8;
+    for _ in 0..DEPTH {
+        nested = TypeTag::Vector(Box::new(nested));
+    }
+    assert_eq!(
+        nested.abstract_size_for_gas_metering(),
+        AbstractMemorySize::new(u64::from(*TYPETAG_ENUM_ABSTRACT_SIZE) * (DEPTH + 1))
+    );
+}
+
+#[test]
+fn test_type_tag_to_bcs_bytes_bounded() {
+    let coin = TypeTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+    let bytes = coin.to_bcs_bytes().unwrap();
+    assert_eq!(bytes, bcs::to_bytes(&coin).unwrap());
+    assert_eq!(coin.serialized_size_estimate().unwrap(), bytes.len());
+    assert_eq!(coin.to_bcs_bytes_bounded(bytes.len()).unwrap(), bytes);
+    assert_eq!(bcs::from_bytes::<TypeTag>(&bytes).unwrap(), coin);
+
+    let err = coin.to_bcs_bytes_bounded(bytes.len() - 1).unwrap_err();
+    assert!(err.to_string().contains("exceeding the limit"));
+}
+
+#[test]
+fn test_type_tag_visit_pre_order() {
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl TypeTagVisitor for Recorder {
+        fn visit_primitive(&mut self, ty: &TypeTag) {
+            self.0.push(ty.to_string());
+        }
+
+        fn visit_vector(&mut self, _element: &TypeTag) {
+            self.0.push("vector".to_string());
+        }
+
+        fn visit_struct(&mut self, tag: &StructTag) {
+            self.0.push(format!("{}::{}", tag.module, tag.name));
+        }
+    }
+
+    let ty = TypeTag::from_str("0x2::a::A<vector<0x3::b::B<u8, bool>>, u64, 0x4::c::C>").unwrap();
+    let mut recorder = Recorder::default();
+    ty.visit(&mut recorder);
+    assert_eq!(
+        recorder.0,
+        ["a::A", "vector", "b::B", "u8", "bool", "u64", "c::C"]
+    );
+
+    let TypeTag::Struct(tag) = &ty else {
+        unreachable!()
+    };
+    let mut struct_recorder = Recorder::default();
+    tag.visit(&mut struct_recorder);
+    assert_eq!(struct_recorder.0, recorder.0);
+
+    // Addresses are collected in the same order that structs are visited.
+    assert_eq!(
+        ty.all_addresses().into_iter().collect::<Vec<_>>(),
+        [2u16, 3, 4].map(AccountAddress::from_suffix),
+    );
+}
+
+#[test]
+fn test_struct_tag_map_addresses() {
+    let a = AccountAddress::from_hex_literal("0xa").unwrap();
+    let b = AccountAddress::from_hex_literal("0xb").unwrap();
+    let c = AccountAddress::from_hex_literal("0xc").unwrap();
+    let d = AccountAddress::from_hex_literal("0xd").unwrap();
+    let remap = |addr| match addr {
+        addr if addr == a => c,
+        addr if addr == b => d,
+        addr => addr,
+    };
+
+    let tag = StructTag::from_str("0xa::m::T<0xb::n::U>").unwrap();
+    assert_eq!(
+        tag.map_addresses(remap),
+        StructTag::from_str("0xc::m::T<0xd::n::U>").unwrap()
+    );
+
+    // Only `0xb` is remapped here, and addresses inside of vectors are remapped too.
+    let ty = TypeTag::from_str("vector<0xb::n::U<0xa::m::T, address>>").unwrap();
+    assert_eq!(
+        ty.map_addresses(|addr| if addr == b { d } else { addr }),
+        TypeTag::from_str("vector<0xd::n::U<0xa::m::T, address>>").unwrap()
+    );
+    assert_eq!(TypeTag::U64.map_addresses(remap), TypeTag::U64);
+}
+
+#[test]
+fn test_struct_tag_check_arity() {
+    let tag = StructTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+    assert_eq!(tag.type_param_arity(), 1);
+    tag.check_arity(1).unwrap();
+
+    let err = tag.check_arity(2).unwrap_err().to_string();
+    assert_eq!(err, "0x2::coin::Coin has 1 type parameter(s), expected 2");
+
+    let tag = StructTag::from_str("0x1::string::String").unwrap();
+    assert_eq!(tag.type_param_arity(), 0);
+    tag.check_arity(0).unwrap();
+    assert!(tag.check_arity(1).is_err());
+}
+
+#[test]
+fn test_struct_tag_fmt_canonical() {
+    struct Transfer<'a> {
+        tags: &'a [StructTag],
+    }
+
+    impl std::fmt::Display for Transfer<'_> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "transfer")?;
+            for tag in self.tags {
+                write!(f, " ")?;
+                tag.fmt_canonical(f, true)?;
+            }
+            Ok(())
+        }
+    }
+
+    let coin = StructTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+    let table = StructTag::from_str("0x2::table::Table<address, vector<u8>>").unwrap();
+    let tags = [coin.clone(), table.clone()];
+    assert_eq!(
+        Transfer { tags: &tags }.to_string(),
+        format!(
+            "transfer {} {}",
+            coin.to_canonical_string(true),
+            table.to_canonical_string(true)
+        ),
+    );
+}
+
+fn nested_vector(depth: u64) -> TypeTag {
+    let mut ty = TypeTag::U8;
+    for _ in 0..depth {
+        ty = TypeTag::Vector(Box::new(ty));
+    }
+    ty
+}
+
+fn struct_with_params(count: usize) -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: AccountAddress::TWO,
+        module: Identifier::new("m").unwrap(),
+        name: Identifier::new("S").unwrap(),
+        type_params: vec![TypeTag::U8; count],
+    }))
+}
+
+#[test]
+fn test_type_tag_deserialize_default_limits() {
+    let TypeTagLimits {
+        max_depth,
+        max_nodes,
+    } = TypeTagLimits::DEFAULT;
+
+    let at_limit = nested_vector(max_depth);
+    let bytes = bcs::to_bytes(&at_limit).unwrap();
+    assert_eq!(bcs::from_bytes::<TypeTag>(&bytes).unwrap(), at_limit);
+    let over_limit = nested_vector(max_depth + 1);
+    let bytes = bcs::to_bytes(&over_limit).unwrap();
+    let err = bcs::from_bytes::<TypeTag>(&bytes).unwrap_err();
+    assert!(err.to_string().contains("depth"), "{err}");
+
+    // The struct itself is one of the nodes.
+    let at_limit = struct_with_params(max_nodes as usize - 1);
+    let bytes = bcs::to_bytes(&at_limit).unwrap();
+    assert_eq!(bcs::from_bytes::<TypeTag>(&bytes).unwrap(), at_limit);
+    let over_limit = struct_with_params(max_nodes as usize);
+    let bytes = bcs::to_bytes(&over_limit).unwrap();
+    let err = bcs::from_bytes::<TypeTag>(&bytes).unwrap_err();
+    assert!(err.to_string().contains("node count"), "{err}");
+    let json = serde_json::to_string(&over_limit).unwrap();
+    assert!(serde_json::from_str::<TypeTag>(&json).is_err());
+}
+
+#[test]
+fn test_bounded_type_tag_deserialize() {
+    type Bounded = BoundedTypeTag<3, 5>;
+
+    let bytes = bcs::to_bytes(&nested_vector(3)).unwrap();
+    assert_eq!(
+        bcs::from_bytes::<Bounded>(&bytes).unwrap().0,
+        nested_vector(3)
+    );
+    let bytes = bcs::to_bytes(&nested_vector(4)).unwrap();
+    let err = bcs::from_bytes::<Bounded>(&bytes).unwrap_err();
+    assert!(err.to_string().contains("depth"), "{err}");
+
+    let json = serde_json::to_string(&struct_with_params(4)).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Bounded>(&json).unwrap().0,
+        struct_with_params(4)
+    );
+    let json = serde_json::to_string(&struct_with_params(5)).unwrap();
+    let err = serde_json::from_str::<Bounded>(&json).unwrap_err();
+    assert!(err.to_string().contains("node count"), "{err}");
+
+    // Nodes are counted across every level of nesting, and the limits are restored afterwards.
+    let nested = TypeTag::Vector(Box::new(struct_with_params(3)));
+    let bytes = bcs::to_bytes(&nested).unwrap();
+    assert!(bcs::from_bytes::<Bounded>(&bytes).is_ok());
+    let nested = TypeTag::Vector(Box::new(struct_with_params(4)));
+    let bytes = bcs::to_bytes(&nested).unwrap();
+    assert!(bcs::from_bytes::<Bounded>(&bytes).is_err());
+    assert_eq!(bcs::from_bytes::<TypeTag>(&bytes).unwrap(), nested);
+}
+
+#[test]
+fn test_bounded_type_tag_serde_matches_type_tag() {
+    type Bounded = BoundedTypeTag<16, 64>;
+
+    let tags = vec![
+        TypeTag::Bool,
+        TypeTag::U8,
+        TypeTag::U16,
+        TypeTag::U32,
+        TypeTag::U64,
+        TypeTag::U128,
+        TypeTag::U256,
+        TypeTag::Address,
+        TypeTag::Signer,
+        nested_vector(2),
+        struct_with_params(0),
+        struct_with_params(2),
+        TypeTag::Vector(Box::new(struct_with_params(1))),
+        TypeTag::from_str("0x2::coin::Coin<vector<0x2::sui::SUI>>").unwrap(),
+    ];
+
+    for tag in tags {
+        let bytes = bcs::to_bytes(&tag).unwrap();
+        let bounded = bcs::from_bytes::<Bounded>(&bytes).unwrap();
+        assert_eq!(bounded.0, tag);
+        assert_eq!(bcs::to_bytes(&bounded).unwrap(), bytes);
+        assert_eq!(bcs::from_bytes::<TypeTag>(&bytes).unwrap(), tag);
+
+        let json = serde_json::to_string(&tag).unwrap();
+        let bounded = serde_json::from_str::<Bounded>(&json).unwrap();
+        assert_eq!(bounded.0, tag);
+        assert_eq!(serde_json::to_string(&bounded).unwrap(), json);
+        assert_eq!(serde_json::from_str::<TypeTag>(&json).unwrap(), tag);
+    }
+}
+
+#[test]
+fn test_bounded_type_tag_depth_matches_parser() {
+    fn accepts<const MAX_DEPTH: u64>(tag: &TypeTag) -> bool {
+        let bytes = bcs::to_bytes(tag).unwrap();
+        bcs::from_bytes::<BoundedTypeTag<MAX_DEPTH, 64>>(&bytes).is_ok()
+    }
+
+    for s in [
+        "u8",
+        "vector<u8>",
+        "0x1::m::S",
+        "vector<0x1::m::S>",
+        "0x1::m::S<u8>",
+        "0x1::m::S<0x1::m::T>",
+        "0x1::m::S<0x1::m::T<u8>>",
+        "vector<0x1::m::S<vector<u8>>>",
+        "0x1::m::S<u8, vector<vector<u8>>>",
+    ] {
+        let tag = TypeTag::from_str(s).unwrap();
+        for (max_depth, accepted) in [
+            (0, accepts::<0>(&tag)),
+            (1, accepts::<1>(&tag)),
+            (2, accepts::<2>(&tag)),
+            (3, accepts::<3>(&tag)),
+        ] {
+            assert_eq!(
+                accepted,
+                TypeTag::from_str_with_depth_limit(s, max_depth).is_ok(),
+                "{s} with a depth limit of {max_depth}",
+            );
+            assert_eq!(accepted, tag.generic_depth() <= max_depth, "{s}");
+        }
+    }
+}
+
+#[test]
+fn test_type_tag_predicates() {
+    for ty in [
+        TypeTag::Bool,
+        TypeTag::U8,
+        TypeTag::U16,
+        TypeTag::U32,
+        TypeTag::U64,
+        TypeTag::U128,
+        TypeTag::U256,
+        TypeTag::Address,
+    ] {
+        assert!(ty.is_primitive(), "{ty}");
+        assert!(
+            !ty.is_signer() && !ty.is_vector() && !ty.is_struct(),
+            "{ty}"
+        );
+    }
+
+    assert!(!TypeTag::Signer.is_primitive());
+    assert!(TypeTag::Signer.is_signer());
+
+    let vector = TypeTag::from_str("vector<u8>").unwrap();
+    assert!(vector.is_vector());
+    assert!(!vector.is_primitive() && !vector.is_struct());
+
+    let coin = TypeTag::from_str("0x2::coin::Coin<u64>").unwrap();
+    assert!(coin.is_struct());
+    assert!(!coin.is_primitive() && !coin.is_vector());
+}
+
+/// The string forms of each type tag in the matrix below.
+struct TypeTagForms {
+    display: &'static str,
+    canonical: &'static str,
+    canonical_no_prefix: &'static str,
+    /// Whether `FromStr` parses `canonical_no_prefix` back to the same tag.
+    no_prefix_round_trips: bool,
+}
+
+/// Pins the output of every string form of a representative set of type tags, and which forms
+/// `FromStr` and `from_runtime_string` accept:
+///
+/// | Form                         | Addresses                   | Separator | `FromStr` | runtime |
+/// |------------------------------|-----------------------------|-----------|-----------|---------|
+/// | `Display`                    | `0x`, leading zeros trimmed | `", "`    | yes       | yes     |
+/// | `to_canonical_string(true)`  | `0x`, 64 hex digits         | `","`     | yes       | yes     |
+/// | `to_canonical_string(false)` | 64 hex digits, no `0x`      | `","`     | no        | yes     |
+///
+/// Both parsers ignore whitespace between tokens, so the separators are interchangeable. `FromStr`
+/// reads an address without a prefix as a decimal number, or as a named address if it starts with
+/// a letter, so the unprefixed canonical form does not round-trip through it (unless each address
+/// happens to have the same value in decimal). `from_runtime_string` reads every address as hex.
+#[test]
+fn test_type_tag_string_forms() {
+    let cases = [
+        (
+            "u8",
+            TypeTagForms {
+                display: "u8",
+                canonical: "u8",
+                canonical_no_prefix: "u8",
+                no_prefix_round_trips: true,
+            },
+        ),
+        (
+            "vector<vector<address>>",
+            TypeTagForms {
+                display: "vector<vector<address>>",
+                canonical: "vector<vector<address>>",
+                canonical_no_prefix: "vector<vector<address>>",
+                no_prefix_round_trips: true,
+            },
+        ),
+        (
+            // Read as decimal without its prefix, but 2 is the same in both bases.
+            "0x2::coin::Coin<0x2::sui::SUI>",
+            TypeTagForms {
+                display: "0x2::coin::Coin<0x2::sui::SUI>",
+                canonical: "0x0000000000000000000000000000000000000000000000000000000000000002::coin::Coin<0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI>",
+                canonical_no_prefix: "0000000000000000000000000000000000000000000000000000000000000002::coin::Coin<0000000000000000000000000000000000000000000000000000000000000002::sui::SUI>",
+                no_prefix_round_trips: true,
+            },
+        ),
+        (
+            // An address made only of decimal digits, which is read as a decimal number when
+            // written without its prefix.
+            "0x10::m::T<u64, vector<bool>>",
+            TypeTagForms {
+                display: "0x10::m::T<u64, vector<bool>>",
+                canonical: "0x0000000000000000000000000000000000000000000000000000000000000010::m::T<u64,vector<bool>>",
+                canonical_no_prefix: "0000000000000000000000000000000000000000000000000000000000000010::m::T<u64,vector<bool>>",
+                no_prefix_round_trips: false,
+            },
+        ),
+        (
+            "vector<0xabc::m::T<0xdef::n::U, signer>>",
+            TypeTagForms {
+                display: "vector<0xabc::m::T<0xdef::n::U, signer>>",
+                canonical: "vector<0x0000000000000000000000000000000000000000000000000000000000000abc::m::T<0x0000000000000000000000000000000000000000000000000000000000000def::n::U,signer>>",
+                canonical_no_prefix: "vector<0000000000000000000000000000000000000000000000000000000000000abc::m::T<0000000000000000000000000000000000000000000000000000000000000def::n::U,signer>>",
+                no_prefix_round_trips: false,
+            },
+        ),
+        (
+            // Read as a named address without its prefix.
+            "0xf00000000000000000000000000000000000000000000000000000000000000d::m::T",
+            TypeTagForms {
+                display: "0xf00000000000000000000000000000000000000000000000000000000000000d::m::T",
+                canonical: "0xf00000000000000000000000000000000000000000000000000000000000000d::m::T",
+                canonical_no_prefix: "f00000000000000000000000000000000000000000000000000000000000000d::m::T",
+                no_prefix_round_trips: false,
+            },
+        ),
+    ];
+
+    for (input, forms) in cases {
+        let tag = TypeTag::from_str(input).unwrap();
+        assert_eq!(tag.to_string(), forms.display);
+        assert_eq!(tag.to_canonical_string(true), forms.canonical);
+        assert_eq!(tag.to_canonical_string(false), forms.canonical_no_prefix);
+
+        for form in [forms.display, forms.canonical] {
+            assert_eq!(TypeTag::from_str(form).unwrap(), tag, "{form}");
+        }
+        let no_prefix = TypeTag::from_str(forms.canonical_no_prefix).ok();
+        assert_eq!(
+            no_prefix.as_ref() == Some(&tag),
+            forms.no_prefix_round_trips,
+            "{}",
+            forms.canonical_no_prefix,
+        );
+        for form in [forms.display, forms.canonical, forms.canonical_no_prefix] {
+            assert_eq!(TypeTag::from_runtime_string(form).unwrap(), tag, "{form}");
+        }
+
+        // Separators are interchangeable.
+        let spaced = forms.canonical.replace(',', ", ");
+        assert_eq!(TypeTag::from_str(&spaced).unwrap(), tag);
+        let unspaced = forms.display.replace(", ", ",");
+        assert_eq!(TypeTag::from_str(&unspaced).unwrap(), tag);
+
+        if let TypeTag::Struct(s) = &tag {
+            assert_eq!(s.to_string(), forms.display);
+            assert_eq!(s.to_canonical_string(true), forms.canonical);
+            for form in [forms.display, forms.canonical] {
+                assert_eq!(StructTag::from_str(form).unwrap(), **s, "{form}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_type_tag_unprefixed_address_widths() {
+    // Numbers are decimal whatever their width, and full-width hex that starts with a letter is a
+    // named address. Only `from_runtime_string` reads them as hex.
+    assert_eq!(
+        TypeTag::from_str("16::m::T").unwrap(),
+        TypeTag::from_str("0x10::m::T").unwrap(),
+    );
+    // One digit longer than a full-width address.
+    assert_eq!(
+        TypeTag::from_str(
+            "00000000000000000000000000000000000000000000000000000000000000010::m::T"
+        )
+        .unwrap(),
+        TypeTag::from_str("0xa::m::T").unwrap(),
+    );
+    // A full-width decimal address.
+    assert_eq!(
+        TypeTag::from_str("1234567890123456789012345678901234567890123456789012345678901234::m::S")
+            .unwrap(),
+        TypeTag::from_str("0x30046030f26f462d7ac21a27eb9d53fff233c7acd12d87e96aff2::m::S").unwrap(),
+    );
+    let hex = "f00000000000000000000000000000000000000000000000000000000000000d::m::T";
+    assert!(TypeTag::from_str(hex).is_err());
+    assert_eq!(
+        TypeTag::from_runtime_string(hex).unwrap(),
+        TypeTag::from_str(&format!("0x{hex}")).unwrap(),
+    );
+}
+
+#[test]
+fn test_struct_tag_normalize_too_deep() {
+    let mut ty = TypeTag::U8;
+    for _ in 0..=MAX_TYPE_DEPTH {
+        ty = TypeTag::Vector(Box::new(ty));
+    }
+    let tag = StructTag {
+        address: AccountAddress::ONE,
+        module: Identifier::new("m").unwrap(),
+        name: Identifier::new("T").unwrap(),
+        type_params: vec![ty],
+    };
+    // Too deep to be parsed back from its canonical string.
+    assert!(tag.normalize().is_err());
+}
+
+#[test]
+fn test_module_id_struct_tag() {
+    let module_id = ModuleId::new(AccountAddress::TWO, Identifier::new("coin").unwrap());
+    let sui = StructTag::from_str("0x2::sui::SUI").unwrap();
+    let tag = module_id.struct_tag(Identifier::new("Coin").unwrap(), vec![sui.into()]);
+
+    assert_eq!(
+        tag.to_canonical_string(/* with_prefix */ true),
+        "0x0000000000000000000000000000000000000000000000000000000000000002::coin::Coin<\
+         0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI>",
+    );
+    assert_eq!(tag.module_id(), module_id);
+}
+
+#[test]
+fn test_struct_tag_stable_bytes_layout() {
+    let tag = StructTag::from_str(
+        "0x2::m::T<0x1::n::U<bool, u8, u16, u32, u64, u128, u256, address, signer>, vector<u8>>",
+    )
+    .unwrap();
+
+    // This layout must never change: bytes written by `to_stable_bytes` may be persisted.
+    let expected = [
+        // 0x2::m::T, with 2 type parameters
+        "0000000000000000000000000000000000000000000000000000000000000002",
+        "016d",
+        "0154",
+        "02",
+        // struct 0x1::n::U, with 9 type parameters
+        "07",
+        "0000000000000000000000000000000000000000000000000000000000000001",
+        "016e",
+        "0155",
+        "09",
+        // bool, u8, u16, u32, u64, u128, u256, address, signer
+        "000108090203",
+        "0a0405",
+        // vector<u8>
+        "0601",
+    ]
+    .concat();
+
+    let bytes = tag.to_stable_bytes();
+    assert_eq!(hex::encode(&bytes), expected);
+    assert_eq!(StructTag::from_stable_bytes(&bytes).unwrap(), tag);
+}
+
+#[test]
+fn test_struct_tag_from_stable_bytes_errors() {
+    let tag = StructTag::from_str("0x2::coin::Coin<vector<u8>>").unwrap();
+    let bytes = tag.to_stable_bytes();
+
+    // Truncated
+    assert!(StructTag::from_stable_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    // Trailing bytes
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(StructTag::from_stable_bytes(&trailing).is_err());
+
+    // Unknown type tag
+    let mut unknown = bytes.clone();
+    *unknown.last_mut().unwrap() = 11;
+    assert!(StructTag::from_stable_bytes(&unknown).is_err());
+
+    // Too deep
+    let mut ty = TypeTag::U8;
+    for _ in 0..=MAX_TYPE_DEPTH {
+        ty = TypeTag::Vector(Box::new(ty));
+    }
+    let deep = StructTag {
+        type_params: vec![ty],
+        ..tag
+    };
+    assert!(StructTag::from_stable_bytes(&deep.to_stable_bytes()).is_err());
+}
+
+#[test]
+fn test_struct_tag_stable_dedup_key() {
+    // This key must never change without bumping `STABLE_DEDUP_KEY_VERSION`: it may be persisted.
+    let coin = StructTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+    assert_eq!(
+        coin.stable_dedup_key(),
+        "0122c7ec1c68267899a6ec22b4f4565bf008ea7200d0ffbd3cc6828ec9830df682",
+    );
+    assert!(
+        coin.stable_dedup_key()
+            .starts_with(&hex::encode([STABLE_DEDUP_KEY_VERSION]))
+    );
+
+    let other = StructTag::from_str("0x2::coin::Coin<0x2::sui::SUIX>").unwrap();
+    assert_ne!(coin.stable_dedup_key(), other.stable_dedup_key());
+}
+
+#[test]
+fn test_to_display_address_format() {
+    let tag = StructTag::from_str("0x2::coin::Coin<vector<0xa0::m::S<u64, 0x0::n::T>>>").unwrap();
+    assert_eq!(
+        tag.to_display(/* with_prefix */ true, AddressFormat::Short)
+            .to_string(),
+        "0x2::coin::Coin<vector<0xa0::m::S<u64,0x0::n::T>>>",
+    );
+    assert_eq!(
+        tag.to_display(/* with_prefix */ false, AddressFormat::Short)
+            .to_string(),
+        "2::coin::Coin<vector<a0::m::S<u64,0::n::T>>>",
+    );
+
+    // The full format is the canonical string.
+    for with_prefix in [true, false] {
+        assert_eq!(
+            tag.to_display(with_prefix, AddressFormat::Full).to_string(),
+            tag.to_canonical_string(with_prefix),
+        );
+        let ty = TypeTag::Vector(Box::new(TypeTag::Struct(Box::new(tag.clone()))));
+        assert_eq!(
+            ty.to_display(with_prefix, AddressFormat::Full).to_string(),
+            ty.to_canonical_string(with_prefix),
+        );
+    }
+
+    let ty = TypeTag::from_str("vector<0x00002::sui::SUI>").unwrap();
+    assert_eq!(
+        ty.to_display(/* with_prefix */ true, AddressFormat::Short)
+            .to_string(),
+        "vector<0x2::sui::SUI>",
+    );
+}
+
+#[test]
+fn test_struct_tags() {
+    let ty = TypeTag::from_str(
+        "vector<0x2::m::A<0x2::m::B<u64, vector<0x2::m::C>>, 0x2::m::B<u8, vector<0x2::m::C>>>>",
+    )
+    .unwrap();
+    let names = |tags: Vec<&StructTag>| {
+        tags.into_iter()
+            .map(|tag| tag.to_canonical_string(/* with_prefix */ false))
+            .collect::<Vec<_>>()
+    };
+
+    let c = "0000000000000000000000000000000000000000000000000000000000000002::m::C";
+    let b = |prim: &str| {
+        format!(
+            "0000000000000000000000000000000000000000000000000000000000000002::m::B<{prim},vector<{c}>>"
+        )
+    };
+    let a = format!(
+        "0000000000000000000000000000000000000000000000000000000000000002::m::A<{},{}>",
+        b("u64"),
+        b("u8"),
+    );
+    let expect = vec![a, b("u64"), c.to_string(), b("u8"), c.to_string()];
+    assert_eq!(names(ty.struct_tags()), expect);
+
+    let TypeTag::Vector(inner) = &ty else {
+        panic!("Expected a vector");
+    };
+    let TypeTag::Struct(tag) = inner.as_ref() else {
+        panic!("Expected a struct");
+    };
+    assert_eq!(names(tag.leaf_structs()), expect);
+    assert_eq!(tag.leaf_structs(), inner.struct_tags());
+
+    assert!(TypeTag::U64.struct_tags().is_empty());
+    assert!(
+        TypeTag::from_str("vector<vector<u8>>")
+            .unwrap()
+            .struct_tags()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_module_id_as_parts() {
+    let module_id = ModuleId::new(AccountAddress::TWO, Identifier::new("coin").unwrap());
+    let (address, name) = module_id.as_parts();
+    assert_eq!(*address, AccountAddress::TWO);
+    assert_eq!(name.as_str(), "coin");
+
+    let owned: (AccountAddress, Identifier) = module_id.clone().into();
+    assert_eq!((&owned.0, owned.1.as_ident_str()), module_id.as_parts());
+}
+
+#[test]
+fn test_type_tag_find_struct() {
+    let ty = TypeTag::from_str("vector<0x2::coin::Coin<0x2::sui::SUI>>").unwrap();
+    let coin = ty.find_struct("coin", "Coin").unwrap();
+    assert_eq!(coin.to_string(), "0x2::coin::Coin<0x2::sui::SUI>");
+    assert_eq!(
+        ty.find_struct("sui", "SUI").unwrap().to_string(),
+        "0x2::sui::SUI"
+    );
+    assert_eq!(ty.find_struct("coin", "TreasuryCap"), None);
+    assert_eq!(ty.find_struct("balance", "Coin"), None);
+    assert_eq!(TypeTag::U64.find_struct("coin", "Coin"), None);
+
+    // Matches are returned in pre-order, from any address.
+    let ty = TypeTag::from_str(
+        "0x1::m::T<0x3::coin::Coin<0x2::coin::Coin<u8>>, vector<0x4::coin::Coin<u16>>>",
+    )
+    .unwrap();
+    let coins: Vec<_> = ty
+        .find_all_structs("coin", "Coin")
+        .into_iter()
+        .map(|tag| tag.to_string())
+        .collect();
+    assert_eq!(
+        coins,
+        [
+            "0x3::coin::Coin<0x2::coin::Coin<u8>>",
+            "0x2::coin::Coin<u8>",
+            "0x4::coin::Coin<u16>",
+        ]
+    );
+    assert_eq!(
+        ty.find_struct("coin", "Coin").unwrap().to_string(),
+        coins[0]
+    );
+    assert!(ty.find_all_structs("coin", "TreasuryCap").is_empty());
+}
+
+#[test]
+fn test_cached_struct_tag_canonical_string() {
+    let tag = StructTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+    let cached = CachedStructTag::new(tag.clone());
+
+    assert_eq!(cached.canonical_string(true), tag.to_canonical_string(true));
+    assert_eq!(
+        cached.canonical_string(false),
+        tag.to_canonical_string(false)
+    );
+
+    // Later calls return the string computed by the first one.
+    let first = cached.canonical_string(true);
+    assert!(std::ptr::eq(first, cached.canonical_string(true)));
+
+    // Derefs to the inner tag.
+    assert_eq!(cached.name.as_str(), "Coin");
+    assert_eq!(cached.module_id(), tag.module_id());
+    assert_eq!(cached.to_string(), tag.to_string());
+    assert_eq!(cached.into_inner(), tag);
+}
+
+#[test]
+// The cache is interior mutable, but it does not take part in hashing or equality.
+#[allow(clippy::mutable_key_type)]
+fn test_cached_struct_tag_ignores_cache() {
+    fn hash_of(value: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let tag = StructTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+    let cold = CachedStructTag::new(tag.clone());
+    let warm = CachedStructTag::new(tag.clone());
+    warm.canonical_string(true);
+    warm.canonical_string(false);
+
+    assert_eq!(cold, warm);
+    assert_eq!(hash_of(&cold), hash_of(&warm));
+    assert_eq!(hash_of(&warm), hash_of(&tag));
+
+    // Can be looked up by the tag it wraps.
+    let set: HashSet<CachedStructTag> = [warm.clone()].into_iter().collect();
+    assert!(set.contains(&tag));
+
+    let other = CachedStructTag::new(StructTag::from_str("0x2::sui::SUI").unwrap());
+    assert_ne!(warm, other);
+    assert_eq!(warm.cmp(&other), tag.cmp(&other));
+
+    // Serializes like the tag it wraps, and deserializes with an empty cache.
+    let bytes = bcs::to_bytes(&warm).unwrap();
+    assert_eq!(bytes, bcs::to_bytes(&tag).unwrap());
+    let decoded: CachedStructTag = bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, warm);
+    assert_eq!(
+        serde_json::to_string(&warm).unwrap(),
+        serde_json::to_string(&tag).unwrap()
+    );
+}
+
+#[test]
+fn test_type_tag_canonical_json() {
+    let tag = TypeTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+    let value = CanonicalJson { tag: tag.clone() };
+
+    let json = serde_json::to_value(&value).unwrap();
+    assert_eq!(
+        json["tag"],
+        "0x0000000000000000000000000000000000000000000000000000000000000002::coin::Coin<\
+         0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI>"
+    );
+
+    // Short addresses, unprefixed addresses, and the derived representation are all accepted.
+    for lenient in [
+        serde_json::json!("0x2::coin::Coin<0x2::sui::SUI>"),
+        serde_json::json!(tag.to_canonical_string(/* with_prefix */ false)),
+        serde_json::to_value(&tag).unwrap(),
+    ] {
+        let parsed: CanonicalJson = serde_json::from_value(serde_json::json!({ "tag": lenient }))
+            .unwrap_or_else(|e| panic!("{lenient}: {e}"));
+        assert_eq!(parsed, value);
+    }
+
+    let primitive: CanonicalJson = serde_json::from_str(r#"{"tag": "vector<u8>"}"#).unwrap();
+    assert_eq!(primitive.tag, TypeTag::Vector(Box::new(TypeTag::U8)));
+
+    assert!(serde_json::from_str::<CanonicalJson>(r#"{"tag": "0x2::coin"}"#).is_err());
+    assert!(serde_json::from_str::<CanonicalJson>(r#"{"tag": 42}"#).is_err());
+
+    // Formats that are not human-readable are unaffected.
+    let bytes = bcs::to_bytes(&value).unwrap();
+    assert_eq!(bytes, bcs::to_bytes(&tag).unwrap());
+    assert_eq!(bcs::from_bytes::<CanonicalJson>(&bytes).unwrap(), value);
+}
+
+#[test]
+fn test_from_runtime_string() {
+    let expected = TypeTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap();
+    let full = expected.to_canonical_string(/* with_prefix */ true);
+    let unprefixed = expected.to_canonical_string(/* with_prefix */ false);
+
+    for input in [
+        "0x2::coin::Coin<0x2::sui::SUI>",
+        "2::coin::Coin<2::sui::SUI>",
+        "0x2::coin::Coin<2::sui::SUI>",
+        "0x2 :: coin :: Coin < 0x2 :: sui :: SUI >",
+        " 2::coin::Coin<\n\t0x2::sui::SUI> ",
+        &expected.to_string(),
+        &full,
+        &unprefixed,
+    ] {
+        let tag = TypeTag::from_runtime_string(input).unwrap_or_else(|e| panic!("{input}: {e}"));
+        assert_eq!(tag, expected, "{input}");
+    }
+
+    // Addresses are always hex, even when they look like decimals.
+    let pair = TypeTag::from_runtime_string("0x1::pair::Pair<u8, 12::a::B, vector<abc::c::D>>");
+    assert_eq!(
+        pair.unwrap(),
+        TypeTag::from_str("0x1::pair::Pair<u8,0x12::a::B,vector<0xabc::c::D>>").unwrap()
+    );
+
+    // Module and type names that look like hex are left alone.
+    assert_eq!(
+        TypeTag::from_runtime_string("a::b::c").unwrap(),
+        TypeTag::from_str("0xa::b::c").unwrap()
+    );
+
+    assert_eq!(
+        TypeTag::from_runtime_string(" vector< u8 > ").unwrap(),
+        TypeTag::Vector(Box::new(TypeTag::U8))
+    );
+
+    assert!(TypeTag::from_runtime_string("u 8").is_err());
+    assert!(TypeTag::from_runtime_string("0x2::coin").is_err());
+    assert!(TypeTag::from_runtime_string("xyz::m::S").is_err());
+}
+
+#[test]
+fn test_struct_tag_builder() {
+    let sui = StructTag::builder()
+        .address(AccountAddress::TWO)
+        .module("sui")
+        .name("SUI")
+        .build()
+        .unwrap();
+    let coin = StructTag::builder()
+        .address(AccountAddress::ONE)
+        .module("coin")
+        .name("Coin")
+        .type_param(TypeTag::Struct(Box::new(sui)))
+        .build()
+        .unwrap();
+    assert_eq!(
+        coin,
+        StructTag::from_str("0x1::coin::Coin<0x2::sui::SUI>").unwrap()
+    );
+
+    let pair = StructTag::builder()
+        .address(AccountAddress::ONE)
+        .module("pair")
+        .name("Pair")
+        .type_params([TypeTag::U8, TypeTag::Bool])
+        .type_param(TypeTag::Address)
+        .build()
+        .unwrap();
+    assert_eq!(
+        pair,
+        StructTag::from_str("0x1::pair::Pair<u8, bool, address>").unwrap()
+    );
+
+    let complete = StructTag::builder()
+        .address(AccountAddress::ONE)
+        .module("m")
+        .name("S");
+    assert!(complete.clone().build().is_ok());
+
+    let err = complete.clone().module("not valid").build().unwrap_err();
+    assert!(err.to_string().contains("Invalid identifier"), "{err}");
+    let err = complete.name("0S").build().unwrap_err();
+    assert!(err.to_string().contains("Invalid identifier"), "{err}");
+
+    for (builder, missing) in [
+        (StructTag::builder().module("m").name("S"), "address"),
+        (
+            StructTag::builder().address(AccountAddress::ONE).name("S"),
+            "module",
+        ),
+        (
+            StructTag::builder()
+                .address(AccountAddress::ONE)
+                .module("m"),
+            "name",
+        ),
+    ] {
+        let err = builder.build().unwrap_err();
+        assert!(err.to_string().contains(missing), "{err}");
+    }
+}
+
+#[test]
+fn test_all_addresses_order() {
+    let [a, b, c] = ["0xa", "0xb", "0xc"].map(|a| AccountAddress::from_hex_literal(a).unwrap());
+
+    let tag = StructTag::from_str("0xa::m::T<0xb::n::U<0xc::p::V>, 0xb::n::W>").unwrap();
+    assert_eq!(
+        tag.all_addresses().into_iter().collect::<Vec<_>>(),
+        [a, b, c]
+    );
+
+    let ty = TypeTag::Struct(Box::new(tag));
+    assert_eq!(
+        ty.all_addresses().into_iter().collect::<Vec<_>>(),
+        [a, b, c]
+    );
+
+    // The first occurrence of an address decides its position, however deep it is.
+    let ty = TypeTag::from_str("vector<0xc::p::V<0xb::n::U, 0xc::p::W<0xa::m::T>>>").unwrap();
+    assert_eq!(
+        ty.all_addresses().into_iter().collect::<Vec<_>>(),
+        [c, b, a]
+    );
+
+    assert!(
+        TypeTag::from_str("vector<u8>")
+            .unwrap()
+            .all_addresses()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_module_id_from_short_str() {
+    let id = ModuleId::new(AccountAddress::TWO, Identifier::new("coin").unwrap());
+    assert_eq!(id.short_str_lossless(), "0x2::coin");
+    assert_eq!(ModuleId::from_str("0x2::coin").unwrap(), id);
+    assert_eq!(ModuleId::from_str(&id.short_str_lossless()).unwrap(), id);
+    assert_eq!(
+        ModuleId::from_str(&id.to_canonical_string(/* with_prefix */ true)).unwrap(),
+        id
+    );
+    assert_eq!(ModuleId::from_str(&id.to_string()).unwrap(), id);
+
+    let zero = ModuleId::new(AccountAddress::ZERO, Identifier::new("m").unwrap());
+    assert_eq!(zero.short_str_lossless(), "0x0::m");
+    assert_eq!(
+        ModuleId::from_str(&zero.short_str_lossless()).unwrap(),
+        zero
+    );
+}
+
+#[test]
+fn test_generic_depth_and_node_count() {
+    let metrics = |s: &str| {
+        let tag = TypeTag::from_str(s).unwrap();
+        (tag.generic_depth(), tag.node_count())
+    };
+
+    assert_eq!(metrics("u8"), (0, 1));
+    assert_eq!(metrics("vector<u8>"), (1, 2));
+    assert_eq!(metrics("vector<vector<address>>"), (2, 3));
+    assert_eq!(metrics("0x1::m::S"), (0, 1));
+    assert_eq!(metrics("0x1::m::S<u8, bool>"), (1, 3));
+    assert_eq!(metrics("vector<0x1::m::S<u8>>"), (2, 3));
+    assert_eq!(
+        metrics("0x1::m::S<vector<u8>, 0x2::n::T<0x3::o::U<u64>>, bool>"),
+        (3, 7)
+    );
+
+    // The metrics match the limits that parsing and deserialization enforce.
+    let s = "0x1::m::S<vector<vector<u8>>, u8>";
+    let tag = TypeTag::from_str(s).unwrap();
+    assert_eq!(tag.generic_depth(), 3);
+    assert!(TypeTag::from_str_with_depth_limit(s, 3).is_ok());
+    assert!(TypeTag::from_str_with_depth_limit(s, 2).is_err());
+
+    let bytes = bcs::to_bytes(&tag).unwrap();
+    let nodes = tag.node_count();
+    assert_eq!(nodes, 5);
+    assert!(bcs::from_bytes::<BoundedTypeTag<3, 5>>(&bytes).is_ok());
+    assert!(bcs::from_bytes::<BoundedTypeTag<3, 4>>(&bytes).is_err());
+    assert!(bcs::from_bytes::<BoundedTypeTag<2, 5>>(&bytes).is_err());
+
+    // Deeply nested types are measured without overflowing the stack.
+    let mut deep = TypeTag::U8;
+    for _ in 0..100_000 {
+        deep = TypeTag::Vector(Box::new(deep));
+    }
+    assert_eq!(deep.generic_depth(), 100_000);
+    assert_eq!(deep.node_count(), 100_001);
+    std::mem::forget(deep);
+}
+
+#[test]
+fn test_coin_type_tags() {
+    let two = "0x0000000000000000000000000000000000000000000000000000000000000002";
+    let framework = AccountAddress::TWO;
+
+    assert_eq!(
+        TypeTag::sui(framework).to_canonical_string(true),
+        format!("{two}::sui::SUI")
+    );
+    assert_eq!(
+        TypeTag::sui_coin(framework).to_canonical_string(true),
+        format!("{two}::coin::Coin<{two}::sui::SUI>")
+    );
+    assert_eq!(
+        TypeTag::coin_of(framework, TypeTag::from_str("0xa::usdc::USDC").unwrap())
+            .to_canonical_string(true),
+        format!(
+            "{two}::coin::Coin<0x000000000000000000000000000000000000000000000000000000000000000a::usdc::USDC>"
+        )
+    );
+    assert_eq!(
+        TypeTag::sui_coin(framework),
+        TypeTag::from_str("0x2::coin::Coin<0x2::sui::SUI>").unwrap()
+    );
+
+    // The framework address is not assumed.
+    let other = AccountAddress::from_hex_literal("0x42").unwrap();
+    assert_eq!(
+        TypeTag::sui_coin(other)
+            .to_display(true, AddressFormat::Short)
+            .to_string(),
+        "0x42::coin::Coin<0x42::sui::SUI>"
+    );
+}
+
+#[test]
+fn test_is_framework() {
+    let custom_a = AccountAddress::from_hex_literal("0xa").unwrap();
+    let custom_b = AccountAddress::from_hex_literal("0xb").unwrap();
+    let framework = [CORE_CODE_ADDRESS, AccountAddress::TWO];
+
+    let module = |addr| ModuleId::new(addr, Identifier::new("m").unwrap());
+    assert!(module(CORE_CODE_ADDRESS).is_framework(&framework));
+    assert!(module(AccountAddress::TWO).is_framework(&framework));
+    assert!(!module(custom_a).is_framework(&framework));
+    assert!(!module(CORE_CODE_ADDRESS).is_framework(&[]));
+
+    // Any set of addresses can be treated as the framework.
+    assert!(module(custom_a).is_framework(&[custom_a, custom_b]));
+    assert!(!module(CORE_CODE_ADDRESS).is_framework(&[custom_a, custom_b]));
+
+    let string = StructTag::from_str("0x1::string::String").unwrap();
+    assert!(string.is_framework_type(&framework));
+    assert!(!string.is_framework_type(&[custom_b]));
+
+    // Only the struct's own address counts, not those of its type parameters.
+    let coin = StructTag::from_str("0xb::coin::Coin<0x1::string::String>").unwrap();
+    assert!(!coin.is_framework_type(&framework));
+    assert!(coin.is_framework_type(&[custom_b]));
+    let wrapper = StructTag::from_str("0x2::wrapper::Wrapper<0xa::t::T>").unwrap();
+    assert!(wrapper.is_framework_type(&framework));
+}
+
+#[test]
+fn test_qualified_name() {
+    let one = "0000000000000000000000000000000000000000000000000000000000000001";
+    let two = "0000000000000000000000000000000000000000000000000000000000000002";
+
+    let string = StructTag::from_str("0x1::string::String").unwrap();
+    assert_eq!(
+        string.qualified_name(/* with_prefix */ true),
+        format!("0x{one}::string::String")
+    );
+    assert_eq!(
+        string.qualified_name(/* with_prefix */ false),
+        format!("{one}::string::String")
+    );
+    // Without type parameters, it is the canonical string.
+    assert_eq!(
+        string.qualified_name(true),
+        string.to_canonical_string(true)
+    );
+    assert_eq!(
+        string.qualified_name(false),
+        string.to_canonical_string(false)
+    );
+
+    let coin = StructTag::from_str("0x2::coin::Coin<0x2::table::Table<u64, 0x1::string::String>>")
+        .unwrap();
+    assert_eq!(
+        coin.qualified_name(/* with_prefix */ true),
+        format!("0x{two}::coin::Coin")
+    );
+    assert_eq!(
+        coin.qualified_name(/* with_prefix */ false),
+        format!("{two}::coin::Coin")
+    );
+    assert_eq!(
+        coin.to_canonical_string(/* with_prefix */ true),
+        format!("0x{two}::coin::Coin<0x{two}::table::Table<u64,0x{one}::string::String>>")
+    );
+}
+
+#[test]
+fn test_struct_type_tag_round_trip() {
+    let tag = StructTag::from_str("0x2::coin::Coin<vector<0x2::sui::SUI>>").unwrap();
+    let ty = tag.clone().into_type_tag();
+    assert_eq!(ty, TypeTag::from(tag.clone()));
+    assert_eq!(ty.as_struct(), Some(&tag));
+    assert_eq!(
+        ty.as_struct().cloned().map(StructTag::into_type_tag),
+        Some(ty)
+    );
+
+    for ty in [
+        TypeTag::U8,
+        TypeTag::Signer,
+        TypeTag::Vector(Box::new(tag.clone().into_type_tag())),
+    ] {
+        assert_eq!(ty.as_struct(), None, "{ty}");
+    }
+}
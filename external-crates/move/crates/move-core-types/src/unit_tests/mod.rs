@@ -6,5 +6,7 @@ mod extractor_test;
 mod identifier_test;
 mod language_storage_test;
 mod parsing_test;
+#[cfg(feature = "struct-tag-matcher")]
+mod struct_tag_matcher_test;
 mod value_test;
 mod visitor_test;
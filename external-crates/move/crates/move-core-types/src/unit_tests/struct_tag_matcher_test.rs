@@ -0,0 +1,67 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag},
+    struct_tag_matcher::StructTagMatcher,
+};
+
+fn tag(address: u16, module: &str, name: &str, type_params: Vec<TypeTag>) -> StructTag {
+    StructTag {
+        address: AccountAddress::from_suffix(address),
+        module: Identifier::new(module).unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params,
+    }
+}
+
+#[test]
+fn test_matches_any() {
+    let coin = |t: TypeTag| tag(2, "coin", "Coin", vec![t]);
+    let sui = TypeTag::Struct(Box::new(tag(2, "sui", "SUI", vec![])));
+
+    let targets = vec![
+        coin(sui.clone()),
+        tag(1, "string", "String", vec![]),
+        coin(TypeTag::U64),
+    ];
+    let matcher = StructTagMatcher::new(targets.clone());
+    assert_eq!(matcher.targets(), targets.as_slice());
+
+    for (i, target) in targets.iter().enumerate() {
+        assert_eq!(matcher.matches_any(target), Some(i));
+    }
+
+    // Tags that differ only in a (nested) type parameter, the address, or the name do not match.
+    assert_eq!(matcher.matches_any(&coin(TypeTag::U8)), None);
+    assert_eq!(
+        matcher.matches_any(&tag(3, "string", "String", vec![])),
+        None
+    );
+    assert_eq!(
+        matcher.matches_any(&tag(1, "string", "Strings", vec![])),
+        None
+    );
+    assert_eq!(
+        matcher.matches_any(&coin(TypeTag::Vector(Box::new(sui)))),
+        None
+    );
+}
+
+#[test]
+fn test_matches_first_duplicate() {
+    let string = tag(1, "string", "String", vec![]);
+    let matcher = StructTagMatcher::new(vec![string.clone(), string.clone()]);
+    assert_eq!(matcher.matches_any(&string), Some(0));
+}
+
+#[test]
+fn test_empty_matcher() {
+    let matcher = StructTagMatcher::new(vec![]);
+    assert_eq!(
+        matcher.matches_any(&tag(1, "string", "String", vec![])),
+        None
+    );
+}